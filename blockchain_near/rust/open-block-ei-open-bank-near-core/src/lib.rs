@@ -20,6 +20,7 @@
 /// <br/> - 'request debit' - this feature provides the ability for third parties to draw down fixed funds at set intervals for a given period to a named 'account id' 
 /// <br/> - 'deposit' - this feature provides the ability for internal payments in a controlled way 
 /// <br/> - 'withdraw' - this feature provides the ability for controlled withdrawal to the 'nominee account id' associated with this bank 
+/// <br/> - 'scheduled payout' - this feature provides the ability for the bank to push fixed funds once or at a set frequency (standing orders) to a named 'account id' 
 /// <br/> 
 /// <br/> # Integration 
 /// <br/> To integrate OpenBank into your NEAR dApp you use either/both of the traits [ob_traits::TOpenBank] and/or [ob_traits::TOpenBankAdmin]
@@ -27,18 +28,34 @@
 
 mod ob_io;
 mod tests; 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ob_memo;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{env, near_bindgen,  ext_contract, json_types, PromiseResult, Promise, PromiseOrValue,};
 use near_sdk::json_types::{U64, I64, U128};
-use ob_io::{RequestDebit, Payment, MultiPaymentRequest};
+use ob_io::{RequestDebit, Payment, MultiPaymentRequest, VestingGrantRequest, EscrowRequest, MilestoneRequest, InvoiceRequest, SplitRuleRequest, FeeSchedule, TreasuryPolicy, PaymentAnnotation};
 
 near_sdk::setup_alloc!();
 
 const NO_DEPOSIT: near_sdk::Balance = 0;
 const BASE_GAS: near_sdk::Gas = 5_000_000_000_000;
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+const WEEK_MILLIS: i64 = 7 * DAY_MILLIS;
+const MAX_FORECAST_PERIODS: i64 = 366;
+const CRANK_STEP_GAS: near_sdk::Gas = 20_000_000_000_000;
+const DEFAULT_POT: &str = "general";
+const MAX_DESCRIPTION_LENGTH: usize = 256;
+const MAX_EXTERNAL_REFERENCE_LENGTH: usize = 64;
+const MAX_METADATA_ENTRIES: usize = 16;
+const MAX_METADATA_KEY_LENGTH: usize = 32;
+const MAX_METADATA_VALUE_LENGTH: usize = 256;
+const MAX_ACTIVITY_DAYS: i64 = 366;
+const MEMO_KEY_LENGTH: usize = 32;
+const MEMO_NONCE_LENGTH: usize = 24;
+const MEMO_TAG_LENGTH: usize = 16;
 
 #[ext_contract(ext_open_roles)]
 pub trait TOpenRoles {
@@ -67,12 +84,76 @@ struct OpenBank {
 
     payments                    : HashSet<ob_io::Payment>,
     payments_by_reference       : HashMap<u64, ob_io::Payment>,
+    payment_references          : Vec<u64>, // these are the references of the payments registered, in sequence 
 
     access_security             : near_sdk::AccountId, 
     nonce_register              : HashMap<String, HashSet<u64>>,
     test_mode                   : bool,
     affirmative_code            : i32, 
     negative_code               : i32, 
+
+    keeper_reward               : u128, // this is the reward paid to keepers per RequestDebit settled 
+    keeper_reward_budget        : u128, // this is what remains to be paid out as keeper rewards
+    keeper_reward_pot           : String, // this is the pot keeper rewards are drawn from
+
+    scheduled_payouts           : HashMap<u64, ob_io::ScheduledPayout>, // these are the standing orders set up by this bank searchable by reference
+    payroll_runs                : HashMap<u64, ob_io::PayrollRun>, // these are the payroll runs created at this bank searchable by reference 
+    payment_sequence            : u64, // this is the sequence number of the last payment registered
+    record_sequence             : u64, // this is the sequence number of the last record created that holds or moves funds
+
+    vesting_grants              : HashMap<u64, ob_io::VestingGrant>, // these are the vesting grants made by this bank searchable by reference
+    vesting_balance             : u128, // this is the amount set aside for vesting grants that has not yet been claimed, it is not part of the bank balance
+
+    payment_streams             : HashMap<u64, ob_io::PaymentStream>, // these are the payment streams set up by this bank searchable by reference 
+    stream_balance              : u128, // this is the amount set aside for payment streams that has not yet been withdrawn, it is not part of the bank balance
+
+    escrows                     : HashMap<u64, ob_io::Escrow>, // these are the escrows held by this bank searchable by reference 
+    escrow_balance              : u128, // this is the amount held in escrow pending release or refund, it is not part of the bank balance
+
+    supplier_contracts          : HashMap<u64, ob_io::SupplierContract>, // these are the milestone based supplier contracts of this bank searchable by reference 
+    milestone_contracts         : HashMap<u64, u64>, // this maps each milestone id to the reference of the supplier contract it belongs to
+
+    invoices                    : HashMap<u64, ob_io::Invoice>, // these are the invoices raised by this bank searchable by reference 
+    customer_credits            : HashMap<String, u128>, // this is the credit held by this bank for each account 
+    credit_balance              : u128, // this is the total credit held for accounts, it is owed to them and is not part of the bank balance
+
+    subscription_plans          : HashMap<u64, ob_io::SubscriptionPlan>, // these are the plans customers can subscribe to searchable by reference 
+    subscriptions               : HashMap<u64, ob_io::Subscription>, // these are the subscriptions paid from customer credit searchable by reference 
+
+    split_rules                 : HashMap<u64, ob_io::SplitRule>, // these are the revenue split rules set on this bank searchable by reference 
+    active_split_rules          : HashMap<String, u64>, // this maps each match i.e. 'TAG:{value}' or 'DAPP:{value}' to the reference of its 'ACTIVE' split rule
+    split_accruals              : HashMap<String, u128>, // this is the revenue accrued to each payee awaiting distribution 
+    split_balance               : u128, // this is the total revenue accrued to payees awaiting distribution, it is not part of the bank balance
+
+    fee_schedule                : ob_io::FeeSchedule, // these are the fees charged by this bank 
+    fee_schedule_changes        : Vec<ob_io::FeeScheduleChange>, // this is a log of the changes made to the fee schedule, oldest first 
+    fee_balance                 : u128, // this is the total of the fees charged that has not been withdrawn, it is not part of the bank balance
+
+    pots                        : HashMap<String, ob_io::Pot>, // these are the internal budgets of this bank searchable by name, their balances always sum to the bank balance 
+
+    treasury_policy             : ob_io::TreasuryPolicy, // these are the reserve, ceiling and warning levels of the bank balance 
+    low_balance_events          : Vec<ob_io::LowBalanceEvent>, // this is a log of each time the bank balance dropped below the warning level, oldest first 
+
+    journal                     : Vec<ob_io::JournalEntry>, // this is the double entry journal of every movement of funds, oldest first 
+    tied_journal_sequence       : u64, // this is the sequence of the last journal entry tied to the Payment it is behind 
+    ledger_accounts             : HashMap<String, ob_io::LedgerAccount>, // these are the running debit and credit totals of each account in the journal searchable by account name
+
+    payment_categories          : HashMap<String, ob_io::PaymentCategory>, // this is the chart of categories payments can be given, searchable by code 
+
+    lifetime_activity           : ob_io::ActivitySummary, // this is the running total of the pay ins, pay outs, deposits, withdrawals and internal movements of this bank 
+    daily_activity              : BTreeMap<i64, ob_io::ActivitySummary>, // these are the running totals for each of the last 'MAX_ACTIVITY_DAYS' days searchable by day number 
+    last_activity_time          : i64, // this is the time of the latest Payment
+
+    statement_periods           : Vec<ob_io::StatementPeriod>, // these are the closed statement periods of this bank, oldest first 
+    open_statement_period       : ob_io::StatementPeriod, // this is the current statement period, it holds the totals of the Payments made since the last close 
+    statement_schedule          : ob_io::StatementSchedule, // this is when statement periods are due to close 
+
+    audit_checkpoint            : ob_io::AuditCheckpoint, // this is how far the payments have been audited and the balance rebuilt from them 
+    outflows_paused             : bool, // this is set when a strict audit finds a mismatch, no funds leave the bank until outflows are resumed 
+
+    privacy_mode                : bool, // this is set when full Payment and RequestDebit records are only shown to their parties and accounts allowed to 'view_payments'
+
+    memo_public_key             : String, // this is the base64 x25519 public key payers seal encrypted memos for, empty until it is set 
 }
 
 #[near_bindgen]
@@ -118,6 +199,200 @@ impl OpenBank {
         }     
         panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
     }
+    /// this operation will return the total held in escrow by this bank, this is not part of the balance of the bank 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn view_escrow_balance(&mut self) -> U128 {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return U128::from(self.escrow_balance);
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the total credit held by this bank for accounts. This is owed to the accounts and is not part of the balance of the bank 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn view_credit_balance(&mut self) -> U128 {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return U128::from(self.credit_balance);
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the total of the fees charged by this bank that has not been withdrawn. This is not part of the balance of the bank 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn view_fee_balance(&mut self) -> U128 {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return U128::from(self.fee_balance);
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the balance of the given pot 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn view_pot_balance(&mut self, pot : String) -> U128 {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return U128::from(self.get_pot(&pot).balance);
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return all the pots of this bank in name order. Their balances sum to the balance of the bank 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn find_pots(&mut self) -> Vec<ob_io::Pot> {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            let mut pots : Vec<ob_io::Pot> = self.pots.values().cloned().collect();
+            pots.sort_by(|a, b| a.name.cmp(&b.name));
+            return pots;
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the trial balance of this bank i.e. the total debits and credits of each account in the journal, in account name order 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn view_trial_balance(&mut self) -> Vec<ob_io::LedgerAccount> {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            let mut ledger_accounts : Vec<ob_io::LedgerAccount> = self.ledger_accounts.values().cloned().collect();
+            ledger_accounts.sort_by(|a, b| a.name.cmp(&b.name));
+            return ledger_accounts;
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the ledger of the given account i.e. every journal entry posted to it, oldest first 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    pub fn find_ledger_entries(&mut self, account : String) -> Vec<ob_io::JournalEntry> {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return self.journal.iter().filter(|entry| entry.debit_account == account || entry.credit_account == account).cloned().collect();
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will check that the journal balances and agrees with the balances held by this bank i.e. the 'CASH' account equals the bank balance, 
+    /// the 'HELD_FUNDS' account equals the funds held apart from it and the 'ESCROW', 'RESERVES', 'FEES' and 'PAYABLES' accounts owe what is held for each 
+    /// [**ungoverned**], [**non-payable**]
+    /// # Return Value 
+    /// 'true' if the journal agrees with the bank 
+    pub fn check_ledger(&self) -> bool {
+        let total_debits : u128 = self.ledger_accounts.values().map(|ledger_account| ledger_account.debits).sum();
+        let total_credits : u128 = self.ledger_accounts.values().map(|ledger_account| ledger_account.credits).sum();
+
+        total_debits == total_credits 
+            && self.get_ledger_balance("CASH") == self.bank_balance as i128 
+            && self.get_ledger_balance("HELD_FUNDS") == self.get_outstanding_obligations() as i128 
+            && -self.get_ledger_balance("ESCROW") == self.escrow_balance as i128 
+            && -self.get_ledger_balance("RESERVES") == (self.vesting_balance + self.stream_balance) as i128 
+            && -self.get_ledger_balance("FEES") == self.fee_balance as i128 
+            && -self.get_ledger_balance("PAYABLES") == (self.credit_balance + self.split_balance) as i128 
+    }
+
+    /// this operation will return a summary of this bank for dashboards, with the pay ins, pay outs, deposits, withdrawals and internal movements over each of the given 'periods' in days. 
+    /// A period of 0 days covers the life of the bank, other periods can be up to 366 days 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **BankSummary** struct 
+    pub fn get_bank_summary(&mut self, periods : Vec<u64>) -> ob_io::BankSummary {
+        if !self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+        }
+        let today = env::block_timestamp() as i64 / DAY_MILLIS;
+
+        let mut activity = Vec::<ob_io::ActivitySummary>::new();
+        for days in periods {
+            if days == 0 {
+                activity.push(self.lifetime_activity.clone());
+                continue;
+            }
+            self.require(days as i64 <= MAX_ACTIVITY_DAYS, format!("INVALID SUMMARY PERIOD {} DAYS. MAXIMUM PERIOD: {} DAYS", days, MAX_ACTIVITY_DAYS));
+            let mut period_activity = ob_io::ActivitySummary { days, ..Default::default() };
+            for day_activity in self.daily_activity.range((today - days as i64 + 1)..=today).map(|(_, a)| a) {
+                period_activity.add(day_activity);
+            }
+            activity.push(period_activity);
+        }
+
+        let mut request_debits_by_status : Vec<(String, u64)> = self.request_debits_by_status.iter().map(|(status, debits)| (status.clone(), debits.len() as u64)).collect();
+        request_debits_by_status.sort();
+
+        ob_io::BankSummary {
+            balance                     : self.bank_balance,
+            activity,
+            request_debits_by_status,
+            outstanding_obligations     : self.get_outstanding_obligations(),
+            last_activity_time          : self.last_activity_time,
+        }
+    }
+
+    /// this operation will return the current statement period of this bank i.e. the totals of the Payments made since the last close 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **StatementPeriod** struct with 'OPEN' status 
+    pub fn view_open_statement_period(&mut self) -> ob_io::StatementPeriod {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return self.open_statement_period.clone();
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will return the closed statement periods of this bank, oldest first 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Vec** of **StatementPeriod** structs 
+    pub fn find_statement_periods(&mut self) -> Vec<ob_io::StatementPeriod> {
+        if self.is_secure( "view_balance".to_string(), "ALLOWED".to_string()) {
+            return self.statement_periods.clone();
+        }     
+        panic!("BALANCE VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ",env::signer_account_id());
+    }
+
+    /// this operation will rebuild the closed statement period with the given number from the journal and the Payments made in it and check it against the recorded statement 
+    /// i.e. the opening balance is the closing balance of the period before, the movement of the 'CASH' account takes the opening balance to the closing balance 
+    /// and the Payments in the sequence range add up to the totals by payment type 
+    /// [**ungoverned**], [**non-payable**]
+    /// # Return Value 
+    /// 'true' if the statement period agrees with the journal and the Payments 
+    /// @panic if unknown statement period number provided 
+    pub fn check_statement_period(&self, statement_period_number : U64) -> bool {
+        let number = u64::from(statement_period_number);
+        if number == 0 || number > self.statement_periods.len() as u64 {
+            panic!("UNKNOWN STATEMENT PERIOD {} ", number);
+        }
+        let statement_period = &self.statement_periods[(number - 1) as usize];
+        let previous_closing_balance = if number == 1 { 0 } else { self.statement_periods[(number - 2) as usize].closing_balance };
+
+        let cash_movement = OpenBank::get_cash_movement(&self.journal[(statement_period.first_journal_sequence - 1) as usize..statement_period.last_journal_sequence as usize]);
+
+        let mut rebuilt = ob_io::StatementPeriod::default();
+        for payment in self.payments.iter().filter(|payment| payment.sequence >= statement_period.first_payment_sequence && payment.sequence <= statement_period.last_payment_sequence) {
+            rebuilt.record(&payment.payment_type, payment.amount);
+        }
+
+        statement_period.opening_balance == previous_closing_balance 
+            && statement_period.opening_balance as i128 + cash_movement == statement_period.closing_balance as i128 
+            && rebuilt.totals_by_type == statement_period.totals_by_type 
+    }
+
+    /// this operation will return whether outflows from this bank have been paused by a strict **audit** 
+    /// [**ungoverned**], [**non-payable**]
+    /// # Return Value 
+    /// 'true' if no funds can currently leave this bank 
+    pub fn is_outflows_paused(&self) -> bool {
+        self.outflows_paused
+    }
+
+    /// this operation will return the public key payers seal encrypted memos for, see **ob_memo** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// base64 x25519 public key, empty if encrypted memos are not accepted 
+    pub fn view_memo_public_key(&self) -> String {
+        self.memo_public_key.clone()
+    }
+
     /// this operation will return the role security codes for this bank
     /// [**governed**], [**non-payable**] 
     /// # Return Value 
@@ -140,655 +415,4065 @@ impl OpenBank {
     }
 
 	/// this operation will find the given RequestDebit according to the given reference    
+    /// In privacy mode only the payee and the creator can see the RequestDebit, accounts allowed to 'view_payments' can use **find_request_debit_private** 
     /// [**ungoverned**], [**non-payable**] 
     /// # Return Value
     /// Request Debit struct matching the provided reference 
     /// @panic if unknown reference provided 
     pub fn find_request_debit(&self, u_request_debit_reference : U64)-> ob_io::RequestDebit  {
-        let request_debit_reference = u64::from(u_request_debit_reference);
-        if !self.request_debit_by_reference.contains_key(&request_debit_reference) {
-            panic!("UNKNOWN REQUEST DEBIT REFERENCE {} ", request_debit_reference);
-        }
-        self.request_debit_by_reference.get(&request_debit_reference).unwrap().clone()
+        let request_debit = self.get_request_debit(u64::from(u_request_debit_reference));
+        self.check_visible(&[&request_debit.payee, &request_debit.creator]);
+        request_debit
+    }
+
+    /// this operation will find the given RequestDebit according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Request Debit struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_request_debit_private(&mut self, u_request_debit_reference : U64)-> ob_io::RequestDebit  {
+        self.check_view_payments();
+        self.get_request_debit(u64::from(u_request_debit_reference))
     }
 
     /// this operation will find a set of RequestDebits that have the given status 
+    /// In privacy mode only the RequestDebits the signer is the payee or creator of are found, accounts allowed to 'view_payments' can use **find_request_debits_by_status_private** 
     /// [**ungoverned**], [**non-payable**] 
     /// # Return Value 
     /// **HashSet** of **RequestDebit** structs with the status provided 
     /// @panic if unknown request debit status provided 
     pub fn find_request_debits_by_status(&self, status : String) -> HashSet<ob_io::RequestDebit> {
-        if !self.request_debits_by_status.contains_key(&status) {
-            panic!("UNKNOWN REQUEST DEBIT STATUS {} ", status);
-        }
-        self.request_debits_by_status.get(&status).unwrap().clone()
+        self.get_request_debits_by_status(status).into_iter().filter(|rd| self.is_visible(&[&rd.payee, &rd.creator])).collect()
     }
-   
-    /// this operation will find the given Payment with the given reference 
-    /// [**ungoverned**], [**non-payable**]     
-    /// # Return Value
-    /// **Payment** struct matching payment reference 
-    /// @panic if unknown payment reference provided
-    pub fn find_payment(&self, u_payment_ref :U64) -> ob_io::Payment {
-        let payment_ref = u64::from(u_payment_ref);
-        if !self.payments_by_reference.contains_key(&payment_ref)  {
-            panic!("UNKNOWN PAYMENT REFERENCE {} ", payment_ref);
-        }
-        self.payments_by_reference.get(&payment_ref).unwrap().clone()
+
+    /// this operation will find a set of RequestDebits that have the given status whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **HashSet** of **RequestDebit** structs with the status provided 
+    /// @panic if unknown request debit status provided 
+    pub fn find_request_debits_by_status_private(&mut self, status : String) -> HashSet<ob_io::RequestDebit> {
+        self.check_view_payments();
+        self.get_request_debits_by_status(status)
     }
-    /// this operation will return whether the given payment reference is valid 
+   
+    /// this operation will find the 'APPROVED' RequestDebits that can be claimed now, earliest due first 
+    /// In privacy mode only the RequestDebits the signer is the payee or creator of are found, accounts allowed to 'view_payments' can use **find_due_request_debits_private** 
     /// [**ungoverned**], [**non-payable**] 
     /// # Return Value 
-    /// **true** if and only if the payment reference is valid 
-    pub fn is_valid_payment_ref(&self, u_payment_ref :U64) -> bool {
-        let payment_ref = u64::from(u_payment_ref);
-        self.payments_by_reference.contains_key(&payment_ref)
-    } 
+    /// **Vec** of **RequestDebit** structs that are currently due
+    pub fn find_due_request_debits(&self) -> Vec<ob_io::RequestDebit> {
+        let mut request_debits = self.get_due_request_debits();
+        request_debits.retain(|rd| self.is_visible(&[&rd.payee, &rd.creator]));
+        request_debits
+    }
 
-    /// this operation will *'pay in'* the attached funds to the bank and increment the bank balance accordingly
-    /// [**governed**], [**payable**]
+    /// this operation will find the 'APPROVED' RequestDebits that can be claimed now, earliest due first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **RequestDebit** structs that are currently due
+    pub fn find_due_request_debits_private(&mut self) -> Vec<ob_io::RequestDebit> {
+        self.check_view_payments();
+        self.get_due_request_debits()
+    }
+   
+    /// this operation will find the given ScheduledPayout according to the given reference    
+    /// In privacy mode only the payee and the creator can see the ScheduledPayout, accounts allowed to 'view_payments' can use **find_scheduled_payout_private** 
+    /// [**ungoverned**], [**non-payable**] 
     /// # Return Value
-    /// **Payment** struct containing  details of the "pay in" made
-    #[payable]
-    pub fn pay_in(&mut self, payment_description :  String ,  pay_in_amount : U128, nonce : U64)->  ob_io::Payment {
-        // check nonce
-        self.check_nonce(u64::from(nonce));
+    /// Scheduled Payout struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_scheduled_payout(&self, scheduled_payout_ref : U64) -> ob_io::ScheduledPayout {
+        let scheduled_payout = self.get_scheduled_payout(u64::from(scheduled_payout_ref));
+        self.check_visible(&[&scheduled_payout.payee, &scheduled_payout.creator]);
+        scheduled_payout
+    }
 
-        let signer_account_id = env::signer_account_id();
-        
-        // do security
-        let security_response = self.is_secure("pay_in".to_string(), "BARRED".to_string());
-        self.require(security_response, format!("PAY IN NOT ALLOWED. ACCOUNT {} BARRED", signer_account_id));
+    /// this operation will find the given ScheduledPayout according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Scheduled Payout struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_scheduled_payout_private(&mut self, scheduled_payout_ref : U64) -> ob_io::ScheduledPayout {
+        self.check_view_payments();
+        self.get_scheduled_payout(u64::from(scheduled_payout_ref))
+    }
 
-        let stated_amount = u128::from(pay_in_amount);
+    /// this operation will find the ScheduledPayouts that have the given status, ordered by next payment date 
+    /// In privacy mode only the ScheduledPayouts the signer is the payee or creator of are found, accounts allowed to 'view_payments' can use **find_scheduled_payouts_by_status_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **ScheduledPayout** structs with the status provided 
+    pub fn find_scheduled_payouts_by_status(&self, status : String) -> Vec<ob_io::ScheduledPayout> {
+        let mut scheduled_payouts = self.get_scheduled_payouts_by_status(status);
+        scheduled_payouts.retain(|sp| self.is_visible(&[&sp.payee, &sp.creator]));
+        scheduled_payouts
+    }
 
-        // check amounts
-        let attached_amount = env::attached_deposit();
-        self.check_attachment_vs_stated_amount(attached_amount, stated_amount);
+    /// this operation will find the ScheduledPayouts that have the given status, ordered by next payment date, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **ScheduledPayout** structs with the status provided 
+    pub fn find_scheduled_payouts_by_status_private(&mut self, status : String) -> Vec<ob_io::ScheduledPayout> {
+        self.check_view_payments();
+        self.get_scheduled_payouts_by_status(status)
+    }
 
-        // increment the bank balance
-        self.increment_bank_balance(stated_amount);
+    /// this operation will find the given PayrollRun according to the given reference    
+    /// In privacy mode only the creator and the approver can see the whole PayrollRun and a payee only sees its own lines, accounts allowed to 'view_payments' can use **find_payroll_run_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Payroll Run struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_payroll_run(&self, payroll_run_ref : U64) -> ob_io::PayrollRun {
+        match self.get_visible_payroll_run(self.get_payroll_run(u64::from(payroll_run_ref))) {
+            Some(payroll_run) => payroll_run,
+            None => panic!("PAYMENT VIEW NOT ALLOWED IN PRIVACY MODE - ACCOUNT {} NOT A PARTY ", env::signer_account_id()),
+        }
+    }
 
-        self.create_and_register_payment(  
-                                            self.bank_deployed_account_id.clone(), 
-                                            signer_account_id, 
-                                            env::signer_account_id(), 
-                                            stated_amount, 
-                                            payment_description,
-                                            "COMPLETED".to_string(),
-                                            "PAY_IN".to_string())
-                                      
-    } 
+    /// this operation will find the given PayrollRun according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Payroll Run struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_payroll_run_private(&mut self, payroll_run_ref : U64) -> ob_io::PayrollRun {
+        self.check_view_payments();
+        self.get_payroll_run(u64::from(payroll_run_ref))
+    }
 
-    /// This operation will *'pay out'* funds to the given account ID and decrement the balance of this bank accordingly 
-    /// [**governed**], [**non-payable**]
+    /// this operation will find the PayrollRuns that have the given status, oldest first 
+    /// In privacy mode only the PayrollRuns the signer created, approved or is paid by are found, accounts allowed to 'view_payments' can use **find_payroll_runs_by_status_private** 
+    /// [**ungoverned**], [**non-payable**] 
     /// # Return Value 
-    /// **Payment** object with details of the pay out made 
-    pub fn pay_out(&mut self, description : String, payout_amount : U128, account_id : String, nonce : U64) -> ob_io::Payment {
-        // check nonce 
-        self.check_nonce(u64::from(nonce));
-        
-        let signer_account_id = env::signer_account_id();
+    /// **Vec** of **PayrollRun** structs with the status provided 
+    pub fn find_payroll_runs_by_status(&self, status : String) -> Vec<ob_io::PayrollRun> {
+        self.get_payroll_runs_by_status(status).into_iter().filter_map(|pr| self.get_visible_payroll_run(pr)).collect()
+    }
 
-        let security_response = self.is_secure("payout".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("PAY OUT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+    /// this operation will find the PayrollRuns that have the given status, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **PayrollRun** structs with the status provided 
+    pub fn find_payroll_runs_by_status_private(&mut self, status : String) -> Vec<ob_io::PayrollRun> {
+        self.check_view_payments();
+        self.get_payroll_runs_by_status(status)
+    }
 
-        let amount = u128::from(payout_amount);
+    /// this operation will find the given VestingGrant according to the given reference    
+    /// In privacy mode only the beneficiary and the creator can see the VestingGrant, accounts allowed to 'view_payments' can use **find_vesting_grant_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Vesting Grant struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_vesting_grant(&self, vesting_grant_ref : U64) -> ob_io::VestingGrant {
+        let vesting_grant = self.get_vesting_grant(u64::from(vesting_grant_ref));
+        self.check_visible(&[&vesting_grant.beneficiary, &vesting_grant.creator]);
+        vesting_grant
+    }
 
-        // check bank balance 
-        self.check_bank_balance(amount);
+    /// this operation will find the given VestingGrant according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Vesting Grant struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_vesting_grant_private(&mut self, vesting_grant_ref : U64) -> ob_io::VestingGrant {
+        self.check_view_payments();
+        self.get_vesting_grant(u64::from(vesting_grant_ref))
+    }
 
-        // decrement the bank balance
-        self.decrement_bank_balance(amount);
+    /// this operation will find the VestingGrants made to the given beneficiary, oldest first 
+    /// In privacy mode only the VestingGrants the signer is the beneficiary or creator of are found, accounts allowed to 'view_payments' can use **find_vesting_grants_by_beneficiary_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **VestingGrant** structs for the beneficiary 
+    pub fn find_vesting_grants_by_beneficiary(&self, beneficiary : String) -> Vec<ob_io::VestingGrant> {
+        let mut vesting_grants = self.get_vesting_grants_by_beneficiary(beneficiary);
+        vesting_grants.retain(|vg| self.is_visible(&[&vg.beneficiary, &vg.creator]));
+        vesting_grants
+    }
 
-        // pay to the given account 
-        self.pay_to(  account_id, 
-                            signer_account_id, 
-               amount, 
-           description, 
-                "PAY_OUT".to_string())
+    /// this operation will find the VestingGrants made to the given beneficiary, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **VestingGrant** structs for the beneficiary 
+    pub fn find_vesting_grants_by_beneficiary_private(&mut self, beneficiary : String) -> Vec<ob_io::VestingGrant> {
+        self.check_view_payments();
+        self.get_vesting_grants_by_beneficiary(beneficiary)
     }
-    
-    /// This operation will 'pay out' to multiple 'payee's as described by the *'multi_payment_requests'* and decrement the balance of this bank accordingly
+
+    /// this operation will return how much of the given VestingGrant has vested and how much of that can still be claimed 
+    /// In privacy mode only the beneficiary and the creator can see the amounts, accounts allowed to 'view_payments' can use **view_vested_amount_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// *vested* - amount vested to date, claimed or not 
+    /// *claimable* - amount the beneficiary can claim now 
+    pub fn view_vested_amount(&self, vesting_grant_ref : U64) -> (U128, U128) {
+        OpenBank::get_vested_amounts(&self.find_vesting_grant(vesting_grant_ref))
+    }
+
+    /// this operation will return how much of the given VestingGrant has vested and how much of that can still be claimed whether or not this bank is in privacy mode 
     /// [**governed**], [**non-payable**] 
-    /// #Return Value 
-    /// **HashSet** of **Payment** structs conaining information on the payments made     
-    pub fn pay_out_multi(&mut self, multi_payment_requests : HashSet<MultiPaymentRequest>, nonce : U64) -> HashSet<Payment> {
-        // check nonce 
-        self.check_nonce(u64::from(nonce));
+    /// # Return Value 
+    /// *vested* - amount vested to date, claimed or not 
+    /// *claimable* - amount the beneficiary can claim now 
+    pub fn view_vested_amount_private(&mut self, vesting_grant_ref : U64) -> (U128, U128) {
+        OpenBank::get_vested_amounts(&self.find_vesting_grant_private(vesting_grant_ref))
+    }
 
-        let signer_account_id = env::signer_account_id();
-        
-        let security_response = self.is_secure("pay_out_multi".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("MULTI PAY OUT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        
-        // sum the amounts 
-        let total = OpenBank::get_total(multi_payment_requests.clone());
+    /// this operation will find the given PaymentStream according to the given reference    
+    /// In privacy mode only the recipient and the creator can see the PaymentStream, accounts allowed to 'view_payments' can use **find_payment_stream_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Payment Stream struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_payment_stream(&self, payment_stream_ref : U64) -> ob_io::PaymentStream {
+        let payment_stream = self.get_payment_stream(u64::from(payment_stream_ref));
+        self.check_visible(&[&payment_stream.recipient, &payment_stream.creator]);
+        payment_stream
+    }
 
-        // check the bank balance 
-        self.check_bank_balance(total);
+    /// this operation will find the given PaymentStream according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Payment Stream struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_payment_stream_private(&mut self, payment_stream_ref : U64) -> ob_io::PaymentStream {
+        self.check_view_payments();
+        self.get_payment_stream(u64::from(payment_stream_ref))
+    }
 
-        // set up the payments basket
-        let mut payments :  HashSet<Payment> = HashSet::new();
+    /// this operation will find the PaymentStreams to the given recipient, oldest first 
+    /// In privacy mode only the PaymentStreams the signer is the recipient or creator of are found, accounts allowed to 'view_payments' can use **find_payment_streams_by_recipient_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **PaymentStream** structs for the recipient 
+    pub fn find_payment_streams_by_recipient(&self, recipient : String) -> Vec<ob_io::PaymentStream> {
+        let mut payment_streams = self.get_payment_streams_by_recipient(recipient);
+        payment_streams.retain(|ps| self.is_visible(&[&ps.recipient, &ps.creator]));
+        payment_streams
+    }
 
-        // iterate 
-        for mpr in multi_payment_requests {
-                let amount = mpr.payout_amount;
-                // decrement the bank balance
-                self.decrement_bank_balance(amount);
+    /// this operation will find the PaymentStreams to the given recipient, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **PaymentStream** structs for the recipient 
+    pub fn find_payment_streams_by_recipient_private(&mut self, recipient : String) -> Vec<ob_io::PaymentStream> {
+        self.check_view_payments();
+        self.get_payment_streams_by_recipient(recipient)
+    }
+
+    /// this operation will return how much has accrued to the recipient of the given PaymentStream and how much of that has been withdrawn 
+    /// In privacy mode only the recipient and the creator can see the amounts, accounts allowed to 'view_payments' can use **view_payment_stream_balance_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// *accrued* - amount accrued to date, withdrawn or not 
+    /// *withdrawn* - amount withdrawn to date 
+    pub fn view_payment_stream_balance(&self, payment_stream_ref : U64) -> (U128, U128) {
+        OpenBank::get_payment_stream_amounts(&self.find_payment_stream(payment_stream_ref))
+    }
+
+    /// this operation will return how much has accrued to the recipient of the given PaymentStream and how much of that has been withdrawn whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// *accrued* - amount accrued to date, withdrawn or not 
+    /// *withdrawn* - amount withdrawn to date 
+    pub fn view_payment_stream_balance_private(&mut self, payment_stream_ref : U64) -> (U128, U128) {
+        OpenBank::get_payment_stream_amounts(&self.find_payment_stream_private(payment_stream_ref))
+    }
+
+    /// this operation will find the given Escrow according to the given reference    
+    /// In privacy mode only the payer, the payee, the creator and the approvers can see the Escrow, accounts allowed to 'view_payments' can use **find_escrow_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Escrow struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_escrow(&self, escrow_ref : U64) -> ob_io::Escrow {
+        let escrow = self.get_escrow(u64::from(escrow_ref));
+        if !self.is_escrow_visible(&escrow) {
+            panic!("PAYMENT VIEW NOT ALLOWED IN PRIVACY MODE - ACCOUNT {} NOT A PARTY ", env::signer_account_id());
+        }
+        escrow
+    }
+
+    /// this operation will find the given Escrow according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Escrow struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_escrow_private(&mut self, escrow_ref : U64) -> ob_io::Escrow {
+        self.check_view_payments();
+        self.get_escrow(u64::from(escrow_ref))
+    }
+
+    /// this operation will find the Escrows with the given status, oldest first 
+    /// In privacy mode only the Escrows the signer is party to are found, accounts allowed to 'view_payments' can use **find_escrows_by_status_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Escrow** structs with the given status 
+    pub fn find_escrows_by_status(&self, status : String) -> Vec<ob_io::Escrow> {
+        let mut escrows = self.get_escrows_by_status(status);
+        escrows.retain(|es| self.is_escrow_visible(es));
+        escrows
+    }
+
+    /// this operation will find the Escrows with the given status, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Escrow** structs with the given status 
+    pub fn find_escrows_by_status_private(&mut self, status : String) -> Vec<ob_io::Escrow> {
+        self.check_view_payments();
+        self.get_escrows_by_status(status)
+    }
+
+    /// this operation will find the given SupplierContract according to the given reference    
+    /// In privacy mode only the supplier and the creator can see the SupplierContract, accounts allowed to 'view_payments' can use **find_supplier_contract_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Supplier Contract struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_supplier_contract(&self, supplier_contract_ref : U64) -> ob_io::SupplierContract {
+        let supplier_contract = self.get_supplier_contract(u64::from(supplier_contract_ref));
+        self.check_visible(&[&supplier_contract.supplier, &supplier_contract.creator]);
+        supplier_contract
+    }
+
+    /// this operation will find the given SupplierContract according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Supplier Contract struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_supplier_contract_private(&mut self, supplier_contract_ref : U64) -> ob_io::SupplierContract {
+        self.check_view_payments();
+        self.get_supplier_contract(u64::from(supplier_contract_ref))
+    }
+
+    /// this operation will find the SupplierContracts with the given supplier, oldest first 
+    /// In privacy mode only the SupplierContracts the signer is the supplier or creator of are found, accounts allowed to 'view_payments' can use **find_supplier_contracts_by_supplier_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **SupplierContract** structs for the supplier 
+    pub fn find_supplier_contracts_by_supplier(&self, supplier : String) -> Vec<ob_io::SupplierContract> {
+        let mut supplier_contracts = self.get_supplier_contracts_by_supplier(supplier);
+        supplier_contracts.retain(|sc| self.is_visible(&[&sc.supplier, &sc.creator]));
+        supplier_contracts
+    }
+
+    /// this operation will find the SupplierContracts with the given supplier, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **SupplierContract** structs for the supplier 
+    pub fn find_supplier_contracts_by_supplier_private(&mut self, supplier : String) -> Vec<ob_io::SupplierContract> {
+        self.check_view_payments();
+        self.get_supplier_contracts_by_supplier(supplier)
+    }
+
+    /// this operation will find the Milestone with the given id 
+    /// In privacy mode only the supplier and the creator of its SupplierContract can see the Milestone, accounts allowed to 'view_payments' can use **find_milestone_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Milestone struct matching the provided id 
+    /// @panic if unknown id provided 
+    pub fn find_milestone(&self, milestone_id : U64) -> ob_io::Milestone {
+        let (supplier_contract, index) = self.get_milestone_contract(u64::from(milestone_id));
+        self.check_visible(&[&supplier_contract.supplier, &supplier_contract.creator]);
+        supplier_contract.milestones[index].clone()
+    }
+
+    /// this operation will find the Milestone with the given id whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Milestone struct matching the provided id 
+    /// @panic if unknown id provided 
+    pub fn find_milestone_private(&mut self, milestone_id : U64) -> ob_io::Milestone {
+        self.check_view_payments();
+        let (supplier_contract, index) = self.get_milestone_contract(u64::from(milestone_id));
+        supplier_contract.milestones[index].clone()
+    }
+
+    /// this operation will find the given Invoice according to the given reference    
+    /// In privacy mode only the payer and the creator can see the Invoice, accounts allowed to 'view_payments' can use **find_invoice_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Invoice struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_invoice(&self, invoice_ref : U64) -> ob_io::Invoice {
+        let invoice = self.get_invoice(u64::from(invoice_ref));
+        if !self.is_invoice_visible(&invoice) {
+            panic!("PAYMENT VIEW NOT ALLOWED IN PRIVACY MODE - ACCOUNT {} NOT A PARTY ", env::signer_account_id());
+        }
+        invoice
+    }
+
+    /// this operation will find the given Invoice according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Invoice struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_invoice_private(&mut self, invoice_ref : U64) -> ob_io::Invoice {
+        self.check_view_payments();
+        self.get_invoice(u64::from(invoice_ref))
+    }
+
+    /// this operation will find the Invoices with the given status, oldest first 
+    /// In privacy mode only the Invoices the signer is the payer or creator of are found, accounts allowed to 'view_payments' can use **find_invoices_by_status_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Invoice** structs with the given status 
+    pub fn find_invoices_by_status(&self, status : String) -> Vec<ob_io::Invoice> {
+        self.find_invoices_where(|invoice| invoice.status == status && self.is_invoice_visible(invoice))
+    }
+
+    /// this operation will find the Invoices with the given status, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Invoice** structs with the given status 
+    pub fn find_invoices_by_status_private(&mut self, status : String) -> Vec<ob_io::Invoice> {
+        self.check_view_payments();
+        self.find_invoices_where(|invoice| invoice.status == status)
+    }
+
+    /// this operation will find the Invoices that are still owed in whole or in part, oldest first 
+    /// In privacy mode only the Invoices the signer is the payer or creator of are found, accounts allowed to 'view_payments' can use **find_open_invoices_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of 'OPEN' and 'PARTIALLY_PAID' **Invoice** structs 
+    pub fn find_open_invoices(&self) -> Vec<ob_io::Invoice> {
+        self.find_invoices_where(|invoice| invoice.is_open() && self.is_invoice_visible(invoice))
+    }
+
+    /// this operation will find the Invoices that are still owed in whole or in part, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of 'OPEN' and 'PARTIALLY_PAID' **Invoice** structs 
+    pub fn find_open_invoices_private(&mut self) -> Vec<ob_io::Invoice> {
+        self.check_view_payments();
+        self.find_invoices_where(|invoice| invoice.is_open())
+    }
+
+    /// this operation will find the Invoices that are still owed after their due date, oldest first 
+    /// In privacy mode only the Invoices the signer is the payer or creator of are found, accounts allowed to 'view_payments' can use **find_overdue_invoices_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of overdue **Invoice** structs 
+    pub fn find_overdue_invoices(&self) -> Vec<ob_io::Invoice> {
+        let time_now = env::block_timestamp() as i64;
+        self.find_invoices_where(|invoice| invoice.is_overdue(time_now) && self.is_invoice_visible(invoice))
+    }
+
+    /// this operation will find the Invoices that are still owed after their due date, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of overdue **Invoice** structs 
+    pub fn find_overdue_invoices_private(&mut self) -> Vec<ob_io::Invoice> {
+        self.check_view_payments();
+        let time_now = env::block_timestamp() as i64;
+        self.find_invoices_where(|invoice| invoice.is_overdue(time_now))
+    }
+
+    /// this operation will return the credit held by this bank for the given account 
+    /// In privacy mode only the account itself can see its credit, accounts allowed to 'view_payments' can use **view_customer_credit_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// credit held for the account 
+    pub fn view_customer_credit(&self, account_id : String) -> U128 {
+        self.check_visible(&[&account_id]);
+        U128(self.get_customer_credit(&account_id))
+    }
+
+    /// this operation will return the credit held by this bank for the given account whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// credit held for the account 
+    pub fn view_customer_credit_private(&mut self, account_id : String) -> U128 {
+        self.check_view_payments();
+        U128(self.get_customer_credit(&account_id))
+    }
+
+    /// this operation will return the credit held by this bank for every account that has credit, in account order 
+    /// In privacy mode only the credit of the signer is returned, accounts allowed to 'view_payments' can use **find_customer_credits_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of *account id* and *credit* pairs 
+    pub fn find_customer_credits(&self) -> Vec<(String, U128)> {
+        let mut customer_credits = self.get_customer_credits();
+        customer_credits.retain(|(account_id, _)| self.is_visible(&[account_id]));
+        customer_credits
+    }
+
+    /// this operation will return the credit held by this bank for every account that has credit, in account order, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of *account id* and *credit* pairs 
+    pub fn find_customer_credits_private(&mut self) -> Vec<(String, U128)> {
+        self.check_view_payments();
+        self.get_customer_credits()
+    }
+
+    /// this operation will find the given SubscriptionPlan according to the given reference    
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Subscription Plan struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_subscription_plan(&self, subscription_plan_ref : U64) -> ob_io::SubscriptionPlan {
+        let subscription_plan_reference = u64::from(subscription_plan_ref);
+        match self.subscription_plans.get(&subscription_plan_reference) {
+            Some(subscription_plan) => subscription_plan.clone(),
+            None => panic!("UNKNOWN SUBSCRIPTION PLAN REFERENCE {} ", subscription_plan_reference),
+        }
+    }
+
+    /// this operation will find the given Subscription according to the given reference    
+    /// In privacy mode only the subscriber can see the Subscription, accounts allowed to 'view_payments' can use **find_subscription_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Subscription struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_subscription(&self, subscription_ref : U64) -> ob_io::Subscription {
+        let subscription = self.get_subscription(u64::from(subscription_ref));
+        self.check_visible(&[&subscription.subscriber]);
+        subscription
+    }
+
+    /// this operation will find the given Subscription according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Subscription struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_subscription_private(&mut self, subscription_ref : U64) -> ob_io::Subscription {
+        self.check_view_payments();
+        self.get_subscription(u64::from(subscription_ref))
+    }
+
+    /// this operation will find the Subscriptions of the given subscriber, oldest first 
+    /// In privacy mode only the Subscriptions of the signer are found, accounts allowed to 'view_payments' can use **find_subscriptions_by_subscriber_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Subscription** structs for the subscriber 
+    pub fn find_subscriptions_by_subscriber(&self, subscriber : String) -> Vec<ob_io::Subscription> {
+        let mut subscriptions = self.get_subscriptions_by_subscriber(subscriber);
+        subscriptions.retain(|sub| self.is_visible(&[&sub.subscriber]));
+        subscriptions
+    }
+
+    /// this operation will find the Subscriptions of the given subscriber, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Subscription** structs for the subscriber 
+    pub fn find_subscriptions_by_subscriber_private(&mut self, subscriber : String) -> Vec<ob_io::Subscription> {
+        self.check_view_payments();
+        self.get_subscriptions_by_subscriber(subscriber)
+    }
+
+    /// this operation will return whether the given subscriber can currently use the given SubscriptionPlan. This is intended for dApps gating access to a service 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **true** if and only if the subscriber has a paid up subscription or one that is within its grace period 
+    pub fn is_subscribed(&self, subscriber : String, subscription_plan_ref : U64) -> bool {
+        let time_now = env::block_timestamp() as i64;
+        let plan_reference = u64::from(subscription_plan_ref);
+        self.subscriptions.values().any(|sub| sub.subscriber == subscriber && sub.plan_reference == plan_reference && sub.is_usable(time_now))
+    }
+
+    /// this operation will find the given SplitRule according to the given reference    
+    /// In privacy mode only the payees of its legs and the creator can see the SplitRule, accounts allowed to 'view_payments' can use **find_split_rule_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value
+    /// Split Rule struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_split_rule(&self, split_rule_ref : U64) -> ob_io::SplitRule {
+        let split_rule = self.get_split_rule(u64::from(split_rule_ref));
+        if !self.is_split_rule_visible(&split_rule) {
+            panic!("PAYMENT VIEW NOT ALLOWED IN PRIVACY MODE - ACCOUNT {} NOT A PARTY ", env::signer_account_id());
+        }
+        split_rule
+    }
+
+    /// this operation will find the given SplitRule according to the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// Split Rule struct matching the provided reference 
+    /// @panic if unknown reference provided 
+    pub fn find_split_rule_private(&mut self, split_rule_ref : U64) -> ob_io::SplitRule {
+        self.check_view_payments();
+        self.get_split_rule(u64::from(split_rule_ref))
+    }
+
+    /// this operation will find the 'ACTIVE' SplitRules, oldest first 
+    /// In privacy mode only the SplitRules the signer is a payee or the creator of are found, accounts allowed to 'view_payments' can use **find_active_split_rules_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of 'ACTIVE' **SplitRule** structs 
+    pub fn find_active_split_rules(&self) -> Vec<ob_io::SplitRule> {
+        let mut split_rules = self.get_active_split_rules();
+        split_rules.retain(|sr| self.is_split_rule_visible(sr));
+        split_rules
+    }
+
+    /// this operation will find the 'ACTIVE' SplitRules, oldest first, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of 'ACTIVE' **SplitRule** structs 
+    pub fn find_active_split_rules_private(&mut self) -> Vec<ob_io::SplitRule> {
+        self.check_view_payments();
+        self.get_active_split_rules()
+    }
+
+    /// this operation will return the revenue accrued to each payee awaiting distribution, in payee order 
+    /// In privacy mode only the revenue accrued to the signer is returned, accounts allowed to 'view_payments' can use **find_split_accruals_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of *payee* and *accrued amount* pairs 
+    pub fn find_split_accruals(&self) -> Vec<(String, U128)> {
+        let mut split_accruals = self.get_split_accruals();
+        split_accruals.retain(|(payee, _)| self.is_visible(&[payee]));
+        split_accruals
+    }
+
+    /// this operation will return the revenue accrued to each payee awaiting distribution, in payee order, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of *payee* and *accrued amount* pairs 
+    pub fn find_split_accruals_private(&mut self) -> Vec<(String, U128)> {
+        self.check_view_payments();
+        self.get_split_accruals()
+    }
+
+    /// this operation will return the fees currently charged by this bank 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **FeeSchedule** struct 
+    pub fn view_fee_schedule(&self) -> ob_io::FeeSchedule {
+        self.fee_schedule.clone()
+    }
+
+    /// this operation will return the log of changes made to the fee schedule of this bank, oldest first 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **FeeScheduleChange** structs 
+    pub fn find_fee_schedule_changes(&self) -> Vec<ob_io::FeeScheduleChange> {
+        self.fee_schedule_changes.clone()
+    }
+
+    /// this operation will return the treasury policy of this bank 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **TreasuryPolicy** struct 
+    pub fn view_treasury_policy(&self) -> ob_io::TreasuryPolicy {
+        self.treasury_policy.clone()
+    }
+
+    /// this operation will return the log of each time the balance of this bank dropped below the warning level of the treasury policy, oldest first 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **LowBalanceEvent** structs 
+    pub fn find_low_balance_events(&self) -> Vec<ob_io::LowBalanceEvent> {
+        self.low_balance_events.clone()
+    }
+
+    /// this operation will return the chart of categories payments can be given, in code order 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **PaymentCategory** structs 
+    pub fn find_payment_categories(&self) -> Vec<ob_io::PaymentCategory> {
+        let mut payment_categories : Vec<ob_io::PaymentCategory> = self.payment_categories.values().cloned().collect();
+        payment_categories.sort_by(|a, b| a.code.cmp(&b.code));
+        payment_categories
+    }
+
+    /// this operation will total the amounts of the payments in each category made from 'from_time' up to but not including 'to_time'. Payments without a category are left out 
+    /// In privacy mode only the Payments the signer is the payer, payee or signer of are totalled, accounts allowed to 'view_payments' can use **find_category_totals_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of category and total amount pairs in category order 
+    pub fn find_category_totals(&self, from_time : I64, to_time : I64) -> Vec<(String, U128)> {
+        self.total_categories_where(i64::from(from_time), i64::from(to_time), |p| self.is_visible(&[&p.payer, &p.payee, &p.signer]))
+    }
+
+    /// this operation will total the amounts of the payments in each category made from 'from_time' up to but not including 'to_time' whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of category and total amount pairs in category order 
+    pub fn find_category_totals_private(&mut self, from_time : I64, to_time : I64) -> Vec<(String, U128)> {
+        self.check_view_payments();
+        self.total_categories_where(i64::from(from_time), i64::from(to_time), |_| true)
+    }
+
+    /// this operation will find the Payments carrying the given external reference e.g. an ERP document number, in payment order 
+    /// In privacy mode only the Payments the signer is the payer, payee or signer of are found, accounts allowed to 'view_payments' can use **find_payments_by_external_reference_private** 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Payment** structs with the external reference 
+    pub fn find_payments_by_external_reference(&self, external_reference : String) -> Vec<ob_io::Payment> {
+        let mut payments = self.get_payments_by_external_reference(external_reference);
+        payments.retain(|p| self.is_visible(&[&p.payer, &p.payee, &p.signer]));
+        payments
+    }
+
+    /// this operation will find the Payments carrying the given external reference, in payment order, whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**] 
+    /// # Return Value 
+    /// **Vec** of **Payment** structs with the external reference 
+    pub fn find_payments_by_external_reference_private(&mut self, external_reference : String) -> Vec<ob_io::Payment> {
+        self.check_view_payments();
+        self.get_payments_by_external_reference(external_reference)
+    }
+
+    /// this operation will find the given Payment with the given reference 
+    /// In privacy mode only the payer, the payee and the signer can see the Payment, accounts allowed to 'view_payments' can use **find_payment_private** and anyone can use **verify_payment** 
+    /// [**ungoverned**], [**non-payable**]     
+    /// # Return Value
+    /// **Payment** struct matching payment reference 
+    /// @panic if unknown payment reference provided
+    pub fn find_payment(&self, u_payment_ref :U64) -> ob_io::Payment {
+        let payment = self.get_payment(u64::from(u_payment_ref));
+        self.check_visible(&[&payment.payer, &payment.payee, &payment.signer]);
+        payment
+    }
+
+    /// this operation will find the given Payment with the given reference whether or not this bank is in privacy mode 
+    /// [**governed**], [**non-payable**]     
+    /// # Return Value
+    /// **Payment** struct matching payment reference 
+    /// @panic if unknown payment reference provided
+    pub fn find_payment_private(&mut self, u_payment_ref :U64) -> ob_io::Payment {
+        self.check_view_payments();
+        self.get_payment(u64::from(u_payment_ref))
+    }
+
+    /// this operation will confirm the Payment with the given reference exists without showing who was paid what 
+    /// [**ungoverned**], [**non-payable**]     
+    /// # Return Value
+    /// **PaymentConfirmation** struct with the reference, status and time of the Payment 
+    /// @panic if unknown payment reference provided
+    pub fn verify_payment(&self, u_payment_ref :U64) -> ob_io::PaymentConfirmation {
+        let payment = self.get_payment(u64::from(u_payment_ref));
+        ob_io::PaymentConfirmation {
+            reference       : payment.reference,
+            status          : payment.status,
+            payment_time    : payment.payment_time,
+        }
+    }
+
+    /// this operation will return whether this bank is in privacy mode i.e. whether full Payment and RequestDebit records are only shown to their parties 
+    /// and accounts allowed to 'view_payments' 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// *true* if and only if this bank is in privacy mode 
+    pub fn is_privacy_mode(&self) -> bool {
+        self.privacy_mode
+    }
+    /// this operation will return whether the given payment reference is valid 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// **true** if and only if the payment reference is valid 
+    pub fn is_valid_payment_ref(&self, u_payment_ref :U64) -> bool {
+        let payment_ref = u64::from(u_payment_ref);
+        self.payments_by_reference.contains_key(&payment_ref)
+    } 
+
+    /// this operation will project the obligations owed to 'APPROVED' RequestDebits and 'ACTIVE' ScheduledPayouts over the given 'horizon' (millis) and compare them with the current bank balance 
+    /// 'granularity' - size of each forecast period, either "DAILY" or "WEEKLY"
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **LiquidityForecast** struct with the obligations per period and the start of the first period in which the bank would run short, if any 
+    /// @panic if unknown granularity provided or the horizon covers too many periods
+    pub fn view_liquidity_forecast(&mut self, horizon : I64, granularity : String) -> ob_io::LiquidityForecast {
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("view_liquidity_forecast".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("LIQUIDITY FORECAST VIEW NOT ALLOWED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let period_length = match granularity.as_str() {
+            "DAILY" => DAY_MILLIS,
+            "WEEKLY" => WEEK_MILLIS,
+            _ => panic!("UNKNOWN FORECAST GRANULARITY {} ", granularity),
+        };
+
+        let forecast_horizon = i64::from(horizon);
+        if forecast_horizon <= 0 || forecast_horizon / period_length > MAX_FORECAST_PERIODS {
+            panic!("INVALID FORECAST HORIZON. HORIZON {}, MAXIMUM PERIODS {} ", forecast_horizon, MAX_FORECAST_PERIODS);
+        }
+        // a part period at the end of the horizon is forecast as a full period
+        let period_count = (forecast_horizon + period_length - 1) / period_length;
+
+        let forecast_start = env::block_timestamp() as i64;
+        let forecast_end = forecast_start + period_count * period_length;
+
+        let approved_debits : Vec<ob_io::RequestDebit> = match self.request_debits_by_status.get("APPROVED") {
+            Some(debits) => debits.iter().cloned().collect(),
+            None => Vec::new(),
+        };
+
+        let scheduled_obligations = self.get_scheduled_payout_obligations(forecast_end);
+
+        let mut periods = Vec::<ob_io::ForecastPeriod>::new();
+        let mut projected_balance = self.bank_balance;
+        let mut total_obligations : u128 = 0;
+        let mut shortfall_date = None;
+
+        for period in 0..period_count {
+            let period_start = forecast_start + period * period_length;
+            let period_end = period_start + period_length;
+
+            let mut obligations : u128 = 0;
+            for rd in approved_debits.iter() {
+                // the first period also carries anything already due but not yet claimed
+                let already_counted = if period == 0 { 0 } else { OpenBank::count_request_debit_payments_due_by(rd, period_start) };
+                let due = OpenBank::count_request_debit_payments_due_by(rd, period_end) - already_counted;
+                obligations += rd.amount * due as u128;
+            }
+            for (payment_date, amount) in scheduled_obligations.iter() {
+                if *payment_date <= period_end && (period == 0 || *payment_date > period_start) {
+                    obligations += amount;
+                }
+            }
+            total_obligations += obligations;
+
+            let mut shortfall : u128 = 0;
+            if obligations > projected_balance {
+                shortfall = obligations - projected_balance;
+                projected_balance = 0;
+                if shortfall_date.is_none() {
+                    shortfall_date = Some(period_start);
+                }
+            }
+            else {
+                projected_balance -= obligations;
+            }
+
+            periods.push(ob_io::ForecastPeriod {
+                period_start, 
+                period_end, 
+                obligations, 
+                projected_balance, 
+                shortfall,
+            });
+        }
+
+        ob_io::LiquidityForecast {
+            opening_balance : self.bank_balance, 
+            forecast_start, 
+            forecast_end, 
+            granularity, 
+            total_obligations, 
+            periods, 
+            shortfall_date,
+        }
+    }
+
+    /// this operation will *'pay in'* the attached funds to the bank and increment the bank balance accordingly
+    /// a 'pay_in_tag' of 'CREDIT' adds the attached funds to the credit of the payer instead, which is held apart from the bank balance 
+    /// if a revenue split rule matches the 'pay_in_tag' or the calling dApp the pay in is split according to the rule 
+    /// the funds go to the given 'pot' or the 'general' pot if none is given 
+    /// the given 'annotation' gives the pay in its accounting category and tags 
+    /// [**governed**], [**payable**]
+    /// # Return Value
+    /// **Payment** struct containing  details of the "pay in" made
+    #[payable]
+    pub fn pay_in(&mut self, payment_description :  String ,  pay_in_amount : U128, pay_in_tag : Option<String>, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : U64)->  ob_io::Payment {
+        // check nonce
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+        
+        // do security
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        let security_response = self.is_secure(pot.get_operation("pay_in"), "BARRED".to_string());
+        self.require(security_response, format!("PAY IN NOT ALLOWED. ACCOUNT {} BARRED", signer_account_id));
+
+        self.check_payment_annotation(&annotation);
+
+        let stated_amount = u128::from(pay_in_amount);
+
+        // check amounts
+        let attached_amount = env::attached_deposit();
+        self.check_attachment_vs_stated_amount(attached_amount, stated_amount);
+
+        // charge the fee 
+        let fee = self.fee_schedule.pay_in.get_fee(stated_amount);
+        self.charge_fee(fee);
+        let net_amount = stated_amount - fee;
+
+        if pay_in_tag.as_deref() == Some("CREDIT") {
+            self.add_customer_credit(signer_account_id.clone(), net_amount);
+            self.hold_funds(net_amount, "PAYABLES");
+
+            let payment = self.create_and_register_payment(  
+                                            self.bank_deployed_account_id.clone(), 
+                                            signer_account_id, 
+                                            env::signer_account_id(), 
+                                            stated_amount, 
+                                            payment_description,
+                                            "COMPLETED".to_string(),
+                                            "CREDIT_PAY_IN".to_string());
+            let payment = self.record_fee(payment, fee);
+            return self.annotate_payment(payment, annotation);
+        }
+
+        // increment the pot and bank balance
+        self.increment_pot_balance(&pot.name, net_amount, "REVENUE");
+
+        let payment = self.create_and_register_payment(  
+                                            self.bank_deployed_account_id.clone(), 
+                                            signer_account_id, 
+                                            env::signer_account_id(), 
+                                            stated_amount, 
+                                            payment_description,
+                                            "COMPLETED".to_string(),
+                                            "PAY_IN".to_string());
+        let payment = self.record_fee(payment, fee);
+        let payment = self.annotate_payment(payment, annotation);
+        let payment = self.record_pot(payment, &pot.name);
+
+        if let Some(split_rule) = self.get_matching_split_rule(pay_in_tag) {
+            self.split_pay_in(&payment, split_rule, &pot.name);
+        }
+        self.sweep_excess_balance(&pot.name);
+        payment
+    } 
+
+    /// This operation will *'pay out'* funds to the given account ID and decrement the balance of this bank accordingly 
+    /// the funds are drawn from the given 'pot' or the 'general' pot if none is given 
+    /// the given 'annotation' gives the pay out its accounting category and tags 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** object with details of the pay out made 
+    pub fn pay_out(&mut self, description : String, payout_amount : U128, account_id : String, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : U64) -> ob_io::Payment {
+        // check nonce 
+        self.check_nonce(u64::from(nonce));
+        
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        let security_response = self.is_secure(pot.get_operation("payout"), "ALLOWED".to_string());
+        self.require(security_response, format!("PAY OUT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_payment_annotation(&annotation);
+
+        let amount = u128::from(payout_amount);
+
+        // check pot balance 
+        self.check_pot_balance(&pot.name, amount);
+        self.check_minimum_reserve(amount);
+
+        // decrement the pot and bank balance
+        self.decrement_pot_balance(&pot.name, amount, "EXPENSES");
+
+        // charge the fee 
+        let fee = self.fee_schedule.pay_out.get_fee(amount);
+        self.charge_fee(fee);
+
+        // pay to the given account 
+        let payment = self.pay_to_net_of_fee(  account_id, 
+                            signer_account_id, 
+               amount, 
+               fee, 
+           description, 
+                "PAY_OUT".to_string());
+        self.annotate_payment(payment, annotation)
+    }
+    
+    /// This operation will 'pay out' to multiple 'payee's as described by the *'multi_payment_requests'* and decrement the balance of this bank accordingly
+    /// the funds are drawn from the given 'pot' or the 'general' pot if none is given 
+    /// [**governed**], [**non-payable**] 
+    /// #Return Value 
+    /// **HashSet** of **Payment** structs conaining information on the payments made     
+    pub fn pay_out_multi(&mut self, multi_payment_requests : HashSet<MultiPaymentRequest>, pot : Option<String>, nonce : U64) -> HashSet<Payment> {
+        // check nonce 
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+        
+        let security_response = self.is_secure(pot.get_operation("pay_out_multi"), "ALLOWED".to_string());
+        self.require(security_response, format!("MULTI PAY OUT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        for mpr in &multi_payment_requests {
+            self.check_payment_annotation(&mpr.annotation);
+        }
+        
+        // sum the amounts 
+        let total = OpenBank::get_total(multi_payment_requests.clone());
+
+        // check the pot balance 
+        self.check_pot_balance(&pot.name, total);
+        self.check_minimum_reserve(total);
+
+        // set up the payments basket
+        let mut payments :  HashSet<Payment> = HashSet::new();
+
+        // iterate 
+        for mpr in multi_payment_requests {
+                let amount = mpr.payout_amount;
+                // decrement the pot and bank balance
+                self.decrement_pot_balance(&pot.name, amount, "EXPENSES");
+                
+                // pay to the payee
+               let payment =  self.pay_to(    
+                                                    mpr.payee_account_id, 
+                                                    signer_account_id.clone(), 
+                                        amount, 
+                                 mpr.description,
+                                       "PAY_OUT_MULTI".to_string());
+               let payment = self.annotate_payment(payment, mpr.annotation);
+
+                // add payment to vector
+                payments.insert(payment);
+        }
+        // return the payments
+        payments
+    
+    }
+
+    /// This operation will trigger the payment of the RequestDebit associated wqith the 'request_debit_ref'. Funds will be sent to the account id attached to the RequestDebit *not* the caller
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the payment to the Request Debit 
+    pub fn request_debit(&mut self, request_debit_ref : U64, nonce : U64) -> ob_io::Payment {
+        
+        // check nonce
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("request_debit".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("REQUEST DEBIT PAY OUT CANCELLED. ACCOUNT {} REQUEST DEBIT CLAIM NOT ALLOWED", signer_account_id));
+
+        let request_debit  = self.get_request_debit(u64::from(request_debit_ref)); 
+
+        // check request debit status 
+        self.check_request_debit_status(request_debit.status.clone(), "APPROVED".to_string());
+
+        // check last paid vs interval 
+        self.check_request_debit_interval(request_debit.clone()); 
+
+        // check the balance of the pot the request debit draws on
+        self.check_pot_balance(&request_debit.pot, request_debit.amount);
+
+        self.settle_request_debit(request_debit, signer_account_id)
+    }
+
+    /// This operation will pay every 'APPROVED' RequestDebit that is currently due, up to 'limit' RequestDebits or until the attached gas runs low. 
+    /// It is intended to be called periodically by keepers so that suppliers are paid without having to claim. Funds are sent to the account ids attached to the RequestDebits *not* the caller
+    /// Where a keeper reward has been configured the caller is paid the reward for each RequestDebit settled for as long as the keeper reward budget lasts and the minimum reserve allows 
+    /// RequestDebits that the bank cannot currently afford are left for a later run, nothing is paid while outflows are paused
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Vec** of **Payment** structs for the RequestDebits settled followed by the keeper reward, if any
+    pub fn process_due_request_debits(&mut self, limit : U64) -> Vec<ob_io::Payment> {
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("process_due_request_debits".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("REQUEST DEBIT PROCESSING CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payments = Vec::<ob_io::Payment>::new();
+        if self.outflows_paused {
+            return payments;
+        }
+        for request_debit in self.get_due_request_debits() {
+            if payments.len() as u64 >= u64::from(limit) || !self.has_gas_for_crank_step() {
+                break;
+            }
+            if !self.can_afford_from_pot(&request_debit.pot, request_debit.amount) {
+                continue; 
+            }
+            payments.push(self.settle_request_debit(request_debit, signer_account_id.clone()));
+        }
+
+        let reward = (self.keeper_reward * payments.len() as u128).min(self.keeper_reward_budget);
+        let keeper_reward_pot = self.keeper_reward_pot.clone();
+        if reward > 0 && self.can_afford_from_pot(&keeper_reward_pot, reward) && self.is_above_minimum_reserve(reward) {
+            self.keeper_reward_budget -= reward;
+            self.decrement_pot_balance(&keeper_reward_pot, reward, "EXPENSES");
+            let reward_payment = self.pay_to(signer_account_id.clone(), 
+                                            signer_account_id, 
+                                            reward, 
+                                            "keeper reward".to_string(), 
+                                            "KEEPER_REWARD".to_string());
+            payments.push(reward_payment);
+        }
+        payments
+    }
+
+    /// This operation will set the reward paid to keepers for each RequestDebit settled through **process_due_request_debits** and the total budget available for such rewards
+    /// Setting either to zero switches keeper rewards off. The rewards are drawn from the given 'pot' or the 'general' pot if none is given 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **true** if the keeper reward has been set
+    pub fn set_keeper_reward(&mut self, reward : U128, budget : U128, pot : Option<String>) -> bool {
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("set_keeper_reward"), "ALLOWED".to_string());
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        // run assignment
+        self.keeper_reward = u128::from(reward);
+        self.keeper_reward_budget = u128::from(budget);
+        self.keeper_reward_pot = pot.name;
+        true
+    }
+
+    /// this operation will return the keeper reward paid per RequestDebit settled and the remaining keeper reward budget 
+    /// [**ungoverned**], [**non-payable**] 
+    /// # Return Value 
+    /// *reward* - paid per RequestDebit settled 
+    /// *budget* - remaining to be paid out as keeper rewards 
+    pub fn view_keeper_reward(&self) -> (U128, U128) {
+        (U128::from(self.keeper_reward), U128::from(self.keeper_reward_budget))
+    }
+
+    /// This operation will register a 'new' *'Request Debit'* with this bank. The RequestDebit will need to be approved before it can be 'debited' 
+    /// each debit is drawn from the given 'pot' or the 'general' pot if none is given 
+    /// This operation is [**governed**] - [BARRING], [**non-payable**]
+    /// #Return Value
+    #[allow(clippy::too_many_arguments)]
+    pub fn register_request_debit(&mut self, 
+                                    payee           : String,
+                                    description     : String, 
+                                    amount          : U128, 
+                                    payout_interval : I64, 
+                                    start_date      : I64, 
+                                    end_date        : I64, 
+                                    pot             : Option<String>,
+                                    annotation      : Option<PaymentAnnotation>,
+                                    nonce : U64)-> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        let security_response = self.is_secure(pot.get_operation("register_request_debit"), "BARRED".to_string());
+        self.require(security_response, format!("REQUEST DEBIT REGISTRATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+        
+        self.check_payment_annotation(&annotation);
+        OpenBank::check_description(&description);
+
+        let debit_amount = u128::from(amount);
+
+        let mut request_debit = ob_io::RequestDebit::create_request_debit(payee, debit_amount, description, i64::from(payout_interval), i64::from(start_date), i64::from(end_date), signer_account_id);
+        // the annotation is carried to each Payment of the RequestDebit
+        request_debit.annotation = annotation.unwrap_or_default();
+        request_debit.pot = pot.name;
+        let rd_clone = request_debit.clone();
+        let rd_reference = request_debit.reference.clone();
+
+        self.request_debit_by_reference.insert(request_debit.reference, request_debit);
+        
+        if !self.request_debits_by_status.contains_key( &rd_clone.status) {
+            let mut debit_list = HashSet::<RequestDebit>::new(); 
+            let status = rd_clone.status.clone();
+            debit_list.insert(rd_clone);
+            self.request_debits_by_status.insert(status,debit_list);
+        }
+        else {
+            self.request_debits_by_status.get_mut(&rd_clone.status).unwrap().insert(rd_clone);
+        }
+        U64(rd_reference)
+    }
+
+    /// This operation will 'approve' the 'RequestDebit' associated with the given 'request_debit_ref'. Once approved the 'RequestDebit can be drawn down after the start date 
+    /// This operation is [**governed**], [**non-payable**] 
+    /// # Return Value
+    pub fn approve_request_debit(&mut self, request_debit_ref : U64, nonce: U64) -> U64 {
+        
+        self.check_nonce(u64::from(nonce));
+
+        // do security
+        let signer_account_id = env::signer_account_id();
+        let security_response = self.is_secure("approve_request_debit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("REQUEST DEBIT APPROVAL STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_is_valid_request_debit_reference(u64::from(request_debit_ref));
+        
+        let request_debit = self.get_request_debit(u64::from(request_debit_ref));
+
+        self.check_request_debit_status(request_debit.status.clone(), "PENDING".to_string());
+
+        self.change_request_debit_status(request_debit, "APPROVED".to_string(), signer_account_id);
+
+        request_debit_ref
+    }
+
+    /// This operation will 'approve' each of the 'RequestDebit's associated with the given 'request_debit_refs' under a single role check
+    /// Entries that cannot be approved are skipped with a reason unless 'strict' is set, in which case the whole batch is stopped 
+    /// This operation is [**governed**], [**non-payable**] 
+    /// # Return Value
+    /// **Vec** of **RequestDebitBatchResult** structs describing the outcome for each reference presented, in the order presented
+    pub fn approve_request_debits(&mut self, request_debit_refs : Vec<U64>, strict : bool, nonce : U64) -> Vec<ob_io::RequestDebitBatchResult> {
+        
+        self.check_nonce(u64::from(nonce));
+
+        // do security
+        let signer_account_id = env::signer_account_id();
+        let security_response = self.is_secure("approve_request_debit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("BATCH REQUEST DEBIT APPROVAL STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.change_request_debit_statuses(request_debit_refs, "APPROVED".to_string(), strict, signer_account_id)
+    }
+    
+    /// This operation will 'cancel' the 'RequestDebit' associated with the given 'request_debit_ref'. Cancellation can happen at any point in the 'RequestDebit' lifecycle 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the cancelled Request Debit
+    pub fn cancel_request_debit(&mut self, request_debit_ref : U64, nonce : U64) -> U64 {
+        
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security 
+        let security_response = self.is_secure("request_debit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("REQUEST DEBIT CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_is_valid_request_debit_reference(u64::from(request_debit_ref));
+        
+        let request_debit = self.get_request_debit(u64::from(request_debit_ref));
+
+        self.change_request_debit_status(request_debit, "CANCELLED".to_string(), signer_account_id);
+
+        request_debit_ref
+    } 
+
+    /// This operation will 'cancel' each of the 'RequestDebit's associated with the given 'request_debit_refs' under a single role check
+    /// Entries that cannot be cancelled are skipped with a reason unless 'strict' is set, in which case the whole batch is stopped 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// **Vec** of **RequestDebitBatchResult** structs describing the outcome for each reference presented, in the order presented
+    pub fn cancel_request_debits(&mut self, request_debit_refs : Vec<U64>, strict : bool, nonce : U64) -> Vec<ob_io::RequestDebitBatchResult> {
+        
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security 
+        let security_response = self.is_secure("request_debit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("BATCH REQUEST DEBIT CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.change_request_debit_statuses(request_debit_refs, "CANCELLED".to_string(), strict, signer_account_id)
+    }
+
+    /// This operation will create a 'ScheduledPayout' (standing order) that pushes 'amount' to the 'payee' on the 'first_payment_date' and then at the given 'frequency' until the 'end_date' 
+    /// 'frequency' - one of 'ONCE', 'DAILY', 'WEEKLY' or 'MONTHLY'. Monthly payouts fall on the same day of the month as the first payment or the last day of shorter months 
+    /// 'end_date' - date after which no further payouts are made, zero for no end date 
+    /// Payouts are made by **process_due_scheduled_payouts** 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Scheduled Payout
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_scheduled_payout(&mut self, 
+                                    payee               : String,
+                                    description         : String, 
+                                    amount              : U128, 
+                                    frequency           : String, 
+                                    first_payment_date  : I64, 
+                                    end_date            : I64, 
+                                    pot                 : Option<String>,
+                                    nonce               : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_scheduled_payout"), "ALLOWED".to_string());
+        self.require(security_response, format!("SCHEDULED PAYOUT CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        OpenBank::check_description(&description);
+
+        if !["ONCE", "DAILY", "WEEKLY", "MONTHLY"].contains(&frequency.as_str()) {
+            panic!("UNKNOWN PAYOUT FREQUENCY {} ", frequency);
+        }
+
+        if !ob_io::is_valid_date(i64::from(first_payment_date)) {
+            panic!("INVALID SCHEDULE. FIRST PAYMENT DATE {} NOT A VALID DATE ", i64::from(first_payment_date));
+        }
+
+        if i64::from(end_date) != 0 && !ob_io::is_valid_date(i64::from(end_date)) {
+            panic!("INVALID SCHEDULE. END DATE {} NOT A VALID DATE ", i64::from(end_date));
+        }
+
+        if i64::from(end_date) != 0 && i64::from(end_date) < i64::from(first_payment_date) {
+            panic!("INVALID SCHEDULE. END DATE {} BEFORE FIRST PAYMENT DATE {} ", i64::from(end_date), i64::from(first_payment_date));
+        }
+
+        let mut scheduled_payout = ob_io::ScheduledPayout::create_scheduled_payout(payee, u128::from(amount), description, frequency, i64::from(first_payment_date), i64::from(end_date), signer_account_id);
+        scheduled_payout.pot = pot.name;
+        self.record_sequence += 1;
+        scheduled_payout.assign_sequence(self.record_sequence);
+        let sp_reference = scheduled_payout.reference;
+
+        self.scheduled_payouts.insert(sp_reference, scheduled_payout);
+
+        U64(sp_reference)
+    }
+
+    /// This operation will 'cancel' the 'ScheduledPayout' associated with the given 'scheduled_payout_ref'. No further payouts will be made against it 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the cancelled Scheduled Payout
+    pub fn cancel_scheduled_payout(&mut self, scheduled_payout_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security 
+        let security_response = self.is_secure("cancel_scheduled_payout".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SCHEDULED PAYOUT CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut scheduled_payout = self.get_scheduled_payout(u64::from(scheduled_payout_ref));
+
+        self.require(scheduled_payout.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", scheduled_payout.status));
+
+        scheduled_payout.status = "CANCELLED".to_string();
+        self.scheduled_payouts.insert(scheduled_payout.reference, scheduled_payout);
+
+        scheduled_payout_ref
+    }
+
+    /// This operation will pay every 'ACTIVE' ScheduledPayout that has reached its next payment date, up to 'limit' ScheduledPayouts or until the attached gas runs low
+    /// Each ScheduledPayout is paid at most once per call so missed payouts are caught up over successive calls. ScheduledPayouts that the bank cannot currently afford are left for a later run 
+    /// Funds are sent to the payee of each ScheduledPayout *not* the caller and each Payment carries the ScheduledPayout reference as its 'linked_reference'
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Vec** of **Payment** structs for the payouts made
+    pub fn process_due_scheduled_payouts(&mut self, limit : U64) -> Vec<ob_io::Payment> {
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("process_due_scheduled_payouts".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("SCHEDULED PAYOUT PROCESSING CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payments = Vec::<ob_io::Payment>::new();
+        for scheduled_payout in self.get_due_scheduled_payouts() {
+            if payments.len() as u64 >= u64::from(limit) || !self.has_gas_for_crank_step() {
+                break;
+            }
+            if !self.can_afford_from_pot(&scheduled_payout.pot, scheduled_payout.amount) {
+                continue;
+            }
+            payments.push(self.settle_scheduled_payout(scheduled_payout, signer_account_id.clone()));
+        }
+        payments
+    }
+
+    /// This operation will create a 'DRAFT' PayrollRun paying each of the 'lines' in the order given. Unlike **pay_out_multi** identical lines are kept as separate payments 
+    /// The PayrollRun must be approved before it can be executed and each line is drawn from the given 'pot' or the 'general' pot if none is given 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Payroll Run
+    pub fn create_payroll_run(&mut self, description : String, lines : Vec<MultiPaymentRequest>, pot : Option<String>, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_payroll_run"), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYROLL RUN CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(!lines.is_empty(), "PAYROLL RUN CREATION CANCELLED. NO LINES PROVIDED".to_string());
+        for line in lines.iter() {
+            OpenBank::check_description(&line.description);
+            self.require(line.payout_amount > 0, format!("PAYROLL RUN CREATION CANCELLED. ZERO AMOUNT LINE FOR {}", line.payee_account_id));
+        }
+
+        let mut payroll_run = ob_io::PayrollRun::create_payroll_run(description, lines, signer_account_id);
+        payroll_run.pot = pot.name;
+        self.record_sequence += 1;
+        payroll_run.assign_sequence(self.record_sequence);
+        let pr_reference = payroll_run.reference;
+
+        self.payroll_runs.insert(pr_reference, payroll_run);
+
+        U64(pr_reference)
+    }
+
+    /// This operation will 'approve' the 'DRAFT' PayrollRun associated with the given 'payroll_run_ref'. Once approved the PayrollRun can be executed 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the approved Payroll Run
+    pub fn approve_payroll_run(&mut self, payroll_run_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("approve_payroll_run".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYROLL RUN APPROVAL STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payroll_run = self.get_payroll_run(u64::from(payroll_run_ref));
+
+        self.require(payroll_run.status == "DRAFT", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : DRAFT, ACTUAL STATUS : {} ", payroll_run.status));
+
+        payroll_run.status = "APPROVED".to_string();
+        payroll_run.approved_by = signer_account_id;
+        self.payroll_runs.insert(payroll_run.reference, payroll_run);
+
+        payroll_run_ref
+    }
+
+    /// This operation will 'cancel' the PayrollRun associated with the given 'payroll_run_ref'. Lines that have already been paid are unaffected 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the cancelled Payroll Run
+    pub fn cancel_payroll_run(&mut self, payroll_run_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("cancel_payroll_run".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYROLL RUN CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payroll_run = self.get_payroll_run(u64::from(payroll_run_ref));
+
+        self.require(payroll_run.status == "DRAFT" || payroll_run.status == "APPROVED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : DRAFT OR APPROVED, ACTUAL STATUS : {} ", payroll_run.status));
+
+        payroll_run.status = "CANCELLED".to_string();
+        self.payroll_runs.insert(payroll_run.reference, payroll_run);
+
+        payroll_run_ref
+    }
+
+    /// This operation will pay the outstanding lines of the 'APPROVED' PayrollRun associated with the given 'payroll_run_ref' in order, up to 'limit' lines or until the attached gas runs low 
+    /// Lines that the bank cannot currently afford, or that would take the bank below its minimum reserve, are marked 'FAILED' and retried on the next execution. Once every line has been paid the PayrollRun becomes 'EXECUTED'
+    /// Each Payment carries the PayrollRun reference as its 'linked_reference'
+    /// This operation is [**governed**] within the namespace of the pot the PayrollRun draws on, [**non-payable**]
+    /// # Return Value
+    /// **PayrollRun** struct with the status of each line after this execution 
+    pub fn execute_payroll_run(&mut self, payroll_run_ref : U64, limit : U64, nonce : U64) -> ob_io::PayrollRun {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let mut payroll_run = self.get_payroll_run(u64::from(payroll_run_ref));
+        let pot = self.get_pot(&payroll_run.pot);
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("execute_payroll_run"), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYROLL RUN EXECUTION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(payroll_run.status == "APPROVED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : APPROVED, ACTUAL STATUS : {} ", payroll_run.status));
+
+        for line in payroll_run.lines.iter_mut().filter(|line| line.status != "PAID").take(u64::from(limit) as usize) {
+            if !self.has_gas_for_crank_step() {
+                break;
+            }
+            line.attempts += 1;
+
+            if !self.can_afford_from_pot(&payroll_run.pot, line.amount) {
+                line.status = "FAILED".to_string();
+                line.failure_reason = format!("INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", line.amount, self.get_pot(&payroll_run.pot).balance);
+                continue;
+            }
+            if !self.is_above_minimum_reserve(line.amount) {
+                line.status = "FAILED".to_string();
+                line.failure_reason = format!("MINIMUM RESERVE BREACHED. REQUIRED AMOUNT: {} BALANCE: {} MINIMUM RESERVE: {}", line.amount, self.bank_balance, self.treasury_policy.minimum_reserve);
+                continue;
+            }
+
+            self.decrement_pot_balance(&payroll_run.pot, line.amount, "EXPENSES");
+            let payment = self.pay_to(line.payee.clone(), 
+                                    signer_account_id.clone(), 
+                                    line.amount, 
+                                    line.description.clone(), 
+                                    "PAYROLL".to_string());
+            let payment = self.link_payment(payment, payroll_run.reference);
+
+            line.status = "PAID".to_string();
+            line.failure_reason = "".to_string();
+            line.payment_reference = payment.reference;
+            payroll_run.paid_total += line.amount;
+        }
+
+        if payroll_run.lines.iter().all(|line| line.status == "PAID") {
+            payroll_run.status = "EXECUTED".to_string();
+        }
+        self.payroll_runs.insert(payroll_run.reference, payroll_run.clone());
+
+        payroll_run
+    }
+
+    /// This operation will refund the given 'amount' of the 'PAY_IN' with the given 'payment_ref' back to its payer. Partial refunds are allowed 
+    /// but the total refunded can not exceed what the original pay in credited net of its fee and of any revenue split legs paid or accrued from it. The total refunded is kept on the original Payment. Pay ins against an invoice can not be refunded 
+    /// The refund is drawn from the pot the pay in went to and can not take the bank below its minimum reserve 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the refund, carrying the original pay in reference as its 'linked_reference'
+    pub fn refund_payment(&mut self, payment_ref : U64, amount : U128, reason : String, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("refund".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("REFUND STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut original_payment = self.get_payment(u64::from(payment_ref));
+        let refund_amount = u128::from(amount);
+
+        self.require(original_payment.payment_type == "PAY_IN", format!("REFUND STOPPED. PAYMENT {} IS NOT A PAY IN. PAYMENT TYPE : {} ", original_payment.reference, original_payment.payment_type));
+        self.require(!self.invoices.contains_key(&original_payment.linked_reference), format!("REFUND STOPPED. PAYMENT {} PAYS INVOICE {} ", original_payment.reference, original_payment.linked_reference));
+        self.require(refund_amount > 0, "REFUND STOPPED. ZERO REFUND AMOUNT".to_string());
+
+        let split_total : u128 = self.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == original_payment.reference).map(|p| p.amount).sum();
+        let refundable_amount = original_payment.net_amount.saturating_sub(split_total + original_payment.refunded_amount);
+        self.require(refund_amount <= refundable_amount, format!("REFUND STOPPED. REFUND AMOUNT {} EXCEEDS REFUNDABLE AMOUNT {}", refund_amount, refundable_amount));
+
+        // check the balance of the pot the pay in went to 
+        self.check_pot_balance(&original_payment.pot, refund_amount);
+        self.check_minimum_reserve(refund_amount);
+        self.decrement_pot_balance(&original_payment.pot, refund_amount, "REVENUE");
+
+        original_payment.refunded_amount += refund_amount;
+        self.update_payment(original_payment.clone());
+
+        let payment = self.pay_to(original_payment.payer, 
+                                signer_account_id, 
+                                refund_amount, 
+                                reason, 
+                                "REFUND".to_string());
+        self.link_payment(payment, original_payment.reference)
+    }
+
+    /// This operation will charge the given 'amount' against the credit of the given 'account_id'. No funds are moved, the charged amount leaves the credit of the account and is added to the bank balance 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct describing the charge 
+    pub fn charge_credit(&mut self, account_id : String, amount : U128, memo : String, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("charge_credit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("CREDIT CHARGE STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let charge_amount = u128::from(amount);
+        self.require(charge_amount > 0, "CREDIT CHARGE STOPPED. ZERO CHARGE AMOUNT".to_string());
+
+        self.remove_customer_credit(account_id.clone(), charge_amount);
+        self.recognise_credit(charge_amount);
+
+        self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                        account_id, 
+                                        signer_account_id, 
+                                        charge_amount, 
+                                        memo, 
+                                        "COMPLETED".to_string(), 
+                                        "CREDIT_CHARGE".to_string())
+    }
+
+    /// This operation will pay the given 'amount' of the credit of the signer back to the signer 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the withdrawal 
+    pub fn withdraw_credit(&mut self, amount : U128, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("withdraw_credit".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("CREDIT WITHDRAWAL CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let withdrawal_amount = u128::from(amount);
+        self.require(withdrawal_amount > 0, "CREDIT WITHDRAWAL CANCELLED. ZERO WITHDRAWAL AMOUNT".to_string());
+
+        self.remove_customer_credit(signer_account_id.clone(), withdrawal_amount);
+        self.release_held_funds(withdrawal_amount, "PAYABLES");
+
+        self.pay_to(signer_account_id.clone(), 
+                    signer_account_id, 
+                    withdrawal_amount, 
+                    "credit withdrawal".to_string(), 
+                    "CREDIT_WITHDRAWAL".to_string())
+    }
+
+    /// This operation will create a SubscriptionPlan charging 'price' for each 'period' millis, with subscriptions that can not be renewed remaining usable for 'grace_period' millis 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Subscription Plan
+    pub fn create_subscription_plan(&mut self, name : String, price : U128, period : I64, grace_period : I64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("create_subscription_plan".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SUBSCRIPTION PLAN CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let subscription_plan = ob_io::SubscriptionPlan::create_subscription_plan(name, u128::from(price), i64::from(period), i64::from(grace_period), signer_account_id);
+
+        self.require(subscription_plan.price > 0 && subscription_plan.period > 0 && subscription_plan.grace_period >= 0, format!("INVALID PLAN TERMS. PRICE {}, PERIOD {}, GRACE PERIOD {}", subscription_plan.price, subscription_plan.period, subscription_plan.grace_period));
+
+        let plan_reference = subscription_plan.reference;
+        self.subscription_plans.insert(plan_reference, subscription_plan);
+
+        U64(plan_reference)
+    }
+
+    /// This operation will subscribe the signer to the SubscriptionPlan associated with the given 'subscription_plan_ref'. The first period is charged against the credit of the signer straight away 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Subscription** struct describing the new subscription 
+    pub fn subscribe(&mut self, subscription_plan_ref : U64, nonce : U64) -> ob_io::Subscription {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("subscribe".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("SUBSCRIPTION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let subscription_plan = self.find_subscription_plan(subscription_plan_ref);
+
+        let already_subscribed = self.subscriptions.values().any(|sub| sub.subscriber == signer_account_id && sub.plan_reference == subscription_plan.reference && sub.status == "ACTIVE");
+        self.require(!already_subscribed, format!("SUBSCRIPTION CANCELLED. ACCOUNT {} IS ALREADY SUBSCRIBED TO PLAN {}", signer_account_id, subscription_plan.reference));
+
+        let mut subscription = ob_io::Subscription::create_subscription(&subscription_plan, signer_account_id.clone());
+        self.record_sequence += 1;
+        subscription.assign_sequence(self.record_sequence);
+        let subscription_ref = U64(subscription.reference);
+        self.renew_subscription(subscription, signer_account_id);
+
+        self.get_subscription(u64::from(subscription_ref))
+    }
+
+    /// This operation will cancel the 'ACTIVE' Subscription associated with the given 'subscription_ref'. Only the subscriber can cancel and the subscription remains usable until the end of the paid period 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// Reference of the cancelled Subscription 
+    pub fn cancel_subscription(&mut self, subscription_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("cancel_subscription".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("SUBSCRIPTION CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut subscription = self.get_subscription(u64::from(subscription_ref));
+
+        self.require(subscription.subscriber == signer_account_id, format!("SUBSCRIPTION CANCELLATION STOPPED. ACCOUNT {} IS NOT THE SUBSCRIBER", signer_account_id));
+        self.require(subscription.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", subscription.status));
+
+        subscription.status = "CANCELLED".to_string();
+        self.subscriptions.insert(subscription.reference, subscription);
+
+        subscription_ref
+    }
+
+    /// This operation enables keepers to renew up to 'limit' 'ACTIVE' Subscriptions whose paid period has ended, oldest first. Each renewal is charged against the credit of the subscriber, 
+    /// subscriptions that can not be renewed are left in their grace period and lapse once it has passed 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Vec** of **Payment** structs for the renewals made 
+    pub fn process_due_subscriptions(&mut self, limit : U64) -> Vec<ob_io::Payment> {
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("process_due_subscriptions".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("SUBSCRIPTION PROCESSING CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let time_now = env::block_timestamp() as i64;
+        let mut payments = Vec::<ob_io::Payment>::new();
+        for mut subscription in self.get_due_subscriptions().into_iter().take(u64::from(limit) as usize) {
+            if !self.has_gas_for_crank_step() {
+                break;
+            }
+            if self.get_customer_credit(&subscription.subscriber) >= subscription.price {
+                payments.push(self.renew_subscription(subscription, signer_account_id.clone()));
+            }
+            else if time_now >= subscription.paid_until + subscription.grace_period {
+                subscription.status = "LAPSED".to_string();
+                self.subscriptions.insert(subscription.reference, subscription);
+            }
+        }
+        payments
+    }
+
+    /// This operation will set the given revenue split rule, replacing any 'ACTIVE' rule for the same match. Matching pay ins are split between the bank and the payees of the legs 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the Split Rule
+    pub fn set_split_rule(&mut self, split_rule : SplitRuleRequest, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_split_rule".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SPLIT RULE SETTING CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_split_rule_request(&split_rule);
+
+        let split_rule = ob_io::SplitRule::create_split_rule(split_rule, signer_account_id);
+        let match_key = OpenBank::get_split_match_key(&split_rule.match_type, &split_rule.match_value);
+
+        if let Some(previous_reference) = self.active_split_rules.get(&match_key) {
+            let mut previous = self.split_rules.get(previous_reference).unwrap().clone();
+            previous.status = "REPLACED".to_string();
+            self.split_rules.insert(previous.reference, previous);
+        }
+
+        let sr_reference = split_rule.reference;
+        self.active_split_rules.insert(match_key, sr_reference);
+        self.split_rules.insert(sr_reference, split_rule);
+
+        U64(sr_reference)
+    }
+
+    /// This operation will remove the 'ACTIVE' SplitRule associated with the given 'split_rule_ref', matching pay ins are no longer split 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the removed Split Rule
+    pub fn remove_split_rule(&mut self, split_rule_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_split_rule".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SPLIT RULE REMOVAL STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut split_rule = self.get_split_rule(u64::from(split_rule_ref));
+
+        self.require(split_rule.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", split_rule.status));
+
+        self.active_split_rules.remove(&OpenBank::get_split_match_key(&split_rule.match_type, &split_rule.match_value));
+        split_rule.status = "REMOVED".to_string();
+        self.split_rules.insert(split_rule.reference, split_rule);
+
+        split_rule_ref
+    }
+
+    /// This operation will pay up to 'limit' payees the revenue accrued to them by 'ACCRUED' split rules, in payee order 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Vec** of **Payment** structs for the distributions made 
+    pub fn distribute_split_accruals(&mut self, limit : U64, nonce : U64) -> Vec<ob_io::Payment> {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("distribute_split_accruals".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SPLIT DISTRIBUTION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payments = Vec::<ob_io::Payment>::new();
+        for (payee, amount) in self.get_split_accruals().into_iter().take(u64::from(limit) as usize) {
+            if !self.has_gas_for_crank_step() {
+                break;
+            }
+            let amount = u128::from(amount);
+            self.split_accruals.remove(&payee);
+            self.split_balance -= amount;
+            self.release_held_funds(amount, "PAYABLES");
+
+            payments.push(self.pay_to(payee, 
+                                    signer_account_id.clone(), 
+                                    amount, 
+                                    "revenue split distribution".to_string(), 
+                                    "REVENUE_SPLIT_DISTRIBUTION".to_string()));
+        }
+        payments
+    }
+
+    /// This operation will replace the fee schedule of this bank with the given 'fee_schedule'. Every change is logged 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **FeeScheduleChange** struct describing the change 
+    pub fn set_fee_schedule(&mut self, fee_schedule : FeeSchedule, nonce : U64) -> ob_io::FeeScheduleChange {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_fee_schedule".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("FEE SCHEDULE CHANGE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        for fee_rate in [&fee_schedule.pay_in, &fee_schedule.request_debit, &fee_schedule.pay_out] {
+            self.require(fee_rate.basis_points <= 10_000, format!("INVALID FEE RATE. BASIS POINTS {} ", fee_rate.basis_points));
+        }
+
+        let fee_schedule_change = ob_io::FeeScheduleChange {
+            previous        : self.fee_schedule.clone(),
+            current         : fee_schedule.clone(),
+            changed_by      : signer_account_id,
+            change_date     : env::block_timestamp() as i64,
+        };
+        self.fee_schedule = fee_schedule;
+        self.fee_schedule_changes.push(fee_schedule_change.clone());
+
+        fee_schedule_change
+    }
+
+    /// This operation will pay the given 'amount' of the fees charged by this bank to the given 'account_id' 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the withdrawal 
+    pub fn withdraw_fees(&mut self, amount : U128, account_id : String, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("withdraw_fees".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("FEE WITHDRAWAL CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let withdrawal_amount = u128::from(amount);
+        self.require(withdrawal_amount > 0 && withdrawal_amount <= self.fee_balance, format!("INSUFFICIENT FEES AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", withdrawal_amount, self.fee_balance));
+
+        self.fee_balance -= withdrawal_amount;
+        self.release_held_funds(withdrawal_amount, "FEES");
+
+        self.pay_to(account_id, 
+                    signer_account_id, 
+                    withdrawal_amount, 
+                    "fee withdrawal".to_string(), 
+                    "FEE_WITHDRAWAL".to_string())
+    }
+
+    /// This operation will replace the treasury policy of this bank with the given 'treasury_policy'
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **TreasuryPolicy** struct with the policy now in force 
+    pub fn set_treasury_policy(&mut self, treasury_policy : TreasuryPolicy, nonce : U64) -> ob_io::TreasuryPolicy {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_treasury_policy".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("TREASURY POLICY CHANGE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(treasury_policy.maximum_balance == 0 || treasury_policy.maximum_balance > treasury_policy.minimum_reserve, format!("INVALID TREASURY POLICY. MAXIMUM BALANCE {} NOT ABOVE MINIMUM RESERVE {}", treasury_policy.maximum_balance, treasury_policy.minimum_reserve));
+
+        self.treasury_policy = treasury_policy;
+        self.treasury_policy.clone()
+    }
+
+    /// This operation will add a category with the given 'code' to the chart of categories payments can be given 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **PaymentCategory** struct describing the category 
+    pub fn add_payment_category(&mut self, code : String, description : String, nonce : U64) -> ob_io::PaymentCategory {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("add_payment_category".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT CATEGORY CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(!code.is_empty(), "PAYMENT CATEGORY CREATION CANCELLED. CODE REQUIRED".to_string());
+        self.require(!self.payment_categories.contains_key(&code), format!("PAYMENT CATEGORY CREATION CANCELLED. CATEGORY {} ALREADY EXISTS", code));
+
+        let payment_category = ob_io::PaymentCategory::create_payment_category(code, description, signer_account_id);
+        self.payment_categories.insert(payment_category.code.clone(), payment_category.clone());
+        payment_category
+    }
+
+    /// This operation will retire the category with the given 'code' so that it can no longer be given to payments. Payments already in the category keep it 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **PaymentCategory** struct describing the retired category 
+    pub fn retire_payment_category(&mut self, code : String, nonce : U64) -> ob_io::PaymentCategory {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("retire_payment_category".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT CATEGORY RETIREMENT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let payment_category = match self.payment_categories.get_mut(&code) {
+            Some(payment_category) => payment_category,
+            None => panic!("UNKNOWN PAYMENT CATEGORY {} ", code),
+        };
+        payment_category.status = "RETIRED".to_string();
+        payment_category.clone()
+    }
+
+    /// This operation will set when statement periods are due to close. The current period is due at the 'first_close_time' and each later period one 'frequency' after the one before, 
+    /// where the 'frequency' is one of 'NONE', 'DAILY', 'WEEKLY' or 'MONTHLY'. With a 'frequency' of 'NONE' periods are only closed by **close_statement_period** 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **StatementSchedule** struct with the schedule now in force 
+    pub fn set_statement_schedule(&mut self, frequency : String, first_close_time : I64, nonce : U64) -> ob_io::StatementSchedule {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_statement_schedule".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("STATEMENT SCHEDULE CHANGE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        if !["NONE", "DAILY", "WEEKLY", "MONTHLY"].contains(&frequency.as_str()) {
+            panic!("UNKNOWN STATEMENT FREQUENCY {} ", frequency);
+        }
+        let first_close_time = i64::from(first_close_time);
+        self.require(frequency == "NONE" || ob_io::is_valid_date(first_close_time), format!("STATEMENT SCHEDULE CHANGE CANCELLED. FIRST CLOSE TIME {} NOT A VALID DATE", first_close_time));
+        self.require(frequency == "NONE" || first_close_time > self.open_statement_period.opening_time, format!("STATEMENT SCHEDULE CHANGE CANCELLED. FIRST CLOSE TIME {} NOT AFTER OPENING TIME {} OF STATEMENT PERIOD {}", first_close_time, self.open_statement_period.opening_time, self.open_statement_period.number));
+
+        self.statement_schedule = ob_io::StatementSchedule {
+            frequency,
+            first_close_time,
+            first_period_number : self.open_statement_period.number,
+        };
+        self.open_statement_period.close_due_time = self.statement_schedule.get_close_time(self.open_statement_period.number);
+        self.statement_schedule.clone()
+    }
+
+    /// This operation will close the current statement period now, recording its closing balance and the sequence range of its Payments and journal entries, and open the next 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **StatementPeriod** struct describing the closed period 
+    pub fn close_statement_period(&mut self, nonce : U64) -> ob_io::StatementPeriod {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("close_statement_period".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("STATEMENT PERIOD CLOSE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.close_open_statement_period(signer_account_id, env::block_timestamp() as i64)
+    }
+
+    /// This operation will close the current statement period if it is due to close under the statement schedule. The period is closed at its due time, 
+    /// the next period keeps to the schedule and missed closes are caught up over successive calls. A Payment made after a period is due closes it first, so a period never takes in later Payments 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **StatementPeriod** struct describing the closed period, if the current period was due 
+    pub fn process_due_statement_period(&mut self) -> Option<ob_io::StatementPeriod> {
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("process_due_statement_period".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("STATEMENT PERIOD PROCESSING CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let close_due_time = self.open_statement_period.close_due_time;
+        if close_due_time == 0 || close_due_time > env::block_timestamp() as i64 {
+            return None;
+        }
+        Some(self.close_open_statement_period(signer_account_id, close_due_time))
+    }
+
+    /// This operation will audit the balance of this bank. The balance is rebuilt from the opening balance and the Payments made, carrying on from the last audit checkpoint over at most 'limit' Payments, 
+    /// and compared with the bank balance once the Payments have been audited up to the latest. Each Payment registered is looked up in the Payment records and 
+    /// the balance of the account this bank is deployed to is compared with the funds this bank holds. 
+    /// In 'strict' mode a mismatch pauses all outflows until they are resumed with **resume_outflows** 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **AuditReport** struct describing any differences found 
+    pub fn audit(&mut self, limit : U64, strict : bool, nonce : U64) -> ob_io::AuditReport {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("audit".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("AUDIT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        // carry the rebuilt balance forward from the checkpoint
+        let from = self.audit_checkpoint.payment_sequence as usize;
+        let to = (self.payment_references.len() as u64).min((from as u64).saturating_add(u64::from(limit))) as usize;
+        let mut rebuilt_balance = self.audit_checkpoint.rebuilt_balance;
+        let mut missing_payments = self.audit_checkpoint.missing_payments;
+        for (index, reference) in self.payment_references[from..to].iter().enumerate() {
+            match self.payments_by_reference.get(reference) {
+                Some(payment) if payment.sequence == (from + index + 1) as u64 && self.payments.contains(payment) => rebuilt_balance += payment.get_balance_movement(),
+                _ => missing_payments += 1,
+            }
+        }
+        self.audit_checkpoint = ob_io::AuditCheckpoint {
+            payment_sequence    : to as u64,
+            rebuilt_balance,
+            missing_payments,
+            checkpoint_time     : env::block_timestamp() as i64,
+        };
+        let complete = to as u64 == self.payment_sequence;
+
+        // the part of the account balance locked for storage is not held by the bank 
+        let held_balance = self.bank_balance + self.get_outstanding_obligations();
+        let account_balance = env::account_balance();
+        let storage_cost = env::storage_usage() as u128 * env::storage_byte_cost();
+
+        let mut audit_report = ob_io::AuditReport {
+            audit_time                  : env::block_timestamp() as i64,
+            complete,
+            audited_payment_sequence    : to as u64,
+            recomputed_balance          : rebuilt_balance,
+            bank_balance                : self.bank_balance,
+            ledger_difference           : if complete { self.bank_balance as i128 - rebuilt_balance } else { 0 },
+            payment_difference          : missing_payments as i64 + self.payment_sequence as i64 - self.payment_references.len() as i64,
+            held_balance,
+            account_balance,
+            storage_cost,
+            account_difference          : account_balance as i128 - storage_cost as i128 - held_balance as i128,
+            ..Default::default()
+        };
+        audit_report.mismatch = audit_report.ledger_difference != 0 || audit_report.payment_difference != 0 || audit_report.account_difference != 0;
+
+        if strict && audit_report.mismatch && !self.outflows_paused {
+            self.outflows_paused = true;
+            env::log(format!("OUTFLOWS PAUSED. LEDGER DIFFERENCE: {} PAYMENT DIFFERENCE: {} ACCOUNT DIFFERENCE: {}", audit_report.ledger_difference, audit_report.payment_difference, audit_report.account_difference).as_bytes());
+        }
+        audit_report.outflows_paused = self.outflows_paused;
+        audit_report
+    }
+
+    /// This operation will resume the outflows paused by a strict **audit** 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// *false* as outflows are no longer paused 
+    pub fn resume_outflows(&mut self, nonce : U64) -> bool {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("resume_outflows".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("OUTFLOW RESUMPTION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.outflows_paused = false;
+        self.outflows_paused
+    }
+
+    /// This operation will switch privacy mode on or off. In privacy mode full Payment and RequestDebit records are only shown to their parties and accounts allowed to 'view_payments' 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// *true* if and only if this bank is now in privacy mode 
+    pub fn set_privacy_mode(&mut self, privacy_mode : bool, nonce : U64) -> bool {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_privacy_mode".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PRIVACY MODE CHANGE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.privacy_mode = privacy_mode;
+        self.privacy_mode
+    }
+
+    /// This operation will set the base64 x25519 public key payers seal encrypted memos for. Memos sealed for an earlier key are kept but no longer accepted 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// the memo public key now in force 
+    pub fn set_memo_public_key(&mut self, memo_public_key : String, nonce : U64) -> String {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_memo_public_key".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("MEMO PUBLIC KEY CHANGE CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(OpenBank::get_decoded_length(&memo_public_key) == Some(MEMO_KEY_LENGTH), format!("INVALID MEMO PUBLIC KEY {} ", memo_public_key));
+
+        self.memo_public_key = memo_public_key;
+        self.memo_public_key.clone()
+    }
+
+    /// This operation will create an empty pot i.e. an internal budget, with the given 'name'. Operations on the pot are governed in the given 'operation_namespace' 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Pot** struct describing the created pot 
+    pub fn create_pot(&mut self, name : String, operation_namespace : String, nonce : U64) -> ob_io::Pot {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("create_pot".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("POT CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(!name.is_empty() && !operation_namespace.is_empty(), "POT CREATION CANCELLED. NAME AND OPERATION NAMESPACE REQUIRED".to_string());
+        self.require(!self.pots.contains_key(&name), format!("POT CREATION CANCELLED. POT {} ALREADY EXISTS", name));
+
+        let pot = ob_io::Pot::create_pot(name, operation_namespace, signer_account_id);
+        self.pots.insert(pot.name.clone(), pot.clone());
+        pot
+    }
+
+    /// This operation will move the given 'amount' from the 'from_pot' to the 'to_pot'. The bank balance is unchanged. 
+    /// The move is governed by the 'internal_transfer' operation in the namespace of the 'from_pot' 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct of type 'INTERNAL_TRANSFER' with the 'from_pot' as payer and the 'to_pot' as payee 
+    pub fn transfer_between_pots(&mut self, from_pot : String, to_pot : String, amount : U128, description : String, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let from = self.get_pot(&from_pot);
+        let to = self.get_pot(&to_pot);
+
+        // do security
+        let security_response = self.is_secure(from.get_operation("internal_transfer"), "ALLOWED".to_string());
+        self.require(security_response, format!("INTERNAL TRANSFER CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(from.name != to.name, "INTERNAL TRANSFER CANCELLED. SAME POT".to_string());
+
+        let transfer_amount = u128::from(amount);
+        self.require(transfer_amount > 0 && transfer_amount <= from.balance, format!("INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", transfer_amount, from.balance));
+
+        self.get_pot_mut(&from.name).balance -= transfer_amount;
+        self.get_pot_mut(&to.name).balance += transfer_amount;
+
+        self.create_and_register_payment(to.name, 
+                                        from.name, 
+                                        signer_account_id, 
+                                        transfer_amount, 
+                                        description, 
+                                        "COMPLETED".to_string(), 
+                                        "INTERNAL_TRANSFER".to_string())
+    }
+
+    /// This operation will raise an Invoice on the terms of the given 'invoice' to be settled with 'pay invoice' 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Invoice
+    pub fn create_invoice(&mut self, invoice : InvoiceRequest, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("create_invoice".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("INVOICE CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        if invoice.overpayment_action != "REFUND" && invoice.overpayment_action != "CREDIT" {
+            panic!("UNKNOWN OVERPAYMENT ACTION {} ", invoice.overpayment_action);
+        }
+        self.require(invoice.amount > 0, "INVOICE CREATION CANCELLED. ZERO INVOICE AMOUNT".to_string());
+        OpenBank::check_description(&invoice.description);
+
+        let mut invoice = ob_io::Invoice::create_invoice(invoice, signer_account_id);
+        self.record_sequence += 1;
+        invoice.assign_sequence(self.record_sequence);
+        let inv_reference = invoice.reference;
+
+        self.invoices.insert(inv_reference, invoice);
+
+        U64(inv_reference)
+    }
+
+    /// This operation will *'pay in'* the attached funds against the Invoice associated with the given 'invoice_ref', marking it 'PARTIALLY_PAID' or 'PAID'. 
+    /// Any amount over what is owed is refunded to the payer or kept as credit for the payer according to the overpayment action of the invoice, an amount kept as credit is registered as a 'CREDIT_PAY_IN' of its own 
+    /// The pay in fee of the fee schedule is charged on what is applied to the invoice and on any amount kept as credit 
+    /// [**governed**] - [BARRING], [**payable**]
+    /// # Return Value 
+    /// **Payment** struct containing details of the "pay in" made, carrying the Invoice reference as its 'linked_reference' 
+    #[payable]
+    pub fn pay_invoice(&mut self, invoice_ref : U64, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("pay_invoice".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("INVOICE PAYMENT NOT ALLOWED. ACCOUNT {} BARRED", signer_account_id));
+
+        let mut invoice = self.get_invoice(u64::from(invoice_ref));
+
+        self.require(invoice.is_open(), format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : OPEN OR PARTIALLY_PAID, ACTUAL STATUS : {} ", invoice.status));
+        if let Some(payer) = &invoice.payer {
+            self.require(*payer == signer_account_id, format!("INVOICE PAYMENT NOT ALLOWED. ACCOUNT {} IS NOT THE PAYER", signer_account_id));
+        }
+
+        let attached_amount = env::attached_deposit();
+        self.require(attached_amount > 0, "INVOICE PAYMENT NOT ALLOWED. NO DEPOSIT ATTACHED".to_string());
+
+        let applied_amount = attached_amount.min(invoice.get_outstanding_amount());
+        let overpaid_amount = attached_amount - applied_amount;
+        let keep_credit = overpaid_amount > 0 && invoice.overpayment_action == "CREDIT";
+
+        // charge the fee, only what is owed net of the fee enters the bank balance
+        let fee = self.fee_schedule.pay_in.get_fee(applied_amount);
+        self.charge_fee(fee);
+        self.increment_bank_balance(applied_amount - fee, "REVENUE");
+
+        // an overpayment kept as credit is a pay in of its own so that the invoice pay in can not refund it again 
+        let pay_in_amount = if keep_credit { applied_amount } else { attached_amount };
+        let payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                        signer_account_id.clone(), 
+                                                        signer_account_id.clone(), 
+                                                        pay_in_amount, 
+                                                        invoice.description.clone(), 
+                                                        "COMPLETED".to_string(), 
+                                                        "PAY_IN".to_string());
+        let payment = self.record_fee(payment, fee);
+        let mut payment = self.link_payment(payment, invoice.reference);
+
+        invoice.paid_amount += applied_amount;
+        invoice.payment_references.push(payment.reference);
+        if invoice.get_outstanding_amount() == 0 {
+            invoice.paid_date = env::block_timestamp() as i64;
+            invoice.status = "PAID".to_string();
+        }
+        else {
+            invoice.status = "PARTIALLY_PAID".to_string();
+        }
+
+        if keep_credit {
+            let credit_fee = self.fee_schedule.pay_in.get_fee(overpaid_amount);
+            self.charge_fee(credit_fee);
+            self.add_customer_credit(signer_account_id.clone(), overpaid_amount - credit_fee);
+            self.hold_funds(overpaid_amount - credit_fee, "PAYABLES");
+
+            let credit_payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                                signer_account_id.clone(), 
+                                                                signer_account_id, 
+                                                                overpaid_amount, 
+                                                                format!("overpayment of invoice {}", invoice.external_reference), 
+                                                                "COMPLETED".to_string(), 
+                                                                "CREDIT_PAY_IN".to_string());
+            let credit_payment = self.record_fee(credit_payment, credit_fee);
+            self.link_payment(credit_payment, invoice.reference);
+        }
+        else if overpaid_amount > 0 {
+            payment.refunded_amount = overpaid_amount;
+            self.update_payment(payment.clone());
+            let refund = self.pay_to(signer_account_id.clone(), 
+                                    signer_account_id, 
+                                    overpaid_amount, 
+                                    format!("overpayment of invoice {}", invoice.external_reference), 
+                                    "REFUND".to_string());
+            self.link_payment(refund, payment.reference);
+        }
+        self.invoices.insert(invoice.reference, invoice);
+        self.sweep_excess_balance(DEFAULT_POT);
+
+        payment
+    }
+
+    /// This operation will create a SupplierContract that pays the 'supplier' for each of the given 'milestones' once it has been delivered and accepted 
+    /// each milestone is paid from the given 'pot' or the 'general' pot if none is given 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Supplier Contract
+    pub fn create_supplier_contract(&mut self, supplier : String, description : String, milestones : Vec<MilestoneRequest>, pot : Option<String>, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_supplier_contract"), "ALLOWED".to_string());
+        self.require(security_response, format!("SUPPLIER CONTRACT CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.require(!milestones.is_empty(), "SUPPLIER CONTRACT CREATION CANCELLED. NO MILESTONES".to_string());
+        self.require(milestones.iter().all(|m| m.amount > 0), "SUPPLIER CONTRACT CREATION CANCELLED. ZERO AMOUNT MILESTONE".to_string());
+        for milestone in milestones.iter() {
+            OpenBank::check_description(&milestone.description);
+        }
+
+        let mut supplier_contract = ob_io::SupplierContract::create_supplier_contract(supplier, description, milestones, signer_account_id);
+        supplier_contract.pot = pot.name;
+        let sc_reference = supplier_contract.reference;
+
+        for milestone in supplier_contract.milestones.iter() {
+            self.milestone_contracts.insert(milestone.id, sc_reference);
+        }
+        self.supplier_contracts.insert(sc_reference, supplier_contract);
+
+        U64(sc_reference)
+    }
+
+    /// This operation will mark the 'PENDING' Milestone with the given 'milestone_id' as delivered. Only the supplier on the contract can mark milestones as delivered 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value
+    /// **Milestone** struct after delivery
+    pub fn mark_milestone_delivered(&mut self, milestone_id : U64, nonce : U64) -> ob_io::Milestone {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("mark_milestone_delivered".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("MILESTONE DELIVERY CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let (mut supplier_contract, index) = self.get_milestone_contract(u64::from(milestone_id));
+
+        self.require(supplier_contract.supplier == signer_account_id, format!("MILESTONE DELIVERY CANCELLED. ACCOUNT {} IS NOT THE SUPPLIER", signer_account_id));
+        self.require(supplier_contract.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", supplier_contract.status));
+
+        let milestone = &mut supplier_contract.milestones[index];
+        self.require(milestone.status == "PENDING", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : PENDING, ACTUAL STATUS : {} ", milestone.status));
+
+        milestone.delivered_date = env::block_timestamp() as i64;
+        milestone.status = "DELIVERED".to_string();
+        let milestone = milestone.clone();
+
+        self.supplier_contracts.insert(supplier_contract.reference, supplier_contract);
+
+        milestone
+    }
+
+    /// This operation will accept the 'DELIVERED' Milestone with the given 'milestone_id' and pay the supplier the milestone amount from the pot of the SupplierContract 
+    /// This operation is [**governed**] within the namespace of the pot of the SupplierContract, [**non-payable**]
+    /// # Return Value
+    /// **Payment** struct with details of the payment, carrying the milestone id as its 'linked_reference'
+    pub fn accept_milestone(&mut self, milestone_id : U64, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let (mut supplier_contract, index) = self.get_milestone_contract(u64::from(milestone_id));
+        let pot = self.get_pot(&supplier_contract.pot);
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("accept_milestone"), "ALLOWED".to_string());
+        self.require(security_response, format!("MILESTONE ACCEPTANCE STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let milestone = supplier_contract.milestones[index].clone();
+        self.require(milestone.status == "DELIVERED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : DELIVERED, ACTUAL STATUS : {} ", milestone.status));
+
+        // check the pot balance 
+        self.check_pot_balance(&pot.name, milestone.amount);
+        self.decrement_pot_balance(&pot.name, milestone.amount, "EXPENSES");
+
+        let payment = self.pay_to(supplier_contract.supplier.clone(), 
+                                signer_account_id.clone(), 
+                                milestone.amount, 
+                                milestone.description.clone(), 
+                                "MILESTONE".to_string());
+        let payment = self.link_payment(payment, milestone.id);
+
+        let milestone = &mut supplier_contract.milestones[index];
+        milestone.accepted_by = signer_account_id;
+        milestone.payment_reference = payment.reference;
+        milestone.status = "PAID".to_string();
+        supplier_contract.paid_total += payment.amount;
+
+        if supplier_contract.milestones.iter().all(|m| m.status == "PAID") {
+            supplier_contract.status = "COMPLETED".to_string();
+        }
+        self.supplier_contracts.insert(supplier_contract.reference, supplier_contract);
+
+        payment
+    }
+
+    /// This operation will reject the 'DELIVERED' Milestone with the given 'milestone_id', returning it to 'PENDING' so that the supplier can deliver it again 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// **Milestone** struct after rejection
+    pub fn reject_milestone(&mut self, milestone_id : U64, nonce : U64) -> ob_io::Milestone {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("accept_milestone".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("MILESTONE REJECTION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let (mut supplier_contract, index) = self.get_milestone_contract(u64::from(milestone_id));
+
+        let milestone = &mut supplier_contract.milestones[index];
+        self.require(milestone.status == "DELIVERED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : DELIVERED, ACTUAL STATUS : {} ", milestone.status));
+
+        milestone.delivered_date = 0;
+        milestone.status = "PENDING".to_string();
+        let milestone = milestone.clone();
+
+        self.supplier_contracts.insert(supplier_contract.reference, supplier_contract);
+
+        milestone
+    }
+
+    /// This operation will cancel the 'ACTIVE' SupplierContract associated with the given 'supplier_contract_ref'. Milestones that have not been paid are cancelled 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the cancelled Supplier Contract
+    pub fn cancel_supplier_contract(&mut self, supplier_contract_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("cancel_supplier_contract".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("SUPPLIER CONTRACT CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut supplier_contract = self.get_supplier_contract(u64::from(supplier_contract_ref));
+
+        self.require(supplier_contract.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", supplier_contract.status));
+
+        for milestone in supplier_contract.milestones.iter_mut().filter(|m| m.status != "PAID") {
+            milestone.status = "CANCELLED".to_string();
+        }
+        supplier_contract.status = "CANCELLED".to_string();
+        self.supplier_contracts.insert(supplier_contract.reference, supplier_contract);
+
+        supplier_contract_ref
+    }
+
+    /// This operation will create a VestingGrant for the beneficiary on the terms of the given 'grant'. The grant total is taken out of the bank balance and set aside until it is claimed or revoked 
+    /// the grant is drawn from the given 'pot' or the 'general' pot if none is given and the unvested part returns to the same pot on revocation 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Vesting Grant
+    pub fn create_vesting_grant(&mut self, grant : VestingGrantRequest, pot : Option<String>, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_vesting_grant"), "ALLOWED".to_string());
+        self.require(security_response, format!("VESTING GRANT CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_vesting_grant_request(&grant);
+
+        // check the pot balance 
+        self.check_pot_balance(&pot.name, grant.total);
+
+        let mut vesting_grant = ob_io::VestingGrant::create_vesting_grant(grant, signer_account_id);
+        vesting_grant.pot = pot.name;
+        self.record_sequence += 1;
+        vesting_grant.assign_sequence(self.record_sequence);
+        let vg_reference = vesting_grant.reference;
+
+        // set the grant aside
+        self.set_aside_funds(&vesting_grant.pot, vesting_grant.total, "RESERVES", vg_reference, "vesting grant".to_string());
+        self.vesting_balance += vesting_grant.total;
+
+        self.vesting_grants.insert(vg_reference, vesting_grant);
+
+        U64(vg_reference)
+    }
+
+    /// This operation will pay the vested but unclaimed part of the VestingGrant associated with the given 'vesting_grant_ref' to its beneficiary. Only the beneficiary can claim 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the claim, carrying the VestingGrant reference as its 'linked_reference'
+    pub fn claim_vested(&mut self, vesting_grant_ref : U64, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("claim_vested".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("VESTING CLAIM CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut vesting_grant = self.get_vesting_grant(u64::from(vesting_grant_ref));
+
+        self.require(vesting_grant.beneficiary == signer_account_id, format!("VESTING CLAIM CANCELLED. ACCOUNT {} IS NOT THE BENEFICIARY", signer_account_id));
+
+        let claimable = vesting_grant.get_claimable_amount(env::block_timestamp() as i64);
+        self.require(claimable > 0, format!("NOTHING TO CLAIM. VESTED AMOUNT {}, CLAIMED AMOUNT {}", vesting_grant.get_vested_amount(env::block_timestamp() as i64), vesting_grant.claimed));
+
+        vesting_grant.claimed += claimable;
+        self.vesting_balance -= claimable;
+        self.release_held_funds(claimable, "RESERVES");
+        if vesting_grant.status == "ACTIVE" && vesting_grant.claimed == vesting_grant.total {
+            vesting_grant.status = "COMPLETED".to_string();
+        }
+        self.vesting_grants.insert(vesting_grant.reference, vesting_grant.clone());
+
+        let payment = self.pay_to(vesting_grant.beneficiary, 
+                                signer_account_id, 
+                                claimable, 
+                                "vesting claim".to_string(), 
+                                "VESTING".to_string());
+        self.link_payment(payment, vesting_grant.reference)
+    }
+
+    /// This operation will revoke the revocable VestingGrant associated with the given 'vesting_grant_ref'. The unvested part is returned to the bank balance, anything already vested remains claimable by the beneficiary 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Amount returned to the bank balance
+    pub fn revoke_vesting_grant(&mut self, vesting_grant_ref : U64, nonce : U64) -> U128 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("revoke_vesting_grant".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("VESTING GRANT REVOCATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut vesting_grant = self.get_vesting_grant(u64::from(vesting_grant_ref));
+
+        self.require(vesting_grant.revocable, format!("VESTING GRANT {} IS NOT REVOCABLE", vesting_grant.reference));
+        self.require(vesting_grant.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", vesting_grant.status));
+
+        let time_now = env::block_timestamp() as i64;
+        let unvested = vesting_grant.total - vesting_grant.get_vested_amount(time_now);
+
+        vesting_grant.total -= unvested;
+        vesting_grant.revoked_date = time_now;
+        vesting_grant.status = "REVOKED".to_string();
+        self.vesting_grants.insert(vesting_grant.reference, vesting_grant.clone());
+
+        // return the unvested part
+        self.vesting_balance -= unvested;
+        self.return_set_aside_funds(&vesting_grant.pot, unvested, "RESERVES", vesting_grant.reference, "vesting grant revocation".to_string());
+
+        U128(unvested)
+    }
+
+    /// This operation will create a PaymentStream that accrues to the 'recipient' at 'rate_per_second' between the 'start_time' and the 'stop_time'. 
+    /// The deposit needed to fund the whole stream is taken out of the bank balance and set aside until it is withdrawn or the stream is cancelled 
+    /// the deposit is drawn from the given 'pot' or the 'general' pot if none is given and the unaccrued part returns to the same pot on cancellation 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Payment Stream
+    pub fn create_payment_stream(&mut self, recipient : String, rate_per_second : U128, start_time : I64, stop_time : I64, pot : Option<String>, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_payment_stream"), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT STREAM CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payment_stream = ob_io::PaymentStream::create_payment_stream(recipient, u128::from(rate_per_second), i64::from(start_time), i64::from(stop_time), signer_account_id);
+        self.record_sequence += 1;
+        payment_stream.assign_sequence(self.record_sequence);
+        payment_stream.pot = pot.name;
+
+        self.require(payment_stream.stop_time > payment_stream.start_time && payment_stream.deposit > 0, format!("INVALID STREAM TERMS. RATE PER SECOND {}, START TIME {}, STOP TIME {}", payment_stream.rate_per_second, payment_stream.start_time, payment_stream.stop_time));
+
+        // check the pot balance 
+        self.check_pot_balance(&payment_stream.pot, payment_stream.deposit);
+
+        // set the deposit aside
+        self.set_aside_funds(&payment_stream.pot, payment_stream.deposit, "RESERVES", payment_stream.reference, "payment stream deposit".to_string());
+        self.stream_balance += payment_stream.deposit;
+
+        let ps_reference = payment_stream.reference;
+        self.payment_streams.insert(ps_reference, payment_stream);
+
+        U64(ps_reference)
+    }
+
+    /// This operation will pay what has accrued to the recipient of the PaymentStream associated with the given 'payment_stream_ref' and has not yet been withdrawn. Only the recipient can withdraw 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the withdrawal, carrying the PaymentStream reference as its 'linked_reference'
+    pub fn withdraw_from_stream(&mut self, payment_stream_ref : U64, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("withdraw_from_stream".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("STREAM WITHDRAWAL CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payment_stream = self.get_payment_stream(u64::from(payment_stream_ref));
+
+        self.require(payment_stream.recipient == signer_account_id, format!("STREAM WITHDRAWAL CANCELLED. ACCOUNT {} IS NOT THE RECIPIENT", signer_account_id));
+
+        let time_now = env::block_timestamp() as i64;
+        let withdrawable = payment_stream.get_withdrawable_amount(time_now);
+        self.require(withdrawable > 0, format!("NOTHING TO WITHDRAW. ACCRUED AMOUNT {}, WITHDRAWN AMOUNT {}", payment_stream.get_accrued_amount(time_now), payment_stream.withdrawn));
+
+        payment_stream.withdrawn += withdrawable;
+        self.stream_balance -= withdrawable;
+        self.release_held_funds(withdrawable, "RESERVES");
+        if payment_stream.status == "ACTIVE" && payment_stream.withdrawn == payment_stream.deposit {
+            payment_stream.accrued_at_checkpoint = payment_stream.deposit;
+            payment_stream.checkpoint_time = time_now;
+            payment_stream.status = "COMPLETED".to_string();
+        }
+        self.payment_streams.insert(payment_stream.reference, payment_stream.clone());
+
+        let payment = self.pay_to(payment_stream.recipient, 
+                                signer_account_id, 
+                                withdrawable, 
+                                "stream withdrawal".to_string(), 
+                                "STREAM".to_string());
+        self.link_payment(payment, payment_stream.reference)
+    }
+
+    /// This operation will pause the 'ACTIVE' PaymentStream associated with the given 'payment_stream_ref'. Nothing accrues while the stream is paused, what has already accrued remains withdrawable 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the paused Payment Stream
+    pub fn pause_payment_stream(&mut self, payment_stream_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("pause_payment_stream".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT STREAM PAUSE STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payment_stream = self.get_payment_stream(u64::from(payment_stream_ref));
+
+        self.require(payment_stream.status == "ACTIVE", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE, ACTUAL STATUS : {} ", payment_stream.status));
+
+        OpenBank::checkpoint_payment_stream(&mut payment_stream, "PAUSED".to_string());
+        self.payment_streams.insert(payment_stream.reference, payment_stream);
+
+        payment_stream_ref
+    }
+
+    /// This operation will resume the 'PAUSED' PaymentStream associated with the given 'payment_stream_ref'. The stream accrues again from now and its stop time is put back by the time it spent paused 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the resumed Payment Stream
+    pub fn resume_payment_stream(&mut self, payment_stream_ref : U64, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("resume_payment_stream".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT STREAM RESUMPTION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payment_stream = self.get_payment_stream(u64::from(payment_stream_ref));
+
+        self.require(payment_stream.status == "PAUSED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : PAUSED, ACTUAL STATUS : {} ", payment_stream.status));
+
+        payment_stream.resume(env::block_timestamp() as i64);
+        self.payment_streams.insert(payment_stream.reference, payment_stream);
+
+        payment_stream_ref
+    }
+
+    /// This operation will cancel the PaymentStream associated with the given 'payment_stream_ref'. The part of the deposit that has not accrued is returned to the bank balance, 
+    /// what has already accrued remains withdrawable by the recipient 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Amount returned to the bank balance
+    pub fn cancel_payment_stream(&mut self, payment_stream_ref : U64, nonce : U64) -> U128 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("cancel_payment_stream".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("PAYMENT STREAM CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut payment_stream = self.get_payment_stream(u64::from(payment_stream_ref));
+
+        self.require(payment_stream.status == "ACTIVE" || payment_stream.status == "PAUSED", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : ACTIVE OR PAUSED, ACTUAL STATUS : {} ", payment_stream.status));
+
+        OpenBank::checkpoint_payment_stream(&mut payment_stream, "CANCELLED".to_string());
+        let unaccrued = payment_stream.deposit - payment_stream.accrued_at_checkpoint;
+        payment_stream.deposit = payment_stream.accrued_at_checkpoint;
+        self.payment_streams.insert(payment_stream.reference, payment_stream.clone());
+
+        // return the unaccrued part 
+        self.stream_balance -= unaccrued;
+        self.return_set_aside_funds(&payment_stream.pot, unaccrued, "RESERVES", payment_stream.reference, "payment stream cancellation".to_string());
+
+        U128(unaccrued)
+    }
+
+    /// This operation will hold the 'amount' of the given 'escrow' for its payee pending release. The amount is taken out of the bank balance and returned to it on refund 
+    /// the amount is drawn from the given 'pot' or the 'general' pot if none is given and returns to the same pot on refund 
+    /// This operation is [**governed**], [**non-payable**]
+    /// # Return Value
+    /// Reference of the created Escrow
+    pub fn create_escrow(&mut self, escrow : EscrowRequest, pot : Option<String>, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("create_escrow"), "ALLOWED".to_string());
+        self.require(security_response, format!("ESCROW CREATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_escrow_request(&escrow);
+
+        // check the pot balance 
+        self.check_pot_balance(&pot.name, escrow.amount);
+
+        let mut escrow = ob_io::Escrow::create_escrow(escrow, self.bank_deployed_account_id.clone(), "BANK".to_string(), signer_account_id);
+        escrow.pot = pot.name;
+        self.record_sequence += 1;
+        escrow.assign_sequence(self.record_sequence);
+        let es_reference = escrow.reference;
+
+        // hold the funds
+        self.set_aside_funds(&escrow.pot, escrow.amount, "ESCROW", es_reference, escrow.description.clone());
+        self.escrow_balance += escrow.amount;
+
+        self.escrows.insert(es_reference, escrow);
+
+        U64(es_reference)
+    }
+
+    /// This operation will hold the attached funds in escrow for the payee of the given 'escrow' pending release. The attached funds do not enter the bank balance and are returned to the payer on refund 
+    /// [**governed**] - [BARRING], [**payable**]
+    /// # Return Value
+    /// Reference of the created Escrow
+    #[payable]
+    pub fn create_escrow_from_pay_in(&mut self, escrow : EscrowRequest, nonce : U64) -> U64 {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("create_escrow_from_pay_in".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("ESCROW CREATION CANCELLED. ACCOUNT {} BARRED", signer_account_id));
+
+        self.check_escrow_request(&escrow);
+
+        // check amounts
+        self.check_attachment_vs_stated_amount(env::attached_deposit(), escrow.amount);
+
+        self.escrow_balance += escrow.amount;
+        self.hold_funds(escrow.amount, "ESCROW");
+
+        let mut escrow = ob_io::Escrow::create_escrow(escrow, signer_account_id.clone(), "PAY_IN".to_string(), signer_account_id.clone());
+        self.record_sequence += 1;
+        escrow.assign_sequence(self.record_sequence);
+
+        let payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                        signer_account_id.clone(), 
+                                                        signer_account_id, 
+                                                        escrow.amount, 
+                                                        escrow.description.clone(), 
+                                                        "COMPLETED".to_string(), 
+                                                        "ESCROW_PAY_IN".to_string());
+        let payment = self.link_payment(payment, escrow.reference);
+        escrow.funding_payment_reference = payment.reference;
+
+        let es_reference = escrow.reference;
+        self.escrows.insert(es_reference, escrow);
+
+        U64(es_reference)
+    }
+
+    /// This operation will record the approval of the signer, who must be one of the named approvers of the 'HELD' Escrow associated with the given 'escrow_ref'. 
+    /// The escrow is released to the payee as soon as the required number of approvals has been reached 
+    /// [**governed**] - [BARRING], [**non-payable**]
+    /// # Return Value 
+    /// **Escrow** struct after approval 
+    pub fn approve_escrow(&mut self, escrow_ref : U64, nonce : U64) -> ob_io::Escrow {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let security_response = self.is_secure("approve_escrow".to_string(), "BARRED".to_string());
+        self.require(security_response, format!("ESCROW APPROVAL CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        let mut escrow = self.get_escrow(u64::from(escrow_ref));
+
+        self.require(escrow.status == "HELD", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : HELD, ACTUAL STATUS : {} ", escrow.status));
+        self.require(!escrow.is_expired(env::block_timestamp() as i64), format!("ESCROW APPROVAL CANCELLED. ESCROW EXPIRED ON {}", escrow.expiry));
+        self.require(escrow.approvers.contains(&signer_account_id), format!("ESCROW APPROVAL CANCELLED. ACCOUNT {} IS NOT AN APPROVER", signer_account_id));
+        self.require(!escrow.approvals.contains(&signer_account_id), format!("ESCROW APPROVAL CANCELLED. ACCOUNT {} HAS ALREADY APPROVED", signer_account_id));
+
+        escrow.approvals.push(signer_account_id.clone());
+        self.escrows.insert(escrow.reference, escrow.clone());
+
+        if escrow.is_approved() {
+            self.release_held_escrow(escrow.clone(), signer_account_id);
+            return self.get_escrow(u64::from(escrow_ref));
+        }
+        escrow
+    }
+
+    /// This operation will release the 'HELD' Escrow associated with the given 'escrow_ref' to its payee. The signer must be allowed the Open Roles 'release_operation' of the escrow 
+    /// unless the escrow has expired with a 'RELEASE' timeout action, in which case any account that is not barred can release it 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Payment** struct with details of the release, carrying the Escrow reference as its 'linked_reference'
+    pub fn release_escrow(&mut self, escrow_ref : U64, nonce : U64) -> ob_io::Payment {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let escrow = self.get_escrow(u64::from(escrow_ref));
+
+        self.require(escrow.status == "HELD", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : HELD, ACTUAL STATUS : {} ", escrow.status));
+
+        // do security
+        let security_response = if escrow.is_expired(env::block_timestamp() as i64) && escrow.timeout_action == "RELEASE" {
+            self.is_secure("release_escrow".to_string(), "BARRED".to_string())
+        }
+        else {
+            !escrow.release_operation.is_empty() && self.is_secure(escrow.release_operation.clone(), "ALLOWED".to_string())
+        };
+        self.require(security_response, format!("ESCROW RELEASE STOPPED. RELEASE CONDITIONS NOT MET FOR ACCOUNT {}", signer_account_id));
+
+        self.release_held_escrow(escrow, signer_account_id)
+    }
+
+    /// This operation will refund the 'HELD' Escrow associated with the given 'escrow_ref'. Escrows funded by the bank are returned to the bank balance, escrows funded by a pay in are paid back to the payer. 
+    /// Once the escrow has expired with a 'REFUND' timeout action any account that is not barred can refund it 
+    /// [**governed**], [**non-payable**]
+    /// # Return Value 
+    /// **Escrow** struct after refund 
+    pub fn refund_escrow(&mut self, escrow_ref : U64, nonce : U64) -> ob_io::Escrow {
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let escrow = self.get_escrow(u64::from(escrow_ref));
+
+        self.require(escrow.status == "HELD", format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : HELD, ACTUAL STATUS : {} ", escrow.status));
+
+        // do security
+        let security_response = if escrow.is_expired(env::block_timestamp() as i64) && escrow.timeout_action == "REFUND" {
+            self.is_secure("refund_escrow".to_string(), "BARRED".to_string())
+        }
+        else {
+            self.is_secure("refund_escrow".to_string(), "ALLOWED".to_string())
+        };
+        self.require(security_response, format!("ESCROW REFUND STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.refund_held_escrow(escrow, signer_account_id)
+    }
+
+    /// This operation will 'deposit' the attached funds into this bank and increment the balance of this bank.
+    /// This operation is oriented towards internal business payments into the bank as opposed to external 'pay in' 
+    /// The governance of this operation allows the 'nominee_account_id' to make deposits at any time 
+    /// the funds go to the given 'pot' or the 'general' pot if none is given 
+    /// the given 'annotation' gives the deposit its accounting category and tags 
+    /// [**governed**], [**payable**]
+    /// # Return Value
+    #[payable]
+    pub fn deposit(&mut self, description : String, amount : U128, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : U64) -> ob_io::Payment {
+        
+        // check nonce 
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+        
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        let security_response = self.is_secure(pot.get_operation("deposit"), "ALLOWED".to_string());
+
+        self.require(signer_account_id.as_bytes() == self.nominee_account_id.as_bytes() || security_response, format!("DEPOSIT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        self.check_payment_annotation(&annotation);
+
+        // check transfer amount
+        let attached_deposit = near_sdk::env::attached_deposit();
+
+        let stated_deposit = u128::from(amount);
+
+        self.check_attachment_vs_stated_amount(attached_deposit, stated_deposit);
+
+        // increase the pot and bank balance
+        self.increment_pot_balance(&pot.name, stated_deposit, "CAPITAL");
+
+        // log the payment
+        let current_account_id = env::current_account_id();
+        let payment = self.create_and_register_payment( self.bank_deployed_account_id.clone(), 
+                                          current_account_id, 
+                                                signer_account_id, 
+                                                stated_deposit, 
+                                                description, 
+                                   "COMPLETED".to_string(),
+                                     "DEPOSIT".to_string());
+        let payment = self.annotate_payment(payment, annotation);
+        self.sweep_excess_balance(&pot.name);
+        payment
+    }
+   
+    /// This operation will 'withdraw' the given amout to the 'nominee_account_id' 
+    /// This operation can be called by the 'nominee_account_id' at any time 
+    /// the funds are drawn from the given 'pot' or the 'general' pot if none is given 
+    /// the given 'annotation' gives the withdrawal its accounting category and tags 
+    /// This operation is [**governed**], [**non-payable**] 
+    /// # Return Value
+    pub fn withdraw(&mut self, description : String, amount : U128, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : U64) -> ob_io::Payment {
+        // check nonce 
+        self.check_nonce(u64::from(nonce));
+
+        let signer_account_id = env::signer_account_id();
+
+        let pot = self.get_pot(&OpenBank::get_pot_name(pot));
+
+        // do security
+        let security_response = self.is_secure(pot.get_operation("withdraw"), "ALLOWED".to_string());
+        self.require(signer_account_id.as_bytes() == self.nominee_account_id.as_bytes() || security_response, format!("Account {} not allowed ", signer_account_id));               
+     
+        self.check_payment_annotation(&annotation);
+
+        let withdrawal_amount = u128::from(amount) ;
+        
+        // check the pot can afford it 
+        self.check_pot_balance(&pot.name, withdrawal_amount);
+        self.check_minimum_reserve(withdrawal_amount);
+
+        self.decrement_pot_balance(&pot.name, withdrawal_amount, "CAPITAL");
+
+        // pay to the nominee account
+        let payment = self.pay_to( self.nominee_account_id.clone(), 
+                            signer_account_id, 
+                            withdrawal_amount, 
+                            description, 
+                            "WITHDRAWAL".to_string());
+        self.annotate_payment(payment, annotation)
+    }
+
+    /// This operation will set the 'bank_name' for this bank 
+    /// This operation is [**'governed'**], [**non-payable**]
+    /// # Return Value
+    /// 
+    pub fn set_open_bank_name(&mut self, bank_name : String) -> bool {
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_open_bank_name".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+        
+        // run assignment
+        self.bank_name = bank_name; 
+        true
+    }
+
+    /// This operation will set the 'nominee_account_id' for this bank 
+    /// This operation is [**'governed'**], [**non-payable**] 
+    /// # Return Value
+    /// 
+    pub fn set_open_bank_nominee_account(&mut self, nominee_account_id : String) -> bool {
+        let signer_account_id = env::signer_account_id();
+
+         // do security
+        let security_response = self.is_secure("set_obei_nominee_acccount".to_string(), "ALLOWED".to_string()); 
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+        
+        // run assignment
+        self.nominee_account_id = nominee_account_id; 
+        true
+    }
+
+    /// This operation will set the **'obei_or_near_core'** (Open Roles) 'account_id'. All governance calls will be made to this 'account_id'
+    /// This operation is [**'governed'**], [**non-payable**]
+    /// # Return Value
+    /// 
+    pub fn set_obei_open_roles(&mut self, open_roles_account_id : String) -> bool {
+        let signer_account_id = env::signer_account_id();
+
+        // do security
+        let security_response = self.is_secure("set_obei_open_roles".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+
+        // run assignment
+        self.access_security = open_roles_account_id.to_string(); 
+        
+        true
+    }
+
+    /// This operation will set the affirmative security code for role management 
+    /// # Return Value 
+    /// **Numeric** representing new security code
+    pub fn set_affirmative_secure_code(&mut self, affirmative_secure_code : i32) -> i32 {
+        let signer_account_id = env::signer_account_id();
+        let security_response = self.is_secure("set_affirmative_secure_code".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+        self.affirmative_code = affirmative_secure_code; 
+        self.affirmative_code.clone()
+    }
+
+    /// This operation will set the negative security code for role management 
+    /// # Return value 
+    /// **Numeric** representing new security code
+    pub fn set_negative_secure_code(&mut self, negative_secure_code : i32) -> i32 {
+        let signer_account_id = env::signer_account_id();
+        let security_response = self.is_secure("set_negative_secure_code".to_string(), "ALLOWED".to_string());
+        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+        self.negative_code = negative_secure_code; 
+        self.negative_code.clone()
+    }
+
+
+    /// This operaion will deactivate test mode on this bank. Once deactivated this cannot be reactivated 
+    /// # Return Value 
+    /// **true** if and only if test mode has been deactivated
+    pub fn deactivate_test_mode(&mut self)-> bool {
+        self.test_mode = false; 
+        self.test_mode
+    }
+
+    fn pay_to( &mut self, 
+                payee : String, 
+                signer : String, 
+                payment_amount : u128, 
+                payment_description : String,
+                payment_type : String ) -> ob_io::Payment { 
+                self.pay_to_net_of_fee(payee, signer, payment_amount, 0, payment_description, payment_type)
+    }
+
+    // pays the 'payment_amount' less the 'fee' to the payee, the caller is responsible for accruing the fee 
+    fn pay_to_net_of_fee( &mut self, 
+                payee : String, 
+                signer : String, 
+                payment_amount : u128, 
+                fee : u128, 
+                payment_description : String,
+                payment_type : String ) -> ob_io::Payment { 
                 
-                // pay to the payee
-               let payment =  self.pay_to(    
-                                                    mpr.payee_account_id, 
-                                                    signer_account_id.clone(), 
-                                        amount, 
-                                 mpr.description,
-                                       "PAY_OUT_MULTI".to_string());
+                self.require(!self.outflows_paused, format!("OUTFLOWS PAUSED. PAYMENT OF {} TO {} CANCELLED", payment_amount, payee));
+
+                // transfer funds to payee                
+                Promise::new(payee.clone()).transfer(payment_amount - fee);
+                
+                self.require(env::promise_results_count() == 1, "pay_to:01 PROMISE FAILURE ".to_string());
+                let res  = match env::promise_result(0) {
+                                       PromiseResult::Successful(x) => x,
+                                        _ => Vec::<u8>::new(),
+                                    };
+                let transfer_code = res[0];
+
+                let payment_status : String = transfer_code.to_string();
+
+                // log the payment
+                let payment = self.create_and_register_payment( payee, 
+                                            self.bank_deployed_account_id.clone(),
+                                            signer, 
+                                            payment_amount, 
+                                            payment_description,
+                                            payment_status,
+                                            payment_type);
+                self.record_fee(payment, fee)
+    }
+
+    fn record_fee(&mut self, mut payment : ob_io::Payment, fee : u128) -> ob_io::Payment {
+        if fee == 0 {
+            return payment;
+        }
+        payment.fee_amount = fee;
+        payment.net_amount = payment.gross_amount - fee;
+        self.update_payment(payment.clone());
+        payment
+    }
+
+    fn create_and_register_payment(&mut self,   
+                                payee : String, 
+                                payer : String, 
+                                signer : String, 
+                                amount : u128, 
+                                description : String,
+                                payment_status : String,
+                                payment_type : String) -> ob_io::Payment {
+        
+        // every payment the bank makes is created here 
+        OpenBank::check_description(&description);
+
+        // the periods that fell due before this Payment are closed without it 
+        self.close_due_statement_periods(signer.clone());
+
+        let mut payment = ob_io::Payment::create_payment ( payee,
+                                                            payer,
+                                                            signer, 
+                                                            amount,
+                                                            description,
+                                                            payment_type,
+                                                            payment_status); 
+
+        self.payment_sequence += 1;
+        payment.assign_sequence(self.payment_sequence);
+
+        // the journal entries posted since the last Payment are behind this one
+        for journal_entry in self.journal[self.tied_journal_sequence as usize..].iter_mut() {
+            journal_entry.payment_reference = payment.reference;
+        }
+        self.tied_journal_sequence = self.journal.len() as u64;
+                                                            
+        self.payments.insert(payment.clone());
+        self.payments_by_reference.insert(payment.reference, payment.clone());
+        self.payment_references.push(payment.reference);
+        self.record_activity(&payment);
+
+        payment
+    }
+
+    // keeps the running totals read by 'get_bank_summary' so that it costs the same however many payments have been made
+    fn record_activity(&mut self, payment : &ob_io::Payment) {
+        self.last_activity_time = payment.payment_time;
+        self.open_statement_period.record(&payment.payment_type, payment.amount);
+        if !self.lifetime_activity.record(payment) {
+            return;
+        }
+        let day = payment.payment_time / DAY_MILLIS;
+        self.daily_activity.entry(day).or_insert_with(|| ob_io::ActivitySummary { days : 1, ..Default::default() }).record(payment);
+
+        // forget the days that have passed out of range
+        self.daily_activity = self.daily_activity.split_off(&(day - MAX_ACTIVITY_DAYS + 1));
+    }
+
+    // closes the current statement period at the given 'closing_time' and opens the next, which starts with the next Payment and the journal entries behind it 
+    fn close_open_statement_period(&mut self, closed_by : String, closing_time : i64) -> ob_io::StatementPeriod {
+        // journal entries posted for a Payment not yet registered belong to the next period
+        let untied_cash_movement = OpenBank::get_cash_movement(&self.journal[self.tied_journal_sequence as usize..]);
+        let closing_balance = (self.bank_balance as i128 - untied_cash_movement) as u128;
+
+        let mut statement_period = self.open_statement_period.clone();
+        statement_period.close(closing_time, closing_balance, self.payment_sequence, self.tied_journal_sequence, closed_by);
+        self.statement_periods.push(statement_period.clone());
+
+        let number = statement_period.number + 1;
+        self.open_statement_period = ob_io::StatementPeriod::open_statement_period(number, closing_balance, self.payment_sequence + 1, self.tied_journal_sequence + 1);
+        self.open_statement_period.opening_time = closing_time;
+        self.open_statement_period.close_due_time = self.statement_schedule.get_close_time(number);
+        statement_period
+    }
+
+    // closes every statement period that has fallen due under the statement schedule, each at its due time 
+    fn close_due_statement_periods(&mut self, closed_by : String) {
+        let time_now = env::block_timestamp() as i64;
+        while self.open_statement_period.close_due_time != 0 && self.open_statement_period.close_due_time <= time_now {
+            let close_due_time = self.open_statement_period.close_due_time;
+            self.close_open_statement_period(closed_by.clone(), close_due_time);
+        }
+    }
+
+    fn link_payment(&mut self, mut payment : ob_io::Payment, linked_reference : u64) -> ob_io::Payment {
+        payment.linked_reference = linked_reference;
+        self.update_payment(payment.clone());
+        payment
+    }
+
+    fn record_pot(&mut self, mut payment : ob_io::Payment, pot_name : &str) -> ob_io::Payment {
+        payment.pot = pot_name.to_string();
+        self.update_payment(payment.clone());
+        payment
+    }
+
+    // overwrites the stored Payment with the same reference
+    fn update_payment(&mut self, payment : ob_io::Payment) {
+        if let Some(previous) = self.payments_by_reference.get(&payment.reference) {
+            self.payments.remove(previous);
+        }
+        self.payments.insert(payment.clone());
+        self.payments_by_reference.insert(payment.reference, payment);
+    }
+
+    fn require(&mut self, condition : bool, message : String) -> bool {
+        if !condition {
+            panic!("{}", message);
+        }
+        true
+    }
+
+    fn is_secure(&mut self, operation : String, mode : String) -> bool {
+        
+        if self.test_mode {
+           return self.test_mode;
+        }
+
+        let security_response = false; 
+
+        let signer_account_id = env::signer_account_id(); 
+
+        if  mode.as_bytes() == "ALLOWED".to_string().as_bytes() {
+           
+            ext_open_roles::is_allowed(self.access_security.clone(), 
+                                        self.bank_deployed_account_id.clone(), 
+                                        self.bank_name.clone(), 
+                                        operation.clone(), 
+                                        &signer_account_id, 
+                                        NO_DEPOSIT, 
+                                        BASE_GAS); 
+                                        let role_response : i32;
+                                        match env::promise_result(0) {
+                                           near_sdk::PromiseResult::Successful(x) => role_response = num::ToPrimitive::to_i32(x.get(0).unwrap()).unwrap(),
+                                            _ => panic!("is_secure::01 :- PROMISE FAILURE FOR SECURITY REQUEST :: OR LOCATION {} - CONTRACT ACCOUNT {} - CONTRACT {} - OPERATION {} - USER ACCOUNT {}",self.access_security.clone(),self.bank_deployed_account_id.clone(), self.bank_name.clone(), operation.clone(), &signer_account_id),
+                                        };
+                                        
+                                        if role_response == self.affirmative_code {
+                                            return true; 
+                                        }
+            return security_response; 
+        }
+        else {
+
+            ext_open_roles::is_barred(self.access_security.clone(), 
+                                        self.bank_deployed_account_id.clone(), 
+                                        self.bank_name.clone(), 
+                                        operation.clone(), 
+                                        &signer_account_id, 
+                                        NO_DEPOSIT, 
+                                        BASE_GAS); 
+                                        let role_response : i32;
+                                        match env::promise_result(0) {
+                                           near_sdk::PromiseResult::Successful(x) => role_response = num::ToPrimitive::to_i32(x.get(0).unwrap()).unwrap(),
+                                            _ => panic!("is_secure::02 :- PROMISE FAILURE FOR SECURITY REQUEST :: OR LOCATION {} - CONTRACT ACCOUNT {} - CONTRACT {} - OPERATION {} - USER ACCOUNT {}",self.access_security.clone(),self.bank_deployed_account_id.clone(), self.bank_name.clone(), operation.clone(), &signer_account_id),
+                                        };
+                                        
+                                        if role_response == self.negative_code {
+                                            return true; 
+                                        }
+            return security_response; 
+        }
+    }
+
+    fn check_nonce( &mut self, nonce : u64) {
+        let signer_account_id = env::signer_account_id().to_string();
+        
+        if self.nonce_register.contains_key(&signer_account_id) {
+            let nonce_history = self.nonce_register.get(&signer_account_id).unwrap();
+            
+            if nonce_history.contains(&nonce) {
+                panic!("REPEAT NONCE DETECTED. NONCE: {} ", nonce);
+            }
+        }
+        else {
+            let mut nonce_history = HashSet::<u64>::new();
+            nonce_history.insert(nonce);
+            self.nonce_register.insert(signer_account_id, nonce_history);
+        }
+    }
+
+    fn check_bank_balance(&mut self, amount_required : u128) {
+        self.check_pot_balance(DEFAULT_POT, amount_required);
+    }
+
+    // governed outflows can not take the bank below its minimum reserve
+    fn check_minimum_reserve(&mut self, amount_required : u128) {
+        let minimum_reserve = self.treasury_policy.minimum_reserve;
+        if !self.is_above_minimum_reserve(amount_required) {
+            panic!("MINIMUM RESERVE BREACHED. REQUIRED AMOUNT: {} BALANCE: {} MINIMUM RESERVE: {}", amount_required, self.bank_balance, minimum_reserve);
+        }
+    }
+
+    fn is_above_minimum_reserve(&self, amount_required : u128) -> bool {
+        self.bank_balance >= amount_required + self.treasury_policy.minimum_reserve
+    }
+
+    // sweeps whatever the bank holds over its maximum balance to the nominee account, out of the pot the funds came into 
+    fn sweep_excess_balance(&mut self, pot_name : &str) -> Option<ob_io::Payment> {
+        let maximum_balance = self.treasury_policy.maximum_balance;
+        if maximum_balance == 0 || self.bank_balance <= maximum_balance || self.outflows_paused {
+            return None;
+        }
+        let sweep_amount = (self.bank_balance - maximum_balance).min(self.get_pot(pot_name).balance);
+        if sweep_amount == 0 {
+            return None;
+        }
+        self.decrement_pot_balance(pot_name, sweep_amount, "CAPITAL");
+        Some(self.pay_to(self.nominee_account_id.clone(), 
+                        env::signer_account_id(), 
+                        sweep_amount, 
+                        "treasury sweep".to_string(), 
+                        "SWEEP".to_string()))
+    }
 
-                // add payment to vector
-                payments.insert(payment);
+    fn check_pot_balance(&mut self, pot_name : &str, amount_required : u128) {
+        if !self.can_afford_from_pot(pot_name, amount_required) {
+            panic!("INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", amount_required, self.get_pot(pot_name).balance);
         }
-        // return the payments
-        payments
-    
     }
 
-    /// This operation will trigger the payment of the RequestDebit associated wqith the 'request_debit_ref'. Funds will be sent to the account id attached to the RequestDebit *not* the caller
-    /// [**governed**] - [BARRING], [**non-payable**]
-    /// # Return Value 
-    /// **Payment** struct with details of the payment to the Request Debit 
-    pub fn request_debit(&mut self, request_debit_ref : U64, nonce : U64) -> ob_io::Payment {
-        
-        // check nonce
-        self.check_nonce(u64::from(nonce));
+    fn can_afford(&self, amount_required : u128) -> bool {
+        self.can_afford_from_pot(DEFAULT_POT, amount_required)
+    }
 
-        let signer_account_id = env::signer_account_id();
+    // the bank will not pay out its entire balance 
+    fn can_afford_from_pot(&self, pot_name : &str, amount_required : u128) -> bool {
+        amount_required < self.bank_balance && amount_required <= self.get_pot(pot_name).balance
+    }
 
-        let security_response = self.is_secure("request_debit".to_string(), "BARRED".to_string());
-        self.require(security_response, format!("REQUEST DEBIT PAY OUT CANCELLED. ACCOUNT {} REQUEST DEBIT CLAIM NOT ALLOWED", signer_account_id));
+    // fixes what has accrued to the stream so far before it moves to the 'new_status'
+    fn checkpoint_payment_stream(payment_stream : &mut ob_io::PaymentStream, new_status : String) {
+        let time_now = env::block_timestamp() as i64;
+        payment_stream.accrued_at_checkpoint = payment_stream.get_accrued_amount(time_now);
+        payment_stream.checkpoint_time = time_now;
+        payment_stream.status = new_status;
+    }
 
-        let request_debit  = self.find_request_debit(request_debit_ref); 
+    // returns the contract holding the milestone with the given id along with the position of the milestone in the contract
+    fn get_milestone_contract(&self, milestone_id : u64) -> (ob_io::SupplierContract, usize) {
+        let supplier_contract = match self.milestone_contracts.get(&milestone_id) {
+            Some(supplier_contract_reference) => self.get_supplier_contract(*supplier_contract_reference),
+            None => panic!("UNKNOWN MILESTONE ID {} ", milestone_id),
+        };
+        let index = supplier_contract.get_milestone_index(milestone_id).unwrap();
+        (supplier_contract, index)
+    }
 
-        // check request debit status 
-        self.check_request_debit_status(request_debit.status.clone(), "APPROVED".to_string());
+    fn find_invoices_where<F : Fn(&ob_io::Invoice) -> bool>(&self, condition : F) -> Vec<ob_io::Invoice> {
+        let mut invoices : Vec<ob_io::Invoice> = self.invoices.values().filter(|invoice| condition(invoice)).cloned().collect();
+        invoices.sort_by_key(|invoice| (invoice.creation_date, invoice.reference));
+        invoices
+    }
 
-        let rd = self.find_request_debit(request_debit_ref); 
+    // credit is owed to the account and is held apart from the bank balance
+    fn add_customer_credit(&mut self, account_id : String, amount : u128) {
+        *self.customer_credits.entry(account_id).or_default() += amount;
+        self.credit_balance += amount;
+    }
 
-        // check last paid vs interval 
-        self.check_request_debit_interval( rd); 
+    fn remove_customer_credit(&mut self, account_id : String, amount : u128) {
+        let credit = *self.customer_credits.get(&account_id).unwrap_or(&0);
+        if amount > credit {
+            panic!("INSUFFICIENT CREDIT AVAILABLE. ACCOUNT {} REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", account_id, amount, credit);
+        }
+        if amount == credit {
+            self.customer_credits.remove(&account_id);
+        }
+        else {
+            self.customer_credits.insert(account_id, credit - amount);
+        }
+        self.credit_balance -= amount;
+    }
 
-        let rda = self.find_request_debit(request_debit_ref); 
+    fn get_due_subscriptions(&self) -> Vec<ob_io::Subscription> {
+        let time_now = env::block_timestamp() as i64;
+        let mut due_subscriptions : Vec<ob_io::Subscription> = self.subscriptions.values().filter(|sub| sub.status == "ACTIVE" && sub.paid_until <= time_now).cloned().collect();
+        due_subscriptions.sort_by_key(|sub| (sub.paid_until, sub.reference));
+        due_subscriptions
+    }
 
-        // check bank balance 
-        self.check_bank_balance(rda.amount);
+    // charges the next period of the given Subscription against the credit of the subscriber 
+    fn renew_subscription(&mut self, mut subscription : ob_io::Subscription, signer_account_id : String) -> ob_io::Payment {
+        self.remove_customer_credit(subscription.subscriber.clone(), subscription.price);
+        self.recognise_credit(subscription.price);
 
-        // decrement the bank balance
-        self.decrement_bank_balance(rda.amount);
+        subscription.paid_until += subscription.period;
+        subscription.periods_paid += 1;
+        self.subscriptions.insert(subscription.reference, subscription.clone());
 
-        let mut rdp = self.find_request_debit(request_debit_ref); 
-        // update the request_debit last paid date to now 
-        rdp.last_paid = env::block_timestamp() as i64;
+        let description = self.find_subscription_plan(U64(subscription.plan_reference)).name;
+        let payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                        subscription.subscriber, 
+                                                        signer_account_id, 
+                                                        subscription.price, 
+                                                        description, 
+                                                        "COMPLETED".to_string(), 
+                                                        "SUBSCRIPTION".to_string());
+        self.link_payment(payment, subscription.reference)
+    }
 
-        let rdc = self.find_request_debit(request_debit_ref); 
-        // pay to the payee
-        self.pay_to(    rdc.payee, 
-                        signer_account_id, 
-                            rdc.amount, 
-       rdc.description,
-            "REQUEST_DEBIT".to_string())      
+    fn check_split_rule_request(&mut self, split_rule : &ob_io::SplitRuleRequest) {
+        if split_rule.match_type != "TAG" && split_rule.match_type != "DAPP" {
+            panic!("UNKNOWN MATCH TYPE {} ", split_rule.match_type);
+        }
+        if split_rule.distribution != "IMMEDIATE" && split_rule.distribution != "ACCRUED" {
+            panic!("UNKNOWN DISTRIBUTION {} ", split_rule.distribution);
+        }
+        let total_basis_points : u64 = split_rule.legs.iter().map(|leg| leg.basis_points).sum();
+        if split_rule.legs.is_empty() || split_rule.legs.iter().any(|leg| leg.basis_points == 0) || total_basis_points > 10_000 {
+            panic!("INVALID SPLIT LEGS. LEGS {}, TOTAL BASIS POINTS {}", split_rule.legs.len(), total_basis_points);
+        }
     }
 
-    /// This operation will register a 'new' *'Request Debit'* with this bank. The RequestDebit will need to be approved before it can be 'debited' 
-    /// This operation is [**governed**] - [BARRING], [**non-payable**]
-    /// #Return Value
-    pub fn register_request_debit(&mut self, 
-                                    payee           : String,
-                                    description     : String, 
-                                    amount          : U128, 
-                                    payout_interval : I64, 
-                                    start_date      : I64, 
-                                    end_date        : I64, 
-                                    nonce : U64)-> U64 {
-        self.check_nonce(u64::from(nonce));
+    fn get_split_match_key(match_type : &str, match_value : &str) -> String {
+        format!("{}:{}", match_type, match_value)
+    }
 
-        let signer_account_id = env::signer_account_id();
+    // a rule for the pay in tag takes precedence over a rule for the calling dApp
+    fn get_matching_split_rule(&self, pay_in_tag : Option<String>) -> Option<ob_io::SplitRule> {
+        let mut match_keys = Vec::<String>::new();
+        if let Some(tag) = pay_in_tag {
+            match_keys.push(OpenBank::get_split_match_key("TAG", &tag));
+        }
+        match_keys.push(OpenBank::get_split_match_key("DAPP", &env::predecessor_account_id()));
 
-        let security_response = self.is_secure("register_request_debit".to_string(), "BARRED".to_string());
-        self.require(security_response, format!("REQUEST DEBIT REGISTRATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        
-        let debit_amount = u128::from(amount);
+        match_keys.iter()
+                    .find_map(|match_key| self.active_split_rules.get(match_key))
+                    .map(|sr_reference| self.split_rules.get(sr_reference).unwrap().clone())
+    }
 
-        let request_debit = ob_io::RequestDebit::create_request_debit(payee, debit_amount, description, i64::from(payout_interval), i64::from(start_date), i64::from(end_date), signer_account_id);
-        let rd_clone = request_debit.clone();
-        let rd_reference = request_debit.reference.clone();
+    // pays or accrues each leg of the given pay in, every leg is recorded as a Payment carrying the pay in reference as its 'linked_reference'
+    fn split_pay_in(&mut self, pay_in : &ob_io::Payment, split_rule : ob_io::SplitRule, pot_name : &str) {
+        let leg_amounts = split_rule.get_leg_amounts(pay_in.net_amount);
+        // while outflows are paused immediate legs are accrued so that the bank still takes pay ins 
+        let pay_immediately = split_rule.distribution == "IMMEDIATE" && !self.outflows_paused;
+        for (leg, amount) in split_rule.legs.iter().zip(leg_amounts) {
+            if amount == 0 {
+                continue;
+            }
+            // accrued legs are held apart until they are distributed 
+            if pay_immediately {
+                self.decrement_pot_balance(pot_name, amount, "EXPENSES");
+            }
+            else {
+                self.decrement_pot_balance(pot_name, amount, "HELD_FUNDS");
+                self.post_journal_entry("EXPENSES", "PAYABLES", amount);
+            }
 
-        self.request_debit_by_reference.insert(request_debit.reference, request_debit);
-        
-        if !self.request_debits_by_status.contains_key( &rd_clone.status) {
-            let mut debit_list = HashSet::<RequestDebit>::new(); 
-            let status = rd_clone.status.clone();
-            debit_list.insert(rd_clone);
-            self.request_debits_by_status.insert(status,debit_list);
+            let payment = if pay_immediately {
+                self.pay_to(leg.payee.clone(), 
+                            pay_in.signer.clone(), 
+                            amount, 
+                            pay_in.description.clone(), 
+                            "REVENUE_SPLIT".to_string())
+            }
+            else {
+                *self.split_accruals.entry(leg.payee.clone()).or_default() += amount;
+                self.split_balance += amount;
+                self.create_and_register_payment(leg.payee.clone(), 
+                                                self.bank_deployed_account_id.clone(), 
+                                                pay_in.signer.clone(), 
+                                                amount, 
+                                                pay_in.description.clone(), 
+                                                "ACCRUED".to_string(), 
+                                                "REVENUE_SPLIT".to_string())
+            };
+            self.link_payment(payment, pay_in.reference);
         }
-        else {
-            self.request_debits_by_status.get_mut(&rd_clone.status).unwrap().insert(rd_clone);
+    }
+
+    // categories must be 'ACTIVE' in the chart of payment categories, external references and metadata must be within their size limits
+    fn check_payment_annotation(&mut self, annotation : &Option<PaymentAnnotation>) {
+        let annotation = match annotation {
+            Some(annotation) => annotation,
+            None => return,
+        };
+        if let Some(category) = &annotation.category {
+            match self.payment_categories.get(category) {
+                Some(payment_category) if payment_category.status == "ACTIVE" => {},
+                _ => panic!("UNKNOWN PAYMENT CATEGORY {} ", category),
+            }
+        }
+        if let Some(external_reference) = &annotation.external_reference {
+            if external_reference.len() > MAX_EXTERNAL_REFERENCE_LENGTH {
+                panic!("EXTERNAL REFERENCE TOO LONG. MAXIMUM LENGTH: {} ACTUAL LENGTH: {}", MAX_EXTERNAL_REFERENCE_LENGTH, external_reference.len());
+            }
+        }
+        if let Some(metadata) = &annotation.metadata {
+            if metadata.len() > MAX_METADATA_ENTRIES {
+                panic!("TOO MANY METADATA ENTRIES. MAXIMUM ENTRIES: {} ACTUAL ENTRIES: {}", MAX_METADATA_ENTRIES, metadata.len());
+            }
+            for (key, value) in metadata {
+                if key.is_empty() || key.len() > MAX_METADATA_KEY_LENGTH || value.len() > MAX_METADATA_VALUE_LENGTH {
+                    panic!("INVALID METADATA ENTRY {}. MAXIMUM KEY LENGTH: {} MAXIMUM VALUE LENGTH: {}", key, MAX_METADATA_KEY_LENGTH, MAX_METADATA_VALUE_LENGTH);
+                }
+            }
+        }
+        if let Some(encrypted_memo) = &annotation.encrypted_memo {
+            self.check_encrypted_memo(encrypted_memo);
         }
-        U64(rd_reference)
     }
 
-    /// This operation will 'approve' the 'RequestDebit' associated with the given 'request_debit_ref'. Once approved the 'RequestDebit can be drawn down after the start date 
-    /// This operation is [**governed**], [**non-payable**] 
-    /// # Return Value
-    pub fn approve_request_debit(&mut self, request_debit_ref : U64, nonce: U64) -> U64 {
-        
-        self.check_nonce(u64::from(nonce));
+    // encrypted memos must be sealed for the current memo public key and hold no more than a description 
+    fn check_encrypted_memo(&self, encrypted_memo : &ob_io::EncryptedMemo) {
+        if self.memo_public_key.is_empty() || encrypted_memo.bank_public_key != self.memo_public_key {
+            panic!("ENCRYPTED MEMO SEALED FOR UNKNOWN KEY {} ", encrypted_memo.bank_public_key);
+        }
+        if OpenBank::get_decoded_length(&encrypted_memo.sender_public_key) != Some(MEMO_KEY_LENGTH) || OpenBank::get_decoded_length(&encrypted_memo.nonce) != Some(MEMO_NONCE_LENGTH) {
+            panic!("INVALID ENCRYPTED MEMO. SENDER PUBLIC KEY {} NONCE {}", encrypted_memo.sender_public_key, encrypted_memo.nonce);
+        }
+        match OpenBank::get_decoded_length(&encrypted_memo.ciphertext) {
+            Some(length) if (MEMO_TAG_LENGTH..=MAX_DESCRIPTION_LENGTH + MEMO_TAG_LENGTH).contains(&length) => {},
+            _ => panic!("INVALID ENCRYPTED MEMO. MAXIMUM MEMO LENGTH: {}", MAX_DESCRIPTION_LENGTH),
+        }
+    }
 
-        // do security
-        let signer_account_id = env::signer_account_id();
-        let security_response = self.is_secure("approve_request_debit".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("REQUEST DEBIT APPROVAL STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+    // the number of bytes in the given base64 value, None if it is not base64 
+    fn get_decoded_length(value : &str) -> Option<usize> {
+        base64::decode(value).ok().map(|bytes| bytes.len())
+    }
 
-        self.check_is_valid_request_debit_reference(u64::from(request_debit_ref));
-        
-        let mut request_debit = self.find_request_debit(request_debit_ref);
-        
-        let evacuating_status = request_debit.status.clone(); 
+    fn check_description(description : &str) {
+        if description.len() > MAX_DESCRIPTION_LENGTH {
+            panic!("DESCRIPTION TOO LONG. MAXIMUM LENGTH: {} ACTUAL LENGTH: {}", MAX_DESCRIPTION_LENGTH, description.len());
+        }
+    }
 
-        self.check_request_debit_status(evacuating_status.clone(), "PENDING".to_string());
+    fn annotate_payment(&mut self, mut payment : ob_io::Payment, annotation : Option<PaymentAnnotation>) -> ob_io::Payment {
+        let annotation = match annotation {
+            Some(annotation) if annotation != PaymentAnnotation::default() => annotation,
+            _ => return payment,
+        };
+        payment.category = annotation.category.unwrap_or_default();
+        payment.tags = annotation.tags;
+        payment.external_reference = annotation.external_reference.unwrap_or_default();
+        payment.metadata = annotation.metadata.unwrap_or_default();
+        payment.encrypted_memo = annotation.encrypted_memo;
+        self.update_payment(payment.clone());
+        payment
+    }
 
-        request_debit.status = "APPROVED".to_string();
+    fn check_escrow_request(&mut self, escrow : &ob_io::EscrowRequest) {
+        OpenBank::check_description(&escrow.description);
+        if escrow.timeout_action != "RELEASE" && escrow.timeout_action != "REFUND" {
+            panic!("UNKNOWN TIMEOUT ACTION {} ", escrow.timeout_action);
+        }
+        if escrow.amount == 0 || escrow.expiry <= env::block_timestamp() as i64 {
+            panic!("INVALID ESCROW TERMS. AMOUNT {}, EXPIRY {}", escrow.amount, escrow.expiry);
+        }
+        if escrow.approvals_required > escrow.approvers.len() as u64 || (!escrow.approvers.is_empty() && escrow.approvals_required == 0) {
+            panic!("INVALID ESCROW TERMS. APPROVALS REQUIRED {}, APPROVERS {}", escrow.approvals_required, escrow.approvers.len());
+        }
+        if escrow.approvers.is_empty() && escrow.release_operation.is_empty() && escrow.timeout_action != "RELEASE" {
+            panic!("INVALID ESCROW TERMS. NO RELEASE CONDITION");
+        }
+    }
 
-        let rd_reference = request_debit.reference.clone();
-        
-        println!(" request debit status {} reference {} ", request_debit.status, request_debit.reference );
+    fn release_held_escrow(&mut self, mut escrow : ob_io::Escrow, signer_account_id : String) -> ob_io::Payment {
+        self.escrow_balance -= escrow.amount;
+        self.release_held_funds(escrow.amount, "ESCROW");
 
-        // overwrite 
-        self.request_debit_by_reference.insert(rd_reference.clone(), request_debit);
+        let payment = self.pay_to(escrow.payee.clone(), 
+                                signer_account_id, 
+                                escrow.amount, 
+                                escrow.description.clone(), 
+                                "ESCROW_RELEASE".to_string());
+        let payment = self.link_payment(payment, escrow.reference);
 
-        let rd = self.find_request_debit(U64(rd_reference.clone()));
+        escrow.settlement_payment_reference = payment.reference;
+        escrow.settlement_date = env::block_timestamp() as i64;
+        escrow.status = "RELEASED".to_string();
+        self.escrows.insert(escrow.reference, escrow);
 
-        println!(" request debit status {} reference {} ", rd.status.clone(), rd.reference.clone() );
+        payment
+    }
 
-        self.move_request_debit_by_status(evacuating_status, rd.status.clone(), rd);
+    // escrows go back to where they were funded from
+    fn refund_held_escrow(&mut self, mut escrow : ob_io::Escrow, signer_account_id : String) -> ob_io::Escrow {
+        self.escrow_balance -= escrow.amount;
 
-        U64(rd_reference)
+        if escrow.funding == "PAY_IN" {
+            self.release_held_funds(escrow.amount, "ESCROW");
+            let payment = self.pay_to(escrow.payer.clone(), 
+                                    signer_account_id, 
+                                    escrow.amount, 
+                                    escrow.description.clone(), 
+                                    "ESCROW_REFUND".to_string());
+            escrow.settlement_payment_reference = self.link_payment(payment, escrow.reference).reference;
+        }
+        else {
+            self.return_set_aside_funds(&escrow.pot, escrow.amount, "ESCROW", escrow.reference, escrow.description.clone());
+        }
+
+        escrow.settlement_date = env::block_timestamp() as i64;
+        escrow.status = "REFUNDED".to_string();
+        self.escrows.insert(escrow.reference, escrow.clone());
+
+        escrow
     }
-    
-    /// This operation will 'cancel' the 'RequestDebit' associated with the given 'request_debit_ref'. Cancellation can happen at any point in the 'RequestDebit' lifecycle 
-    /// This operation is [**governed**], [**non-payable**]
-    /// # Return Value
-    /// Reference of the cancelled Request Debit
-    pub fn cancel_request_debit(&mut self, request_debit_ref : U64, nonce : U64) -> U64 {
-        
-        self.check_nonce(u64::from(nonce));
 
-        let signer_account_id = env::signer_account_id();
+    fn check_vesting_grant_request(&mut self, grant : &ob_io::VestingGrantRequest) {
+        if grant.release_type != "LINEAR" && grant.release_type != "STEPPED" {
+            panic!("UNKNOWN RELEASE TYPE {} ", grant.release_type);
+        }
+        if grant.total == 0 || grant.end_date <= grant.start_date || grant.cliff_date > grant.end_date {
+            panic!("INVALID VESTING TERMS. TOTAL {}, START DATE {}, CLIFF DATE {}, END DATE {}", grant.total, grant.start_date, grant.cliff_date, grant.end_date);
+        }
+        if grant.release_type == "STEPPED" && grant.step_interval <= 0 {
+            panic!("INVALID VESTING TERMS. STEP INTERVAL {}", grant.step_interval);
+        }
+    }
 
-        // do security 
-        let security_response = self.is_secure("request_debit".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("REQUEST DEBIT CANCELLATION STOPPED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+    fn check_is_valid_request_debit_reference(&mut self, request_debit_ref: u64 ) {
+        if !self.request_debit_by_reference.contains_key(&request_debit_ref) {
+            panic!("REQUEST DEBIT REFERENCE NOT FOUND. REFERENCE PRESENTED: {}", request_debit_ref);    
+        }
+    }
 
-        self.check_is_valid_request_debit_reference(u64::from(request_debit_ref));
-        
-        let mut request_debit = self.find_request_debit(request_debit_ref);
+    fn check_request_debit_status(&mut self, currenct_status : String, required_status : String){
+        if currenct_status.as_bytes() != required_status.as_bytes() { 
+            panic!("INVALID STATUS FOR ACTION. REQUIRED STATUS : {}, ACTUAL STATUS : {} ", required_status, currenct_status);
+        }
+    }
 
-        let evacuating_status = request_debit.status.clone(); 
-        
-        request_debit.status = "CANCELLED".to_string();
-        
-        let rd_reference = U64(request_debit.reference.clone());
-        
-        // overwrite 
-        self.request_debit_by_reference.insert(u64::from(rd_reference.clone()), request_debit);
+    fn check_attachment_vs_stated_amount(&mut self, attached_amount : u128, stated_amount : u128){
+        if attached_amount != stated_amount{
+            panic!("DEPOSIT MIS-MATCH. STATED AMOUNT: {} ATTACHED AMOUNT: {}.", stated_amount, attached_amount);
+        }
+    }
 
-        let rd = self.find_request_debit(rd_reference);
+    fn check_request_debit_interval(&mut self, request_debit : ob_io::RequestDebit){
+        let time_now = env::block_timestamp() as i64;
+        if request_debit.start_date > time_now {            
+            panic!("REQUEST DEBIT CLAIM PERIOD NOT STARTED. TIME NOW {}, CLAIM PERIOD START DATE {}.",time_now, request_debit.start_date);
+        }
 
-        self.move_request_debit_by_status( evacuating_status, rd.status.clone(), rd);
+        if request_debit.end_date < time_now {
+            panic!("REQUEST DEBIT CLAIM PERIOD EXPIRED. TIME NOW {}, CLAIM PERIOD END DATE {}.",time_now, request_debit.end_date);
+        }
 
-        rd_reference
-    } 
+        let next_payment = OpenBank::get_next_request_debit_due_date(&request_debit);
+    
+        if  next_payment > time_now {
+            panic!("PAY OUT INTERVAL NOT REACHED. TIME NOW {}, LAST PAID {}, INTERVAL REQUIRED {}.",time_now, request_debit.last_paid, request_debit.payout_interval);
+        }
+    }
 
-    /// This operation will 'deposit' the attached funds into this bank and increment the balance of this bank.
-    /// This operation is oriented towards internal business payments into the bank as opposed to external 'pay in' 
-    /// The governance of this operation allows the 'nominee_account_id' to make deposits at any time 
-    /// [**governed**], [**payable**]
-    /// # Return Value
-    #[payable]
-    pub fn deposit(&mut self, description : String, amount : U128, nonce : U64) -> ob_io::Payment {
-        
-        // check nonce 
-        self.check_nonce(u64::from(nonce));
+    fn get_due_request_debits(&self) -> Vec<ob_io::RequestDebit> {
+        let time_now = env::block_timestamp() as i64;
+        let mut due_debits : Vec<ob_io::RequestDebit> = match self.request_debits_by_status.get("APPROVED") {
+            Some(debits) => debits.iter().filter(|rd| OpenBank::is_request_debit_due(rd, time_now)).cloned().collect(),
+            None => Vec::new(),
+        };
+        due_debits.sort_by_key(|rd| (OpenBank::get_next_request_debit_due_date(rd), rd.reference));
+        due_debits
+    }
 
-        let signer_account_id = env::signer_account_id();
-        
-        let security_response = self.is_secure("deposit".to_string(), "ALLOWED".to_string());
+    // mirrors the checks made by check_request_debit_interval without panicking 
+    fn is_request_debit_due(request_debit : &ob_io::RequestDebit, time_now : i64) -> bool {
+        request_debit.start_date <= time_now 
+            && request_debit.end_date >= time_now 
+            && OpenBank::get_next_request_debit_due_date(request_debit) <= time_now
+    }
 
-        self.require(signer_account_id.as_bytes() == self.nominee_account_id.as_bytes() || security_response, format!("DEPOSIT CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+    // pays the given RequestDebit and records when it was paid, the caller is responsible for the status, interval and balance checks 
+    fn settle_request_debit(&mut self, mut request_debit : ob_io::RequestDebit, signer_account_id : String) -> ob_io::Payment {
+        // decrement the pot and bank balance
+        self.decrement_pot_balance(&request_debit.pot, request_debit.amount, "EXPENSES");
 
-        // check transfer amount
-        let attached_deposit = near_sdk::env::attached_deposit();
+        // update the request_debit last paid date to now 
+        request_debit.last_paid = env::block_timestamp() as i64;
+        self.update_request_debit(request_debit.clone());
 
-        let stated_deposit = u128::from(amount);
+        // charge the fee, this is deducted from the payout
+        let fee = self.fee_schedule.request_debit.get_fee(request_debit.amount);
+        self.charge_fee(fee);
 
-        self.check_attachment_vs_stated_amount(attached_deposit, stated_deposit);
+        // pay to the payee
+        let payment = self.pay_to_net_of_fee(    request_debit.payee, 
+                        signer_account_id, 
+                        request_debit.amount, 
+                        fee, 
+                        request_debit.description,
+                        "REQUEST_DEBIT".to_string());
+        self.annotate_payment(payment, Some(request_debit.annotation))
+    }
 
-        // increase the bank balance
-        self.increment_bank_balance(stated_deposit);
+    // lists the payment date and amount of each payout still to be made by the 'ACTIVE' ScheduledPayouts up to 'time' 
+    fn get_scheduled_payout_obligations(&self, time : i64) -> Vec<(i64, u128)> {
+        let mut obligations = Vec::<(i64, u128)>::new();
+        for scheduled_payout in self.scheduled_payouts.values().filter(|sp| sp.status == "ACTIVE") {
+            let mut index = scheduled_payout.payments_made;
+            let mut payment_date = scheduled_payout.next_payment_date;
+            while payment_date <= time && scheduled_payout.is_payment_date_in_schedule(payment_date, index) {
+                obligations.push((payment_date, scheduled_payout.amount));
+                index += 1;
+                payment_date = scheduled_payout.get_payment_date(index);
+            }
+        }
+        obligations
+    }
 
-        // log the payment
-        let current_account_id = env::current_account_id();
-        self.create_and_register_payment( self.bank_deployed_account_id.clone(), 
-                                          current_account_id, 
-                                                signer_account_id, 
-                                                stated_deposit, 
-                                                description, 
-                                   "COMPLETED".to_string(),
-                                     "DEPOSIT".to_string())
+    fn get_due_scheduled_payouts(&self) -> Vec<ob_io::ScheduledPayout> {
+        let time_now = env::block_timestamp() as i64;
+        let mut due_payouts : Vec<ob_io::ScheduledPayout> = self.scheduled_payouts.values().filter(|sp| sp.status == "ACTIVE" && sp.next_payment_date <= time_now).cloned().collect();
+        due_payouts.sort_by_key(|sp| (sp.next_payment_date, sp.reference));
+        due_payouts
     }
-   
-    /// This operation will 'withdraw' the given amout to the 'nominee_account_id' 
-    /// This operation can be called by the 'nominee_account_id' at any time 
-    /// This operation is [**governed**], [**non-payable**] 
-    /// # Return Value
-    pub fn withdraw(&mut self, description : String, amount : U128, nonce : U64) -> ob_io::Payment {
-        // check nonce 
-        self.check_nonce(u64::from(nonce));
 
-        let signer_account_id = env::signer_account_id();
+    // pays the next instalment of the given ScheduledPayout and moves it on to the following payment date, the caller is responsible for the balance check 
+    fn settle_scheduled_payout(&mut self, mut scheduled_payout : ob_io::ScheduledPayout, signer_account_id : String) -> ob_io::Payment {
+        self.decrement_pot_balance(&scheduled_payout.pot, scheduled_payout.amount, "EXPENSES");
 
-        // do security
-        let security_response = self.is_secure("withdraw".to_string(), "ALLOWED".to_string());
-        self.require(signer_account_id.as_bytes() == self.nominee_account_id.as_bytes() || security_response, format!("Account {} not allowed ", signer_account_id));               
-     
-        let withdrawal_amount = u128::from(amount) ;
-        
-        // check balance can afford it 
-        self.check_bank_balance(withdrawal_amount);
+        scheduled_payout.payments_made += 1;
+        scheduled_payout.last_paid = env::block_timestamp() as i64;
+        let next_payment_date = scheduled_payout.get_payment_date(scheduled_payout.payments_made);
+        if scheduled_payout.is_payment_date_in_schedule(next_payment_date, scheduled_payout.payments_made) {
+            scheduled_payout.next_payment_date = next_payment_date;
+        }
+        else {
+            scheduled_payout.status = "COMPLETED".to_string();
+        }
+        self.scheduled_payouts.insert(scheduled_payout.reference, scheduled_payout.clone());
 
-        self.decrement_bank_balance(withdrawal_amount);
+        let payment = self.pay_to(scheduled_payout.payee, 
+                                signer_account_id, 
+                                scheduled_payout.amount, 
+                                scheduled_payout.description, 
+                                "STANDING_ORDER".to_string());
+        self.link_payment(payment, scheduled_payout.reference)
+    }
 
-        // pay to the nominee account
-        self.pay_to( self.nominee_account_id.clone(), 
-                            signer_account_id, 
-                            withdrawal_amount, 
-                            description, 
-                            "WITHDRAWAL".to_string())
+    fn has_gas_for_crank_step(&self) -> bool {
+        env::prepaid_gas() - env::used_gas() > CRANK_STEP_GAS
     }
 
-    /// This operation will set the 'bank_name' for this bank 
-    /// This operation is [**'governed'**], [**non-payable**]
-    /// # Return Value
-    /// 
-    pub fn set_open_bank_name(&mut self, bank_name : String) -> bool {
-        let signer_account_id = env::signer_account_id();
+    fn get_next_request_debit_due_date(request_debit : &ob_io::RequestDebit) -> i64 {
+        if request_debit.last_paid > 0 {
+            return request_debit.last_paid + request_debit.payout_interval;
+        }
+        request_debit.start_date + request_debit.payout_interval
+    }
 
-        // do security
-        let security_response = self.is_secure("set_open_bank_name".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        
-        // run assignment
-        self.bank_name = bank_name; 
-        true
+    // counts the payments of the given RequestDebit that will have fallen due by 'time' including one that is due but not yet claimed. 
+    // Settling a RequestDebit pays a single amount and starts the next interval from the claim, so missed intervals collapse into one claim made now 
+    fn count_request_debit_payments_due_by(request_debit : &ob_io::RequestDebit, time : i64) -> u64 {
+        let first_claim = OpenBank::get_next_request_debit_due_date(request_debit).max(env::block_timestamp() as i64);
+        let last_due = time.min(request_debit.end_date);
+        if last_due < first_claim {
+            return 0;
+        }
+        if request_debit.payout_interval <= 0 {
+            return 1;
+        }
+        ((last_due - first_claim) / request_debit.payout_interval) as u64 + 1
     }
 
-    /// This operation will set the 'nominee_account_id' for this bank 
-    /// This operation is [**'governed'**], [**non-payable**] 
-    /// # Return Value
-    /// 
-    pub fn set_open_bank_nominee_account(&mut self, nominee_account_id : String) -> bool {
-        let signer_account_id = env::signer_account_id();
+    fn decrement_bank_balance(&mut self, amount : u128, to_account : &str) {
+        self.decrement_pot_balance(DEFAULT_POT, amount, to_account);
+    }
+
+    fn increment_bank_balance(&mut self, amount : u128, from_account : &str) {
+        self.increment_pot_balance(DEFAULT_POT, amount, from_account);
+    }
+
+    // the bank balance only ever moves with a pot balance so that the pots always sum to the bank balance
+    // and every move is posted to the journal against the CASH account so that CASH always equals the bank balance
+    fn decrement_pot_balance(&mut self, pot_name : &str, amount : u128, to_account : &str) {
+        self.post_journal_entry(to_account, "CASH", amount);
+        self.get_pot_mut(pot_name).balance -= amount;
+        let previous_balance = self.bank_balance;
+        self.bank_balance -= amount;
+
+        // warn only as the balance crosses the warning level
+        let warning_level = self.treasury_policy.warning_level;
+        if previous_balance >= warning_level && self.bank_balance < warning_level {
+            let low_balance_event = ob_io::LowBalanceEvent {
+                balance         : self.bank_balance,
+                warning_level,
+                event_date      : env::block_timestamp() as i64,
+            };
+            env::log(format!("LOW BALANCE WARNING. BALANCE: {} WARNING LEVEL: {}", low_balance_event.balance, warning_level).as_bytes());
+            self.low_balance_events.push(low_balance_event);
+        }
+    }
 
-         // do security
-        let security_response = self.is_secure("set_obei_nominee_acccount".to_string(), "ALLOWED".to_string()); 
-        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        
-        // run assignment
-        self.nominee_account_id = nominee_account_id; 
-        true
+    fn increment_pot_balance(&mut self, pot_name : &str, amount : u128, from_account : &str) {
+        self.post_journal_entry("CASH", from_account, amount);
+        self.get_pot_mut(pot_name).balance += amount;
+        self.bank_balance += amount;
     }
 
-    /// This operation will set the **'obei_or_near_core'** (Open Roles) 'account_id'. All governance calls will be made to this 'account_id'
-    /// This operation is [**'governed'**], [**non-payable**]
-    /// # Return Value
-    /// 
-    pub fn set_obei_open_roles(&mut self, open_roles_account_id : String) -> bool {
-        let signer_account_id = env::signer_account_id();
+    // fees are held apart from the bank balance until they are withdrawn
+    fn charge_fee(&mut self, fee : u128) {
+        self.hold_funds(fee, "FEES");
+        self.fee_balance += fee;
+    }
 
-        // do security
-        let security_response = self.is_secure("set_obei_open_roles".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
+    // funds held apart from the bank balance are the 'HELD_FUNDS' asset, owed under the given liability 'account'
+    fn hold_funds(&mut self, amount : u128, account : &str) {
+        self.post_journal_entry("HELD_FUNDS", account, amount);
+    }
 
-        // run assignment
-        self.access_security = open_roles_account_id.to_string(); 
-        
-        true
+    fn release_held_funds(&mut self, amount : u128, account : &str) {
+        self.post_journal_entry(account, "HELD_FUNDS", amount);
     }
 
-    /// This operation will set the affirmative security code for role management 
-    /// # Return Value 
-    /// **Numeric** representing new security code
-    pub fn set_affirmative_secure_code(&mut self, affirmative_secure_code : i32) -> i32 {
-        let signer_account_id = env::signer_account_id();
-        let security_response = self.is_secure("set_affirmative_secure_code".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        self.affirmative_code = affirmative_secure_code; 
-        self.affirmative_code.clone()
+    // moves funds out of the given pot to be held apart under the given liability 'account', the funds are spent once they are set aside 
+    fn set_aside_funds(&mut self, pot_name : &str, amount : u128, account : &str, linked_reference : u64, description : String) -> ob_io::Payment {
+        self.decrement_pot_balance(pot_name, amount, "HELD_FUNDS");
+        self.post_journal_entry("EXPENSES", account, amount);
+        let payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                        self.bank_deployed_account_id.clone(), 
+                                                        env::signer_account_id(), 
+                                                        amount, 
+                                                        description, 
+                                                        "COMPLETED".to_string(), 
+                                                        "SET_ASIDE".to_string());
+        self.link_payment(payment, linked_reference)
     }
 
-    /// This operation will set the negative security code for role management 
-    /// # Return value 
-    /// **Numeric** representing new security code
-    pub fn set_negative_secure_code(&mut self, negative_secure_code : i32) -> i32 {
-        let signer_account_id = env::signer_account_id();
-        let security_response = self.is_secure("set_negative_secure_code".to_string(), "ALLOWED".to_string());
-        self.require(security_response, format!("OPERATION CANCELLED. ACCOUNT {} NOT ALLOWED", signer_account_id));
-        self.negative_code = negative_secure_code; 
-        self.negative_code.clone()
+    // returns funds held apart under the given liability 'account' to the given pot, reversing the expense of setting them aside 
+    fn return_set_aside_funds(&mut self, pot_name : &str, amount : u128, account : &str, linked_reference : u64, description : String) {
+        if amount == 0 {
+            return;
+        }
+        self.increment_pot_balance(pot_name, amount, "HELD_FUNDS");
+        self.post_journal_entry(account, "EXPENSES", amount);
+        let payment = self.create_and_register_payment(self.bank_deployed_account_id.clone(), 
+                                                        self.bank_deployed_account_id.clone(), 
+                                                        env::signer_account_id(), 
+                                                        amount, 
+                                                        description, 
+                                                        "COMPLETED".to_string(), 
+                                                        "SET_ASIDE_RETURN".to_string());
+        self.link_payment(payment, linked_reference);
     }
 
+    // credit owed to a customer becomes revenue as it is charged and joins the bank balance 
+    fn recognise_credit(&mut self, amount : u128) {
+        self.post_journal_entry("PAYABLES", "REVENUE", amount);
+        self.increment_bank_balance(amount, "HELD_FUNDS");
+    }
 
-    /// This operaion will deactivate test mode on this bank. Once deactivated this cannot be reactivated 
-    /// # Return Value 
-    /// **true** if and only if test mode has been deactivated
-    pub fn deactivate_test_mode(&mut self)-> bool {
-        self.test_mode = false; 
-        self.test_mode
+    fn post_journal_entry(&mut self, debit_account : &str, credit_account : &str, amount : u128) {
+        if amount == 0 {
+            return;
+        }
+        let journal_entry = ob_io::JournalEntry {
+            sequence            : self.journal.len() as u64 + 1,
+            debit_account       : debit_account.to_string(),
+            credit_account      : credit_account.to_string(),
+            amount,
+            entry_date          : env::block_timestamp() as i64,
+            payment_reference   : 0,
+        };
+        self.ledger_accounts.entry(journal_entry.debit_account.clone()).or_insert_with(|| ob_io::LedgerAccount::create_ledger_account(debit_account.to_string())).debits += amount;
+        self.ledger_accounts.entry(journal_entry.credit_account.clone()).or_insert_with(|| ob_io::LedgerAccount::create_ledger_account(credit_account.to_string())).credits += amount;
+        self.journal.push(journal_entry);
     }
 
-    fn pay_to( &mut self, 
-                payee : String, 
-                signer : String, 
-                payment_amount : u128, 
-                payment_description : String,
-                payment_type : String ) -> ob_io::Payment { 
-                
-                // transfer funds to payee                
-                Promise::new(payee.clone()).transfer(payment_amount);
-                
-                self.require(env::promise_results_count() == 1, "pay_to:01 PROMISE FAILURE ".to_string());
-                let res  = match env::promise_result(0) {
-                                       PromiseResult::Successful(x) => x,
-                                        _ => Vec::<u8>::new(),
-                                    };
-                let transfer_code = res[0];
+    fn get_payment(&self, payment_ref : u64) -> ob_io::Payment {
+        match self.payments_by_reference.get(&payment_ref) {
+            Some(payment) => payment.clone(),
+            None => panic!("UNKNOWN PAYMENT REFERENCE {} ", payment_ref),
+        }
+    }
 
-                let payment_status : String = transfer_code.to_string();
+    fn get_request_debit(&self, request_debit_reference : u64) -> ob_io::RequestDebit {
+        match self.request_debit_by_reference.get(&request_debit_reference) {
+            Some(request_debit) => request_debit.clone(),
+            None => panic!("UNKNOWN REQUEST DEBIT REFERENCE {} ", request_debit_reference),
+        }
+    }
 
-                // log the payment
-                self.create_and_register_payment( payee, 
-                                            self.bank_deployed_account_id.clone(),
-                                            signer, 
-                                            payment_amount, 
-                                            payment_description,
-                                            payment_status,
-                                            payment_type)
-                                    
+    fn get_request_debits_by_status(&self, status : String) -> HashSet<ob_io::RequestDebit> {
+        match self.request_debits_by_status.get(&status) {
+            Some(request_debits) => request_debits.clone(),
+            None => panic!("UNKNOWN REQUEST DEBIT STATUS {} ", status),
+        }
     }
 
-    fn create_and_register_payment(&mut self,   
-                                payee : String, 
-                                payer : String, 
-                                signer : String, 
-                                amount : u128, 
-                                description : String,
-                                payment_status : String,
-                                payment_type : String) -> ob_io::Payment {
-        
-        let payment = ob_io::Payment::create_payment ( payee,
-                                                            payer,
-                                                            signer, 
-                                                            amount,
-                                                            description,
-                                                            payment_type,
-                                                            payment_status); 
-                                                            
-        self.payments.insert(payment.clone());
-        self.payments_by_reference.insert(payment.reference, payment.clone());
+    fn get_scheduled_payout(&self, scheduled_payout_reference : u64) -> ob_io::ScheduledPayout {
+        match self.scheduled_payouts.get(&scheduled_payout_reference) {
+            Some(scheduled_payout) => scheduled_payout.clone(),
+            None => panic!("UNKNOWN SCHEDULED PAYOUT REFERENCE {} ", scheduled_payout_reference),
+        }
+    }
 
-        payment
+    fn get_scheduled_payouts_by_status(&self, status : String) -> Vec<ob_io::ScheduledPayout> {
+        let mut scheduled_payouts : Vec<ob_io::ScheduledPayout> = self.scheduled_payouts.values().filter(|sp| sp.status == status).cloned().collect();
+        scheduled_payouts.sort_by_key(|sp| (sp.next_payment_date, sp.reference));
+        scheduled_payouts
     }
 
-    fn require(&mut self, condition : bool, message : String) -> bool {
-        if !condition {
-            panic!("{}", message);
+    fn get_payroll_run(&self, payroll_run_reference : u64) -> ob_io::PayrollRun {
+        match self.payroll_runs.get(&payroll_run_reference) {
+            Some(payroll_run) => payroll_run.clone(),
+            None => panic!("UNKNOWN PAYROLL RUN REFERENCE {} ", payroll_run_reference),
         }
-        true
     }
 
-    fn is_secure(&mut self, operation : String, mode : String) -> bool {
-        
-        if self.test_mode {
-           return self.test_mode;
+    fn get_payroll_runs_by_status(&self, status : String) -> Vec<ob_io::PayrollRun> {
+        let mut payroll_runs : Vec<ob_io::PayrollRun> = self.payroll_runs.values().filter(|pr| pr.status == status).cloned().collect();
+        payroll_runs.sort_by_key(|pr| (pr.creation_date, pr.reference));
+        payroll_runs
+    }
+
+    fn get_vesting_grant(&self, vesting_grant_reference : u64) -> ob_io::VestingGrant {
+        match self.vesting_grants.get(&vesting_grant_reference) {
+            Some(vesting_grant) => vesting_grant.clone(),
+            None => panic!("UNKNOWN VESTING GRANT REFERENCE {} ", vesting_grant_reference),
         }
+    }
 
-        let security_response = false; 
+    fn get_vesting_grants_by_beneficiary(&self, beneficiary : String) -> Vec<ob_io::VestingGrant> {
+        let mut vesting_grants : Vec<ob_io::VestingGrant> = self.vesting_grants.values().filter(|vg| vg.beneficiary == beneficiary).cloned().collect();
+        vesting_grants.sort_by_key(|vg| (vg.creation_date, vg.reference));
+        vesting_grants
+    }
 
-        let signer_account_id = env::signer_account_id(); 
+    fn get_vested_amounts(vesting_grant : &ob_io::VestingGrant) -> (U128, U128) {
+        let time_now = env::block_timestamp() as i64;
+        (U128(vesting_grant.get_vested_amount(time_now)), U128(vesting_grant.get_claimable_amount(time_now)))
+    }
 
-        if  mode.as_bytes() == "ALLOWED".to_string().as_bytes() {
-           
-            ext_open_roles::is_allowed(self.access_security.clone(), 
-                                        self.bank_deployed_account_id.clone(), 
-                                        self.bank_name.clone(), 
-                                        operation.clone(), 
-                                        &signer_account_id, 
-                                        NO_DEPOSIT, 
-                                        BASE_GAS); 
-                                        let role_response : i32;
-                                        match env::promise_result(0) {
-                                           near_sdk::PromiseResult::Successful(x) => role_response = num::ToPrimitive::to_i32(x.get(0).unwrap()).unwrap(),
-                                            _ => panic!("is_secure::01 :- PROMISE FAILURE FOR SECURITY REQUEST :: OR LOCATION {} - CONTRACT ACCOUNT {} - CONTRACT {} - OPERATION {} - USER ACCOUNT {}",self.access_security.clone(),self.bank_deployed_account_id.clone(), self.bank_name.clone(), operation.clone(), &signer_account_id),
-                                        };
-                                        
-                                        if role_response == self.affirmative_code {
-                                            return true; 
-                                        }
-            return security_response; 
+    fn get_payment_stream(&self, payment_stream_reference : u64) -> ob_io::PaymentStream {
+        match self.payment_streams.get(&payment_stream_reference) {
+            Some(payment_stream) => payment_stream.clone(),
+            None => panic!("UNKNOWN PAYMENT STREAM REFERENCE {} ", payment_stream_reference),
         }
-        else {
+    }
 
-            ext_open_roles::is_barred(self.access_security.clone(), 
-                                        self.bank_deployed_account_id.clone(), 
-                                        self.bank_name.clone(), 
-                                        operation.clone(), 
-                                        &signer_account_id, 
-                                        NO_DEPOSIT, 
-                                        BASE_GAS); 
-                                        let role_response : i32;
-                                        match env::promise_result(0) {
-                                           near_sdk::PromiseResult::Successful(x) => role_response = num::ToPrimitive::to_i32(x.get(0).unwrap()).unwrap(),
-                                            _ => panic!("is_secure::02 :- PROMISE FAILURE FOR SECURITY REQUEST :: OR LOCATION {} - CONTRACT ACCOUNT {} - CONTRACT {} - OPERATION {} - USER ACCOUNT {}",self.access_security.clone(),self.bank_deployed_account_id.clone(), self.bank_name.clone(), operation.clone(), &signer_account_id),
-                                        };
-                                        
-                                        if role_response == self.negative_code {
-                                            return true; 
-                                        }
-            return security_response; 
+    fn get_payment_streams_by_recipient(&self, recipient : String) -> Vec<ob_io::PaymentStream> {
+        let mut payment_streams : Vec<ob_io::PaymentStream> = self.payment_streams.values().filter(|ps| ps.recipient == recipient).cloned().collect();
+        payment_streams.sort_by_key(|ps| (ps.creation_date, ps.reference));
+        payment_streams
+    }
+
+    fn get_payment_stream_amounts(payment_stream : &ob_io::PaymentStream) -> (U128, U128) {
+        (U128(payment_stream.get_accrued_amount(env::block_timestamp() as i64)), U128(payment_stream.withdrawn))
+    }
+
+    fn get_escrow(&self, escrow_reference : u64) -> ob_io::Escrow {
+        match self.escrows.get(&escrow_reference) {
+            Some(escrow) => escrow.clone(),
+            None => panic!("UNKNOWN ESCROW REFERENCE {} ", escrow_reference),
         }
     }
 
-    fn check_nonce( &mut self, nonce : u64) {
-        let signer_account_id = env::signer_account_id().to_string();
-        
-        if self.nonce_register.contains_key(&signer_account_id) {
-            let nonce_history = self.nonce_register.get(&signer_account_id).unwrap();
-            
-            if nonce_history.contains(&nonce) {
-                panic!("REPEAT NONCE DETECTED. NONCE: {} ", nonce);
-            }
+    fn get_escrows_by_status(&self, status : String) -> Vec<ob_io::Escrow> {
+        let mut escrows : Vec<ob_io::Escrow> = self.escrows.values().filter(|es| es.status == status).cloned().collect();
+        escrows.sort_by_key(|es| (es.creation_date, es.reference));
+        escrows
+    }
+
+    fn get_supplier_contract(&self, supplier_contract_reference : u64) -> ob_io::SupplierContract {
+        match self.supplier_contracts.get(&supplier_contract_reference) {
+            Some(supplier_contract) => supplier_contract.clone(),
+            None => panic!("UNKNOWN SUPPLIER CONTRACT REFERENCE {} ", supplier_contract_reference),
         }
-        else {
-            let mut nonce_history = HashSet::<u64>::new();
-            nonce_history.insert(nonce);
-            self.nonce_register.insert(signer_account_id, nonce_history);
+    }
+
+    fn get_supplier_contracts_by_supplier(&self, supplier : String) -> Vec<ob_io::SupplierContract> {
+        let mut supplier_contracts : Vec<ob_io::SupplierContract> = self.supplier_contracts.values().filter(|sc| sc.supplier == supplier).cloned().collect();
+        supplier_contracts.sort_by_key(|sc| (sc.creation_date, sc.reference));
+        supplier_contracts
+    }
+
+    fn get_invoice(&self, invoice_reference : u64) -> ob_io::Invoice {
+        match self.invoices.get(&invoice_reference) {
+            Some(invoice) => invoice.clone(),
+            None => panic!("UNKNOWN INVOICE REFERENCE {} ", invoice_reference),
         }
     }
 
-    fn check_bank_balance(&mut self, amount_required : u128) {
-        
-        let pseudo_balance = self.bank_balance.clone(); 
-        if amount_required < pseudo_balance {
-            let answer = pseudo_balance - amount_required;
+    fn get_customer_credit(&self, account_id : &str) -> u128 {
+        *self.customer_credits.get(account_id).unwrap_or(&0)
+    }
 
-            if answer == 0 || answer > self.bank_balance {
-                panic!("INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", amount_required, self.bank_balance);
-            }
-            return;
+    fn get_customer_credits(&self) -> Vec<(String, U128)> {
+        let mut customer_credits : Vec<(String, U128)> = self.customer_credits.iter().map(|(account_id, credit)| (account_id.clone(), U128(*credit))).collect();
+        customer_credits.sort_by(|a, b| a.0.cmp(&b.0));
+        customer_credits
+    }
 
+    fn get_subscription(&self, subscription_reference : u64) -> ob_io::Subscription {
+        match self.subscriptions.get(&subscription_reference) {
+            Some(subscription) => subscription.clone(),
+            None => panic!("UNKNOWN SUBSCRIPTION REFERENCE {} ", subscription_reference),
         }
-        else {
-            panic!("INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: {} AVAILABLE AMOUNT: {}", amount_required, self.bank_balance);
+    }
+
+    fn get_subscriptions_by_subscriber(&self, subscriber : String) -> Vec<ob_io::Subscription> {
+        let mut subscriptions : Vec<ob_io::Subscription> = self.subscriptions.values().filter(|sub| sub.subscriber == subscriber).cloned().collect();
+        subscriptions.sort_by_key(|sub| (sub.creation_date, sub.reference));
+        subscriptions
+    }
+
+    fn get_split_rule(&self, split_rule_reference : u64) -> ob_io::SplitRule {
+        match self.split_rules.get(&split_rule_reference) {
+            Some(split_rule) => split_rule.clone(),
+            None => panic!("UNKNOWN SPLIT RULE REFERENCE {} ", split_rule_reference),
         }
+    }
 
+    fn get_active_split_rules(&self) -> Vec<ob_io::SplitRule> {
+        let mut split_rules : Vec<ob_io::SplitRule> = self.active_split_rules.values().map(|sr_reference| self.split_rules.get(sr_reference).unwrap().clone()).collect();
+        split_rules.sort_by_key(|sr| (sr.creation_date, sr.reference));
+        split_rules
     }
 
-    fn check_is_valid_request_debit_reference(&mut self, request_debit_ref: u64 ) {
-        if !self.request_debit_by_reference.contains_key(&request_debit_ref) {
-            panic!("REQUEST DEBIT REFERENCE NOT FOUND. REFERENCE PRESENTED: {}", request_debit_ref);    
+    fn get_split_accruals(&self) -> Vec<(String, U128)> {
+        let mut split_accruals : Vec<(String, U128)> = self.split_accruals.iter().map(|(payee, amount)| (payee.clone(), U128(*amount))).collect();
+        split_accruals.sort_by(|a, b| a.0.cmp(&b.0));
+        split_accruals
+    }
+
+    fn get_payments_by_external_reference(&self, external_reference : String) -> Vec<ob_io::Payment> {
+        if external_reference.is_empty() {
+            return Vec::<ob_io::Payment>::new();
         }
+        let mut payments : Vec<ob_io::Payment> = self.payments.iter().filter(|p| p.external_reference == external_reference).cloned().collect();
+        payments.sort_by_key(|p| p.sequence);
+        payments
     }
 
-    fn check_request_debit_status(&mut self, currenct_status : String, required_status : String){
-        if currenct_status.as_bytes() != required_status.as_bytes() { 
-            panic!("INVALID STATUS FOR ACTION. REQUIRED STATUS : {}, ACTUAL STATUS : {} ", required_status, currenct_status);
+    // totals the amounts of the categorised payments made from 'from_time' up to but not including 'to_time' that meet the given 'condition' 
+    fn total_categories_where<F : Fn(&ob_io::Payment) -> bool>(&self, from_time : i64, to_time : i64, condition : F) -> Vec<(String, U128)> {
+        let mut totals = HashMap::<String, u128>::new();
+        for payment in self.payments.iter().filter(|p| !p.category.is_empty() && p.payment_time >= from_time && p.payment_time < to_time && condition(p)) {
+            *totals.entry(payment.category.clone()).or_default() += payment.amount;
         }
+        let mut category_totals : Vec<(String, U128)> = totals.into_iter().map(|(category, total)| (category, U128(total))).collect();
+        category_totals.sort_by(|a, b| a.0.cmp(&b.0));
+        category_totals
     }
 
-    fn check_attachment_vs_stated_amount(&mut self, attached_amount : u128, stated_amount : u128){
-        if attached_amount != stated_amount{
-            panic!("DEPOSIT MIS-MATCH. STATED AMOUNT: {} ATTACHED AMOUNT: {}.", stated_amount, attached_amount);
+    // the private views show every record, only accounts allowed to 'view_payments' can use them 
+    fn check_view_payments(&mut self) {
+        let security_response = self.is_secure("view_payments".to_string(), "ALLOWED".to_string());
+        if !security_response {
+            panic!("PAYMENT VIEW NOT ALLOWED - IN SECURE ACCOUNT {} ", env::signer_account_id());
         }
     }
 
-    fn check_request_debit_interval(&mut self, request_debit : ob_io::RequestDebit){
-        let time_now = env::block_timestamp() as i64;
-        if request_debit.start_date > time_now {            
-            panic!("REQUEST DEBIT CLAIM PERIOD NOT STARTED. TIME NOW {}, CLAIM PERIOD START DATE {}.",time_now, request_debit.start_date);
+    // outside privacy mode anyone can view a record through the public views, in privacy mode only its parties can 
+    fn is_visible(&self, parties : &[&String]) -> bool {
+        !self.privacy_mode || OpenBank::is_party(parties)
+    }
+
+    fn check_visible(&self, parties : &[&String]) {
+        if !self.is_visible(parties) {
+            panic!("PAYMENT VIEW NOT ALLOWED IN PRIVACY MODE - ACCOUNT {} NOT A PARTY ", env::signer_account_id());
         }
+    }
 
-        if request_debit.end_date < time_now {
-            panic!("REQUEST DEBIT CLAIM PERIOD EXPIRED. TIME NOW {}, CLAIM PERIOD END DATE {}.",time_now, request_debit.end_date);
+    fn is_escrow_visible(&self, escrow : &ob_io::Escrow) -> bool {
+        let mut parties = vec![&escrow.payer, &escrow.payee, &escrow.creator];
+        parties.extend(escrow.approvers.iter());
+        self.is_visible(&parties)
+    }
+
+    fn is_split_rule_visible(&self, split_rule : &ob_io::SplitRule) -> bool {
+        let mut parties = vec![&split_rule.creator];
+        parties.extend(split_rule.legs.iter().map(|leg| &leg.payee));
+        self.is_visible(&parties)
+    }
+
+    fn is_invoice_visible(&self, invoice : &ob_io::Invoice) -> bool {
+        match &invoice.payer {
+            Some(payer) => self.is_visible(&[payer, &invoice.creator]),
+            None => self.is_visible(&[&invoice.creator]),
         }
+    }
 
-        let next_payment;
-        if request_debit.last_paid > 0 {
-            next_payment = request_debit.last_paid + request_debit.payout_interval;
+    // in privacy mode the creator and the approver of a PayrollRun see all of it and a payee only sees its own lines 
+    fn get_visible_payroll_run(&self, mut payroll_run : ob_io::PayrollRun) -> Option<ob_io::PayrollRun> {
+        if self.is_visible(&[&payroll_run.creator, &payroll_run.approved_by]) {
+            return Some(payroll_run);
         }
-        else{
-            next_payment = request_debit.start_date + request_debit.payout_interval;
+        payroll_run.lines.retain(|line| OpenBank::is_party(&[&line.payee]));
+        if payroll_run.lines.is_empty() {
+            return None;
         }
-    
-        if  next_payment > time_now {
-            panic!("PAY OUT INTERVAL NOT REACHED. TIME NOW {}, LAST PAID {}, INTERVAL REQUIRED {}.",time_now, request_debit.last_paid, request_debit.payout_interval);
+        Some(payroll_run)
+    }
+
+    // whether the signer is one of the given parties to a record
+    fn is_party(parties : &[&String]) -> bool {
+        let signer_account_id = env::signer_account_id();
+        parties.iter().any(|party| **party == signer_account_id)
+    }
+
+    // the net debit of the 'CASH' account over the given journal entries i.e. how much they moved the bank balance 
+    fn get_cash_movement(journal_entries : &[ob_io::JournalEntry]) -> i128 {
+        journal_entries.iter()
+            .map(|entry| if entry.debit_account == "CASH" { entry.amount as i128 } else if entry.credit_account == "CASH" { -(entry.amount as i128) } else { 0 })
+            .sum()
+    }
+
+    // the funds held apart from the bank balance that are owed or committed 
+    fn get_outstanding_obligations(&self) -> u128 {
+        self.escrow_balance + self.vesting_balance + self.stream_balance + self.credit_balance + self.split_balance + self.fee_balance
+    }
+
+    fn get_ledger_balance(&self, account : &str) -> i128 {
+        self.ledger_accounts.get(account).map(|ledger_account| ledger_account.get_balance()).unwrap_or(0)
+    }
+
+    fn get_pot_mut(&mut self, pot_name : &str) -> &mut ob_io::Pot {
+        match self.pots.get_mut(pot_name) {
+            Some(pot) => pot,
+            None => panic!("UNKNOWN POT {} ", pot_name),
         }
     }
 
-    fn decrement_bank_balance(&mut self, amount : u128) {
-        self.bank_balance -= amount;
+    fn get_pot(&self, pot_name : &str) -> ob_io::Pot {
+        match self.pots.get(pot_name) {
+            Some(pot) => pot.clone(),
+            None => panic!("UNKNOWN POT {} ", pot_name),
+        }
     }
 
-    fn increment_bank_balance(&mut self, amount : u128) {
-        self.bank_balance += amount;
+    // unnamed pots are the 'general' pot
+    fn get_pot_name(pot : Option<String>) -> String {
+        pot.unwrap_or_else(|| DEFAULT_POT.to_string())
     }
 
     fn get_total( mprs : HashSet<ob_io::MultiPaymentRequest>) -> u128 {
@@ -812,6 +4497,64 @@ impl OpenBank {
         }
     }
 
+    // moves each referenced RequestDebit to 'new_status', recording why any entry was skipped 
+    fn change_request_debit_statuses(&mut self, request_debit_refs : Vec<U64>, new_status : String, strict : bool, signer_account_id : String) -> Vec<ob_io::RequestDebitBatchResult> {
+        let mut results = Vec::<ob_io::RequestDebitBatchResult>::new();
+        for u_request_debit_ref in request_debit_refs {
+            let reference = u64::from(u_request_debit_ref);
+
+            let skip_reason = match self.request_debit_by_reference.get(&reference) {
+                None => Some("UNKNOWN REQUEST DEBIT REFERENCE".to_string()),
+                Some(rd) => OpenBank::get_request_debit_status_change_issue(&rd.status, &new_status),
+            };
+
+            match skip_reason {
+                Some(reason) => {
+                    if strict {
+                        panic!("BATCH STOPPED. REFERENCE {} {} ", reference, reason);
+                    }
+                    results.push(ob_io::RequestDebitBatchResult { reference, result : "SKIPPED".to_string(), reason });
+                },
+                None => {
+                    let request_debit = self.request_debit_by_reference.get(&reference).unwrap().clone();
+                    self.change_request_debit_status(request_debit, new_status.clone(), signer_account_id.clone());
+                    results.push(ob_io::RequestDebitBatchResult { reference, result : new_status.clone(), reason : "".to_string() });
+                },
+            }
+        }
+        results
+    }
+
+    // describes why a RequestDebit with 'current_status' cannot be moved to 'new_status', if it cannot
+    fn get_request_debit_status_change_issue(current_status : &str, new_status : &str) -> Option<String> {
+        if current_status == new_status {
+            return Some(format!("REQUEST DEBIT ALREADY {}", current_status));
+        }
+        if new_status == "APPROVED" && current_status != "PENDING" {
+            return Some(format!("INVALID STATUS FOR ACTION. REQUIRED STATUS : PENDING, ACTUAL STATUS : {}", current_status));
+        }
+        None
+    }
+
+    fn change_request_debit_status(&mut self, mut request_debit : ob_io::RequestDebit, new_status : String, signer_account_id : String) {
+        if new_status == "APPROVED" {
+            request_debit.approved_by = signer_account_id;
+        }
+        request_debit.status = new_status;
+        self.update_request_debit(request_debit);
+    }
+
+    // overwrites the stored RequestDebit with the same reference, keeping the status index in step 
+    fn update_request_debit(&mut self, request_debit : ob_io::RequestDebit) {
+        if let Some(previous) = self.request_debit_by_reference.get(&request_debit.reference) {
+            if let Some(debit_list) = self.request_debits_by_status.get_mut(&previous.status) {
+                debit_list.remove(previous);
+            }
+        }
+        self.request_debits_by_status.entry(request_debit.status.clone()).or_default().insert(request_debit.clone());
+        self.request_debit_by_reference.insert(request_debit.reference, request_debit);
+    }
+
     #[init]
     pub fn new( bank_name : String, 
                 bank_deployed_account_id : String, 
@@ -822,9 +4565,13 @@ impl OpenBank {
                 affirmative_code : i32, 
                 negative_code : i32, 
                 test_mode : bool ) -> Self {
-        Self {
+        let general_pot = ob_io::Pot::create_pot(DEFAULT_POT.to_string(), "".to_string(), owner.clone());
+        let mut pots = HashMap::<String, ob_io::Pot>::new();
+        pots.insert(general_pot.name.clone(), general_pot);
+
+        let mut open_bank = Self {
             bank_name                   ,
-            bank_balance                : env::account_balance(),
+            bank_balance                : 0,
             bank_deployed_account_id    , 
             denomination                , 
             owner                       ,
@@ -833,12 +4580,65 @@ impl OpenBank {
             request_debits_by_status    : HashMap::<String, HashSet<ob_io::RequestDebit>>::new(),
             payments                    : HashSet::<ob_io::Payment>::new(),
             payments_by_reference       : HashMap::<u64, ob_io::Payment>::new(),
+            payment_references          : Vec::<u64>::new(),
             access_security             : open_roles_account_id, 
             nonce_register              : HashMap::<String, HashSet<u64>>::new(),
             test_mode                   ,
             affirmative_code                 ,
             negative_code              ,
-        }
+            keeper_reward               : 0,
+            keeper_reward_budget        : 0,
+            keeper_reward_pot           : DEFAULT_POT.to_string(),
+            scheduled_payouts           : HashMap::<u64, ob_io::ScheduledPayout>::new(),
+            payroll_runs                : HashMap::<u64, ob_io::PayrollRun>::new(),
+            payment_sequence            : 0,
+            record_sequence             : 0,
+            vesting_grants              : HashMap::<u64, ob_io::VestingGrant>::new(),
+            vesting_balance             : 0,
+            payment_streams             : HashMap::<u64, ob_io::PaymentStream>::new(),
+            stream_balance              : 0,
+            escrows                     : HashMap::<u64, ob_io::Escrow>::new(),
+            escrow_balance              : 0,
+            supplier_contracts          : HashMap::<u64, ob_io::SupplierContract>::new(),
+            milestone_contracts         : HashMap::<u64, u64>::new(),
+            invoices                    : HashMap::<u64, ob_io::Invoice>::new(),
+            customer_credits            : HashMap::<String, u128>::new(),
+            credit_balance              : 0,
+            subscription_plans          : HashMap::<u64, ob_io::SubscriptionPlan>::new(),
+            subscriptions               : HashMap::<u64, ob_io::Subscription>::new(),
+            split_rules                 : HashMap::<u64, ob_io::SplitRule>::new(),
+            active_split_rules          : HashMap::<String, u64>::new(),
+            split_accruals              : HashMap::<String, u128>::new(),
+            split_balance               : 0,
+            fee_schedule                : ob_io::FeeSchedule::default(),
+            fee_schedule_changes        : Vec::<ob_io::FeeScheduleChange>::new(),
+            fee_balance                 : 0,
+            pots                        ,
+            treasury_policy             : ob_io::TreasuryPolicy::default(),
+            low_balance_events          : Vec::<ob_io::LowBalanceEvent>::new(),
+            journal                     : Vec::<ob_io::JournalEntry>::new(),
+            tied_journal_sequence       : 0,
+            ledger_accounts             : HashMap::<String, ob_io::LedgerAccount>::new(),
+            payment_categories          : HashMap::<String, ob_io::PaymentCategory>::new(),
+            lifetime_activity           : ob_io::ActivitySummary::default(),
+            daily_activity              : BTreeMap::<i64, ob_io::ActivitySummary>::new(),
+            last_activity_time          : 0,
+            statement_periods           : Vec::<ob_io::StatementPeriod>::new(),
+            open_statement_period       : ob_io::StatementPeriod::open_statement_period(1, 0, 1, 1),
+            statement_schedule          : ob_io::StatementSchedule { frequency : "NONE".to_string(), ..Default::default() },
+            audit_checkpoint            : ob_io::AuditCheckpoint::default(),
+            outflows_paused             : false,
+            privacy_mode                : false,
+            memo_public_key             : "".to_string(),
+        };
+
+        // the opening balance is the bank's capital, less what is locked for storage, and starts in the 'general' pot 
+        open_bank.increment_bank_balance(env::account_balance().saturating_sub(env::storage_usage() as u128 * env::storage_byte_cost()), "CAPITAL");
+        // the opening balance is not behind any Payment
+        open_bank.tied_journal_sequence = open_bank.journal.len() as u64;
+        // the audit rebuilds the balance from the opening balance 
+        open_bank.audit_checkpoint.rebuilt_balance = open_bank.bank_balance as i128;
+        open_bank
     }
 
     pub fn default() -> Self { 
@@ -11,13 +11,50 @@
 use near_sdk::{near_bindgen, env};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize };
 
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
+use chrono::{DateTime, Months, Utc};
+
+use crate::{DAY_MILLIS, DEFAULT_POT, WEEK_MILLIS};
+
+/// This function returns whether the given 'date' in milliseconds is after the epoch and can be represented as a calendar date
+pub fn is_valid_date(date : i64) -> bool {
+    date > 0 && DateTime::<Utc>::from_timestamp_millis(date).is_some()
+}
+
+/// This function returns 'amount' * 'numerator' / 'denominator' rounded down without overflowing where 'numerator' is no more than 'denominator' 
+/// The 'amount' is split by the 'denominator' first so that no intermediate product is wider than the 'denominator' squared
+pub fn mul_div(amount : u128, numerator : u128, denominator : u128) -> u128 {
+    let quotient = amount / denominator;
+    let remainder = amount % denominator;
+    quotient * numerator + remainder * numerator / denominator
+}
+
+/// This function returns the date the given number of 'months' after the given 'date', the last day of the month where that month is shorter
+fn add_months(date : i64, months : u64) -> i64 {
+    let start = match DateTime::<Utc>::from_timestamp_millis(date) {
+        Some(start) => start,
+        None => panic!("INVALID DATE {} ", date),
+    };
+    match u32::try_from(months).ok().and_then(|months| start.checked_add_months(Months::new(months))) {
+        Some(end) => end.timestamp_millis(),
+        None => panic!("INVALID DATE. {} MONTHS AFTER {} OUT OF RANGE ", months, date),
+    }
+}
+
 // #Payment 
 // The Payment struct represents the payments that are conducted by Open Bank. Transaction that has funds attached regardless of whether it is inbound or outbound from 
 // Open Bank is regarded as a payment. 
 // Payments are typically returned at the end of a transaction along with the necessary references. 
+// Payments made on behalf of another Open Bank record e.g. a 'ScheduledPayout' carry the reference of that record as the 'linked_reference', otherwise it is zero.
+// Every Payment registered by Open Bank is given the next 'sequence' number, starting from one.
+// A 'PAY_IN' that has been refunded in whole or in part carries the total refunded so far as the 'refunded_amount'. 
+// A 'PAY_IN' carries the 'pot' it was paid into so that refunds are drawn from the same pot. 
+// Where the bank has charged a fee the 'amount' is the 'gross_amount', the 'fee_amount' is kept by the bank and the 'net_amount' is what was credited or paid on. 
+// A Payment presented with an encrypted memo carries it as the 'encrypted_memo', only the bank can read it. 
 #[near_bindgen]
 #[derive(Default, Eq, PartialEq, Debug, Clone, PartialOrd, serde::Serialize,  BorshDeserialize, BorshSerialize, Hash)]
 pub struct Payment {
@@ -30,6 +67,18 @@ pub struct Payment {
                 pub status          : String, 
                 pub payment_time    : i64,
                 pub  reference       : u64,
+                pub linked_reference : u64,
+                pub sequence        : u64,
+                pub refunded_amount : u128,
+                pub gross_amount    : u128,
+                pub fee_amount      : u128,
+                pub net_amount      : u128,
+                pub category        : String,
+                pub tags            : Vec<String>,
+                pub external_reference : String,
+                pub metadata        : BTreeMap<String, String>,
+                pub encrypted_memo  : Option<EncryptedMemo>,
+                pub pot             : String,
 }
 
 impl Payment {
@@ -60,10 +109,42 @@ impl Payment {
                                     status, 
                                     payment_time,
                                     reference : 0,
+                                    linked_reference : 0,
+                                    sequence : 0,
+                                    refunded_amount : 0,
+                                    gross_amount : amount,
+                                    fee_amount : 0,
+                                    net_amount : amount,
+                                    category : "".to_string(),
+                                    tags : Vec::<String>::new(),
+                                    external_reference : "".to_string(),
+                                    metadata : BTreeMap::<String, String>::new(),
+                                    encrypted_memo : None,
+                                    pot : DEFAULT_POT.to_string(),
                                 };
                                 s.reference = Payment::calculate_hash(&s);
                                 s
     }
+
+    // This function is used to give the payment its position in the bank's payment history. 
+    // The reference is recalculated so that otherwise identical payments made in the same block are told apart 
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.sequence = sequence;
+        self.reference = 0;
+        self.reference = Payment::calculate_hash(self);
+    }
+
+    /// This function returns the amount this Payment moved into the bank balance, negative where it moved funds out. A pay in counts net of its fee, 
+    /// a refund counts against the balance in its own right and a Payment made from or into funds held apart from the balance does not move it 
+    pub fn get_balance_movement(&self) -> i128 {
+        match self.payment_type.as_str() {
+            "PAY_IN" => self.net_amount as i128,
+            "DEPOSIT" | "CREDIT_CHARGE" | "SUBSCRIPTION" | "SET_ASIDE_RETURN" => self.amount as i128,
+            "PAY_OUT" | "PAY_OUT_MULTI" | "REQUEST_DEBIT" | "PAYROLL" | "STANDING_ORDER" | "MILESTONE" | "REFUND" | "KEEPER_REWARD" 
+                | "SWEEP" | "WITHDRAWAL" | "REVENUE_SPLIT" | "SET_ASIDE" => -(self.amount as i128),
+            _ => 0,
+        }
+    }
     
     // This is an internal method to determine a hash to identify this 'Payment'
     fn calculate_hash<T: Hash>(t: &T) -> u64 {
@@ -79,6 +160,7 @@ impl Payment {
 // The bank will enable the client to request a payment on presentation of a 'RequestDebitReference'. The payout will be made to the client listed on the reference and 'not' to the 
 // caller. 
 // All request debits are created with 'PENDING' status and must be approved before they can be collected. 
+// Each debit is drawn from the bank's 'pot' named on the request debit.
 pub struct RequestDebit {
     pub payee           : String,
     pub amount          : u128,
@@ -92,6 +174,8 @@ pub struct RequestDebit {
     pub status          : String, 
     pub approved_by     : String,
     pub reference       : u64,
+    pub annotation      : PaymentAnnotation,
+    pub pot             : String,
 }
 
 impl RequestDebit {
@@ -124,7 +208,9 @@ impl RequestDebit {
                                         creator,
                                         status          : "PENDING".to_string(), 
                                         approved_by     : "".to_string(),
-                                        reference       : 0
+                                        reference       : 0,
+                                        annotation      : PaymentAnnotation::default(),
+                                        pot             : DEFAULT_POT.to_string(),
                                 };
                                 rd.reference = RequestDebit::calculate_hash(&rd);
                                 rd
@@ -145,4 +231,1279 @@ pub struct MultiPaymentRequest {
     pub payee_account_id : String, 
     pub payout_amount : u128,
     pub description : String,
+    pub annotation : Option<PaymentAnnotation>,
+}
+
+/// # LiquidityForecast
+/// The LiquidityForecast represents the projected outflows of Open Bank over a given horizon, measured against the current bank balance. 
+/// The forecast is split into equal periods, each of which carries the obligations falling due in that period and the balance projected at its end.
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct LiquidityForecast {
+    pub opening_balance     : u128,
+    pub forecast_start      : i64,
+    pub forecast_end        : i64,
+    pub granularity         : String,
+    pub total_obligations   : u128,
+    pub periods             : Vec<ForecastPeriod>,
+    pub shortfall_date      : Option<i64>,
+}
+
+/// # ForecastPeriod
+/// The ForecastPeriod represents a single day or week within a 'LiquidityForecast'. 
+/// 'projected_balance' will not fall below zero, any obligations that cannot be met are carried in 'shortfall'
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct ForecastPeriod {
+    pub period_start        : i64,
+    pub period_end          : i64,
+    pub obligations         : u128,
+    pub projected_balance   : u128,
+    pub shortfall           : u128,
+}
+
+/// # RequestDebitBatchResult
+/// The RequestDebitBatchResult represents the outcome of a batch request debit operation for a single 'RequestDebit' reference. 
+/// 'result' carries the new status of the RequestDebit or 'SKIPPED', in which case 'reason' explains why the entry was not actioned 
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct RequestDebitBatchResult {
+    pub reference   : u64, 
+    pub result      : String,
+    pub reason      : String,
+}
+
+/// # ScheduledPayout
+/// The ScheduledPayout represents a standing order set up by the bank to push a fixed amount to a 'payee' once or at a regular frequency. 
+/// Recurring payouts run from the 'first_payment_date' until the 'end_date' where an 'end_date' of zero means the payout runs until it is cancelled. 
+/// All scheduled payouts are created with 'ACTIVE' status and move to 'COMPLETED' once the final payment has been made. Each payout is drawn from the named 'pot'.
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct ScheduledPayout {
+    pub payee               : String,
+    pub amount              : u128,
+    pub description         : String,
+    pub frequency           : String,
+    pub first_payment_date  : i64,
+    pub next_payment_date   : i64,
+    pub end_date            : i64,
+    pub last_paid           : i64,
+    pub payments_made       : u64,
+    pub creation_date       : i64,
+    pub creator             : String,
+    pub status              : String,
+    pub reference           : u64,
+    pub pot                 : String,
+}
+
+impl ScheduledPayout {
+    /// This function is used to internally create a representation of the ScheduledPayout 
+    /// 'payee' - entity to which the payouts will be directed 
+    /// 'amount' - amount of each payout 
+    /// 'description' - description of the payout 
+    /// 'frequency' - one of 'ONCE', 'DAILY', 'WEEKLY' or 'MONTHLY'
+    /// 'first_payment_date' - date of the first payout, monthly payouts fall on the same day of each month or the last day of shorter months 
+    /// 'end_date' - date after which no further payouts are made, zero for no end date 
+    /// 'creator' - entity that created the ScheduledPayout
+    pub fn create_scheduled_payout( payee               : String,
+                                    amount              : u128,
+                                    description         : String, 
+                                    frequency           : String,
+                                    first_payment_date  : i64,
+                                    end_date            : i64,
+                                    creator             : String) -> Self {
+
+                                let mut sp = Self {
+                                        payee,
+                                        amount,
+                                        description, 
+                                        frequency,
+                                        first_payment_date,
+                                        next_payment_date   : first_payment_date,
+                                        end_date,
+                                        last_paid           : 0,
+                                        payments_made       : 0,
+                                        creation_date       : env::block_timestamp() as i64,
+                                        creator,
+                                        status              : "ACTIVE".to_string(),
+                                        reference           : 0,
+                                        pot                 : DEFAULT_POT.to_string(),
+                                };
+                                sp.reference = ScheduledPayout::calculate_hash(&sp);
+                                sp
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = ScheduledPayout::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns the date of the payout with the given (zero based) 'index'
+    pub fn get_payment_date(&self, index : u64) -> i64 {
+        match self.frequency.as_str() {
+            "DAILY" => self.first_payment_date + DAY_MILLIS * index as i64,
+            "WEEKLY" => self.first_payment_date + WEEK_MILLIS * index as i64,
+            "MONTHLY" => {
+                // months are always counted from the first payment so that a short month does not move later payouts
+                add_months(self.first_payment_date, index)
+            },
+            _ => self.first_payment_date,
+        }
+    }
+
+    /// This function returns whether a payout would be due at the given date
+    pub fn is_payment_date_in_schedule(&self, payment_date : i64, index : u64) -> bool {
+        if self.frequency == "ONCE" && index > 0 {
+            return false;
+        }
+        self.end_date == 0 || payment_date <= self.end_date
+    }
+
+    // This is an internal method to determine a hash to identify this 'ScheduledPayout'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # PayrollLine
+/// The PayrollLine represents a single payee within a 'PayrollRun'. Lines start as 'PENDING' and become 'PAID' once the payment has been made. 
+/// Lines that could not be paid are marked 'FAILED' with the 'failure_reason' and are retried the next time the run is executed.
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct PayrollLine {
+    pub line_number         : u64,
+    pub payee               : String,
+    pub amount              : u128,
+    pub description         : String,
+    pub status              : String,
+    pub attempts            : u64,
+    pub failure_reason      : String,
+    pub payment_reference   : u64,
+}
+
+/// # PayrollRun
+/// The PayrollRun represents an ordered list of payments that are approved and executed together. 
+/// All payroll runs are created with 'DRAFT' status, must be 'APPROVED' before they can be executed and become 'EXECUTED' once every line has been paid. 
+/// Each Payment made by the run carries the run reference as its 'linked_reference' and is drawn from the named 'pot'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct PayrollRun {
+    pub description         : String,
+    pub lines               : Vec<PayrollLine>,
+    pub total               : u128,
+    pub paid_total          : u128,
+    pub creation_date       : i64,
+    pub creator             : String,
+    pub approved_by         : String,
+    pub status              : String,
+    pub reference           : u64,
+    pub pot                 : String,
+}
+
+impl PayrollRun {
+    /// This function is used to internally create a representation of the PayrollRun 
+    /// 'description' - description of the run 
+    /// 'requests' - payees, amounts and descriptions of the lines in the order they will be paid 
+    /// 'creator' - entity that created the PayrollRun 
+    pub fn create_payroll_run(description : String, requests : Vec<MultiPaymentRequest>, creator : String) -> Self {
+        let mut lines = Vec::<PayrollLine>::new();
+        let mut total : u128 = 0;
+        for request in requests {
+            total += request.payout_amount;
+            lines.push(PayrollLine {
+                line_number         : lines.len() as u64 + 1,
+                payee               : request.payee_account_id,
+                amount              : request.payout_amount,
+                description         : request.description,
+                status              : "PENDING".to_string(),
+                attempts            : 0,
+                failure_reason      : "".to_string(),
+                payment_reference   : 0,
+            });
+        }
+
+        let mut pr = Self {
+            description,
+            lines,
+            total,
+            paid_total          : 0,
+            creation_date       : env::block_timestamp() as i64,
+            creator,
+            approved_by         : "".to_string(),
+            status              : "DRAFT".to_string(),
+            reference           : 0,
+            pot                 : DEFAULT_POT.to_string(),
+        };
+        pr.reference = PayrollRun::calculate_hash(&pr);
+        pr
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = PayrollRun::calculate_hash(&(&*self, sequence));
+    }
+
+    // This is an internal method to determine a hash to identify this 'PayrollRun'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # VestingGrantRequest
+/// The VestingGrantRequest represents the terms of a new vesting grant as presented to Open Bank
+/// 'release_type' is either 'LINEAR', vesting continuously between the 'start_date' and 'end_date', or 'STEPPED', vesting in equal parts every 'step_interval' millis from the 'start_date'
+/// Nothing vests before the 'cliff_date' 
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct VestingGrantRequest {
+    pub beneficiary     : String,
+    pub total           : u128,
+    pub start_date      : i64,
+    pub cliff_date      : i64,
+    pub end_date        : i64,
+    pub release_type    : String,
+    pub step_interval   : i64,
+    pub revocable       : bool,
+}
+
+/// # VestingGrant
+/// The VestingGrant represents funds set aside by the bank for a 'beneficiary' that become claimable over time. 
+/// The 'total' is taken out of the bank balance when the grant is created. On revocation the unvested part is returned to the bank balance and 'total' is reduced to what had vested
+/// All vesting grants are created with 'ACTIVE' status and move to 'COMPLETED' once fully claimed or 'REVOKED' if revoked. The grant is set aside from, and returned to, the named 'pot'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct VestingGrant {
+    pub beneficiary     : String,
+    pub total           : u128,
+    pub claimed         : u128,
+    pub start_date      : i64,
+    pub cliff_date      : i64,
+    pub end_date        : i64,
+    pub release_type    : String,
+    pub step_interval   : i64,
+    pub revocable       : bool,
+    pub revoked_date    : i64,
+    pub creation_date   : i64,
+    pub creator         : String,
+    pub status          : String,
+    pub reference       : u64,
+    pub pot             : String,
+}
+
+impl VestingGrant {
+    /// This function is used to internally create a representation of the VestingGrant from the given 'request'
+    /// 'creator' - entity that created the VestingGrant
+    pub fn create_vesting_grant(request : VestingGrantRequest, creator : String) -> Self {
+        let mut vg = Self {
+            beneficiary     : request.beneficiary,
+            total           : request.total,
+            claimed         : 0,
+            start_date      : request.start_date,
+            cliff_date      : request.cliff_date,
+            end_date        : request.end_date,
+            release_type    : request.release_type,
+            step_interval   : request.step_interval,
+            revocable       : request.revocable,
+            revoked_date    : 0,
+            creation_date   : env::block_timestamp() as i64,
+            creator,
+            status          : "ACTIVE".to_string(),
+            reference       : 0,
+            pot             : DEFAULT_POT.to_string(),
+        };
+        vg.reference = VestingGrant::calculate_hash(&vg);
+        vg
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = VestingGrant::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns the amount of this grant that has vested by the given 'time' whether claimed or not
+    pub fn get_vested_amount(&self, time : i64) -> u128 {
+        if self.status == "REVOKED" || time >= self.end_date {
+            return self.total;
+        }
+        if time < self.cliff_date || time <= self.start_date {
+            return 0;
+        }
+        let duration = (self.end_date - self.start_date) as u128;
+        let elapsed = (time - self.start_date) as u128;
+        if self.release_type == "STEPPED" {
+            let step_interval = self.step_interval as u128;
+            let total_steps = duration.div_ceil(step_interval);
+            return mul_div(self.total, elapsed / step_interval, total_steps);
+        }
+        mul_div(self.total, elapsed, duration)
+    }
+
+    /// This function returns the amount of this grant that the beneficiary can claim at the given 'time'
+    pub fn get_claimable_amount(&self, time : i64) -> u128 {
+        self.get_vested_amount(time) - self.claimed
+    }
+
+    // This is an internal method to determine a hash to identify this 'VestingGrant'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # PaymentStream
+/// The PaymentStream represents funds that accrue to a 'recipient' at 'rate_per_second' between the 'start_time' and the 'stop_time'. 
+/// The 'deposit' needed to fund the whole stream is taken out of the bank balance when the stream is created. Time spent 'PAUSED' does not accrue, instead the 
+/// 'stop_time' is put back by the time paused when the stream is resumed so the whole deposit still accrues. On cancellation the part of the deposit that has not accrued is returned to the bank balance. 
+/// 'accrued_at_checkpoint' holds what had accrued by the 'checkpoint_time', the last time the stream was paused or resumed. The deposit is set aside from, and returned to, the named 'pot' 
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct PaymentStream {
+    pub recipient               : String,
+    pub rate_per_second         : u128,
+    pub start_time              : i64,
+    pub stop_time               : i64,
+    pub deposit                 : u128,
+    pub withdrawn               : u128,
+    pub accrued_at_checkpoint   : u128,
+    pub checkpoint_time         : i64,
+    pub creation_date           : i64,
+    pub creator                 : String,
+    pub status                  : String,
+    pub reference               : u64,
+    pub pot                     : String,
+}
+
+impl PaymentStream {
+    /// This function is used to internally create a representation of the PaymentStream 
+    /// 'recipient' - entity to which the stream accrues 
+    /// 'rate_per_second' - amount accrued for each second the stream is active 
+    /// 'start_time' - time from which the stream accrues
+    /// 'stop_time' - time after which the stream no longer accrues 
+    /// 'creator' - entity that created the PaymentStream
+    pub fn create_payment_stream(recipient : String, rate_per_second : u128, start_time : i64, stop_time : i64, creator : String) -> Self {
+        let mut ps = Self {
+            recipient,
+            rate_per_second,
+            start_time,
+            stop_time,
+            deposit                 : match PaymentStream::get_deposit(rate_per_second, start_time, stop_time) {
+                                        Some(deposit) => deposit,
+                                        None => panic!("INVALID STREAM TERMS. DEPOSIT FOR RATE PER SECOND {} BETWEEN {} AND {} OUT OF RANGE ", rate_per_second, start_time, stop_time),
+                                    },
+            withdrawn               : 0,
+            accrued_at_checkpoint   : 0,
+            checkpoint_time         : start_time,
+            creation_date           : env::block_timestamp() as i64,
+            creator,
+            status                  : "ACTIVE".to_string(),
+            reference               : 0,
+            pot                     : DEFAULT_POT.to_string(),
+        };
+        ps.reference = PaymentStream::calculate_hash(&ps);
+        ps
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = PaymentStream::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns the deposit needed to fund a stream at 'rate_per_second' between the 'start_time' and the 'stop_time', None where it is too large to hold
+    pub fn get_deposit(rate_per_second : u128, start_time : i64, stop_time : i64) -> Option<u128> {
+        if stop_time <= start_time {
+            return Some(0);
+        }
+        rate_per_second.checked_mul((stop_time - start_time) as u128).map(|amount| amount / 1000)
+    }
+
+    /// This function returns the amount that has accrued to the recipient by the given 'time' whether withdrawn or not
+    pub fn get_accrued_amount(&self, time : i64) -> u128 {
+        if self.status != "ACTIVE" {
+            return self.accrued_at_checkpoint;
+        }
+        if time >= self.stop_time {
+            return self.deposit;
+        }
+        let from = self.checkpoint_time.max(self.start_time);
+        let to = time.min(self.stop_time);
+        if to <= from {
+            return self.accrued_at_checkpoint;
+        }
+        let accrued = self.accrued_at_checkpoint + self.rate_per_second * (to - from) as u128 / 1000;
+        accrued.min(self.deposit)
+    }
+
+    /// This function resumes this paused stream at the given 'time', putting the 'stop_time' back by the time the stream spent paused before it was due to stop
+    pub fn resume(&mut self, time : i64) {
+        let paused_from = self.checkpoint_time.max(self.start_time);
+        if paused_from < self.stop_time {
+            self.stop_time += time.max(self.start_time) - paused_from;
+        }
+        self.checkpoint_time = time;
+        self.status = "ACTIVE".to_string();
+    }
+
+    /// This function returns the amount the recipient can withdraw at the given 'time'
+    pub fn get_withdrawable_amount(&self, time : i64) -> u128 {
+        self.get_accrued_amount(time) - self.withdrawn
+    }
+
+    // This is an internal method to determine a hash to identify this 'PaymentStream'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # EscrowRequest
+/// The EscrowRequest represents the terms of a new escrow as presented to Open Bank
+/// The escrow is released to the 'payee' once 'approvals_required' of the named 'approvers' have approved, by an account allowed the Open Roles 'release_operation' 
+/// or, if the 'timeout_action' is 'RELEASE', by anyone once the 'expiry' has passed. If the 'timeout_action' is 'REFUND' the funds are returned once the 'expiry' has passed
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct EscrowRequest {
+    pub payee               : String,
+    pub amount              : u128,
+    pub description         : String,
+    pub expiry              : i64,
+    pub approvers           : Vec<String>,
+    pub approvals_required  : u64,
+    pub release_operation   : String,
+    pub timeout_action      : String,
+}
+
+/// # Escrow
+/// The Escrow represents funds locked for a 'payee' pending delivery. 
+/// The 'funding' is either 'BANK', where the 'amount' is taken out of the bank balance, or 'PAY_IN', where the 'amount' is attached by the 'payer' 
+/// and the 'funding_payment_reference' refers to the pay in. On refund the funds go back to where they came from 
+/// All escrows are created with 'HELD' status and move to 'RELEASED' or 'REFUNDED'. A 'BANK' funded escrow is held from, and refunded to, the named 'pot'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct Escrow {
+    pub payee                       : String,
+    pub payer                       : String,
+    pub amount                      : u128,
+    pub description                 : String,
+    pub expiry                      : i64,
+    pub approvers                   : Vec<String>,
+    pub approvals_required          : u64,
+    pub approvals                   : Vec<String>,
+    pub release_operation           : String,
+    pub timeout_action              : String,
+    pub funding                     : String,
+    pub funding_payment_reference   : u64,
+    pub settlement_payment_reference: u64,
+    pub settlement_date             : i64,
+    pub creation_date               : i64,
+    pub creator                     : String,
+    pub status                      : String,
+    pub reference                   : u64,
+    pub pot                         : String,
+}
+
+impl Escrow {
+    /// This function is used to internally create a representation of the Escrow from the given 'request'
+    /// 'payer' - entity the funds are returned to on refund 
+    /// 'funding' - 'BANK' or 'PAY_IN'
+    /// 'creator' - entity that created the Escrow
+    pub fn create_escrow(request : EscrowRequest, payer : String, funding : String, creator : String) -> Self {
+        let mut es = Self {
+            payee                       : request.payee,
+            payer,
+            amount                      : request.amount,
+            description                 : request.description,
+            expiry                      : request.expiry,
+            approvers                   : request.approvers,
+            approvals_required          : request.approvals_required,
+            approvals                   : Vec::new(),
+            release_operation           : request.release_operation,
+            timeout_action              : request.timeout_action,
+            funding,
+            funding_payment_reference   : 0,
+            settlement_payment_reference: 0,
+            settlement_date             : 0,
+            creation_date               : env::block_timestamp() as i64,
+            creator,
+            status                      : "HELD".to_string(),
+            reference                   : 0,
+            pot                         : DEFAULT_POT.to_string(),
+        };
+        es.reference = Escrow::calculate_hash(&es);
+        es
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = Escrow::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns whether the escrow has passed its 'expiry' at the given 'time'
+    pub fn is_expired(&self, time : i64) -> bool {
+        time >= self.expiry
+    }
+
+    /// This function returns whether enough of the named approvers have approved the escrow for release
+    pub fn is_approved(&self) -> bool {
+        !self.approvers.is_empty() && self.approvals.len() as u64 >= self.approvals_required
+    }
+
+    // This is an internal method to determine a hash to identify this 'Escrow'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # MilestoneRequest
+/// The MilestoneRequest represents a single milestone of a new supplier contract as presented to Open Bank
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct MilestoneRequest {
+    pub amount          : u128,
+    pub description     : String,
+    pub due_date        : i64,
+}
+
+/// # Milestone
+/// The Milestone represents a single deliverable within a 'SupplierContract'. Milestones start as 'PENDING', become 'DELIVERED' when the supplier marks them as delivered 
+/// and 'PAID' once accepted, at which point the payment is made. A delivered milestone that is rejected goes back to 'PENDING'. 
+/// Unpaid milestones become 'CANCELLED' when the contract is cancelled. Each Payment carries the milestone 'id' as its 'linked_reference'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct Milestone {
+    pub id                  : u64,
+    pub milestone_number    : u64,
+    pub amount              : u128,
+    pub description         : String,
+    pub due_date            : i64,
+    pub delivered_date      : i64,
+    pub accepted_by         : String,
+    pub status              : String,
+    pub payment_reference   : u64,
+}
+
+/// # SupplierContract
+/// The SupplierContract represents work by a 'supplier' that is paid per milestone. 
+/// All supplier contracts are created with 'ACTIVE' status and become 'COMPLETED' once every milestone has been paid or 'CANCELLED' if cancelled. Each milestone is paid from the named 'pot'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct SupplierContract {
+    pub supplier            : String,
+    pub description         : String,
+    pub milestones          : Vec<Milestone>,
+    pub total               : u128,
+    pub paid_total          : u128,
+    pub creation_date       : i64,
+    pub creator             : String,
+    pub status              : String,
+    pub reference           : u64,
+    pub pot                 : String,
+}
+
+impl SupplierContract {
+    /// This function is used to internally create a representation of the SupplierContract 
+    /// 'supplier' - entity that delivers the milestones and is paid for them 
+    /// 'description' - description of the contract 
+    /// 'requests' - amounts, descriptions and due dates of the milestones in order 
+    /// 'creator' - entity that created the SupplierContract 
+    pub fn create_supplier_contract(supplier : String, description : String, requests : Vec<MilestoneRequest>, creator : String) -> Self {
+        let mut milestones = Vec::<Milestone>::new();
+        let mut total : u128 = 0;
+        for request in requests {
+            total += request.amount;
+            milestones.push(Milestone {
+                id                  : 0,
+                milestone_number    : milestones.len() as u64 + 1,
+                amount              : request.amount,
+                description         : request.description,
+                due_date            : request.due_date,
+                delivered_date      : 0,
+                accepted_by         : "".to_string(),
+                status              : "PENDING".to_string(),
+                payment_reference   : 0,
+            });
+        }
+
+        let mut sc = Self {
+            supplier,
+            description,
+            milestones,
+            total,
+            paid_total          : 0,
+            creation_date       : env::block_timestamp() as i64,
+            creator,
+            status              : "ACTIVE".to_string(),
+            reference           : 0,
+            pot                 : DEFAULT_POT.to_string(),
+        };
+        sc.reference = SupplierContract::calculate_hash(&sc);
+        // milestone ids are unique across contracts
+        for milestone in sc.milestones.iter_mut() {
+            milestone.id = SupplierContract::calculate_hash(&(sc.reference, milestone.milestone_number));
+        }
+        sc
+    }
+
+    /// This function returns the position of the milestone with the given 'milestone_id' within this contract 
+    pub fn get_milestone_index(&self, milestone_id : u64) -> Option<usize> {
+        self.milestones.iter().position(|m| m.id == milestone_id)
+    }
+
+    // This is an internal method to determine a hash to identify this 'SupplierContract'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # InvoiceRequest
+/// The InvoiceRequest represents the terms of a new invoice as presented to Open Bank
+/// If a 'payer' is given only that account can pay the invoice, otherwise any account can pay it 
+/// 'overpayment_action' is either 'REFUND', returning any amount over what is owed to the payer, or 'CREDIT', keeping it as credit for the payer
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct InvoiceRequest {
+    pub payer               : Option<String>,
+    pub amount              : u128,
+    pub due_date            : i64,
+    pub external_reference  : String,
+    pub description         : String,
+    pub overpayment_action  : String,
+}
+
+/// # Invoice
+/// The Invoice represents an amount owed to the bank that is settled with 'pay invoice'. 
+/// All invoices are created with 'OPEN' status, become 'PARTIALLY_PAID' once part of the 'amount' has been paid and 'PAID' once it has been paid in full. 
+/// Each pay in against the invoice carries the invoice reference as its 'linked_reference' and is listed in 'payment_references'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct Invoice {
+    pub payer               : Option<String>,
+    pub amount              : u128,
+    pub paid_amount         : u128,
+    pub due_date            : i64,
+    pub external_reference  : String,
+    pub description         : String,
+    pub overpayment_action  : String,
+    pub payment_references  : Vec<u64>,
+    pub paid_date           : i64,
+    pub creation_date       : i64,
+    pub creator             : String,
+    pub status              : String,
+    pub reference           : u64,
+}
+
+impl Invoice {
+    /// This function is used to internally create a representation of the Invoice from the given 'request'
+    /// 'creator' - entity that created the Invoice
+    pub fn create_invoice(request : InvoiceRequest, creator : String) -> Self {
+        let mut inv = Self {
+            payer               : request.payer,
+            amount              : request.amount,
+            paid_amount         : 0,
+            due_date            : request.due_date,
+            external_reference  : request.external_reference,
+            description         : request.description,
+            overpayment_action  : request.overpayment_action,
+            payment_references  : Vec::new(),
+            paid_date           : 0,
+            creation_date       : env::block_timestamp() as i64,
+            creator,
+            status              : "OPEN".to_string(),
+            reference           : 0,
+        };
+        inv.reference = Invoice::calculate_hash(&inv);
+        inv
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = Invoice::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns what is still owed on the invoice
+    pub fn get_outstanding_amount(&self) -> u128 {
+        self.amount - self.paid_amount
+    }
+
+    /// This function returns whether the invoice is still owed, in whole or in part 
+    pub fn is_open(&self) -> bool {
+        self.status == "OPEN" || self.status == "PARTIALLY_PAID"
+    }
+
+    /// This function returns whether the invoice is still owed after its 'due_date' at the given 'time'
+    pub fn is_overdue(&self, time : i64) -> bool {
+        self.is_open() && time > self.due_date
+    }
+
+    // This is an internal method to determine a hash to identify this 'Invoice'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # SubscriptionPlan
+/// The SubscriptionPlan represents a service that customers subscribe to from their credit. Each 'period' millis the 'price' is charged against the credit of the subscriber 
+/// and a subscription that can not be renewed remains usable for the 'grace_period' millis before it lapses 
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct SubscriptionPlan {
+    pub name            : String,
+    pub price           : u128,
+    pub period          : i64,
+    pub grace_period    : i64,
+    pub creation_date   : i64,
+    pub creator         : String,
+    pub reference       : u64,
+}
+
+impl SubscriptionPlan {
+    /// This function is used to internally create a representation of the SubscriptionPlan 
+    /// 'name' - name of the plan 
+    /// 'price' - amount charged for each period 
+    /// 'period' - length of each paid period in millis 
+    /// 'grace_period' - time in millis a subscription remains usable after it could not be renewed 
+    /// 'creator' - entity that created the SubscriptionPlan 
+    pub fn create_subscription_plan(name : String, price : u128, period : i64, grace_period : i64, creator : String) -> Self {
+        let mut sp = Self {
+            name,
+            price,
+            period,
+            grace_period,
+            creation_date   : env::block_timestamp() as i64,
+            creator,
+            reference       : 0,
+        };
+        sp.reference = SubscriptionPlan::calculate_hash(&sp);
+        sp
+    }
+
+    // This is an internal method to determine a hash to identify this 'SubscriptionPlan'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # Subscription
+/// The Subscription represents a 'subscriber' paying for a 'SubscriptionPlan' from their credit. The subscription has been paid for until 'paid_until'. 
+/// All subscriptions are created with 'ACTIVE' status and become 'LAPSED' when they could not be renewed by the end of the grace period or 'CANCELLED' if the subscriber cancels. 
+/// Each renewal is a Payment of type 'SUBSCRIPTION' carrying the subscription reference as its 'linked_reference'
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct Subscription {
+    pub subscriber          : String,
+    pub plan_reference      : u64,
+    pub price               : u128,
+    pub period              : i64,
+    pub grace_period        : i64,
+    pub paid_until          : i64,
+    pub periods_paid        : u64,
+    pub creation_date       : i64,
+    pub status              : String,
+    pub reference           : u64,
+}
+
+impl Subscription {
+    /// This function is used to internally create a representation of the Subscription to the given 'plan' 
+    /// 'subscriber' - entity that subscribes and whose credit is charged 
+    pub fn create_subscription(plan : &SubscriptionPlan, subscriber : String) -> Self {
+        let time_now = env::block_timestamp() as i64;
+        let mut sub = Self {
+            subscriber,
+            plan_reference      : plan.reference,
+            price               : plan.price,
+            period              : plan.period,
+            grace_period        : plan.grace_period,
+            paid_until          : time_now,
+            periods_paid        : 0,
+            creation_date       : time_now,
+            status              : "ACTIVE".to_string(),
+            reference           : 0,
+        };
+        sub.reference = Subscription::calculate_hash(&sub);
+        sub
+    }
+
+    // This function is used to mix the bank's record 'sequence' into the reference so that otherwise identical records created in the same block are told apart
+    pub fn assign_sequence(&mut self, sequence : u64) {
+        self.reference = 0;
+        self.reference = Subscription::calculate_hash(&(&*self, sequence));
+    }
+
+    /// This function returns whether the subscriber can use the plan at the given 'time'. Cancelled subscriptions can be used until the end of the paid period 
+    pub fn is_usable(&self, time : i64) -> bool {
+        match self.status.as_str() {
+            "ACTIVE" => time < self.paid_until + self.grace_period,
+            "CANCELLED" => time < self.paid_until,
+            _ => false,
+        }
+    }
+
+    // This is an internal method to determine a hash to identify this 'Subscription'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # SplitLeg
+/// The SplitLeg represents the share of a split pay in that goes to a 'payee', in basis points i.e. 100 is 1% 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct SplitLeg {
+    pub payee           : String,
+    pub basis_points    : u64,
+}
+
+/// # SplitRuleRequest
+/// The SplitRuleRequest represents a new revenue split rule as presented to Open Bank
+/// 'match_type' is either 'TAG', matching pay ins with the 'pay_in_tag' given as the 'match_value', or 'DAPP', matching pay ins made through the calling dApp given as the 'match_value' 
+/// 'distribution' is either 'IMMEDIATE', paying each leg as the pay in arrives, or 'ACCRUED', holding each leg for later batch distribution
+#[derive(Default, Eq, PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct SplitRuleRequest {
+    pub match_type      : String,
+    pub match_value     : String,
+    pub legs            : Vec<SplitLeg>,
+    pub distribution    : String,
+}
+
+/// # SplitRule
+/// The SplitRule represents how matching pay ins are split between the bank and the payees of the 'legs'. Whatever is not allocated to a leg is kept by the bank. 
+/// All split rules are created with 'ACTIVE' status. There is one 'ACTIVE' rule per match, a rule becomes 'REPLACED' when a new rule is set for the same match or 'REMOVED' when removed
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct SplitRule {
+    pub match_type      : String,
+    pub match_value     : String,
+    pub legs            : Vec<SplitLeg>,
+    pub distribution    : String,
+    pub creation_date   : i64,
+    pub creator         : String,
+    pub status          : String,
+    pub reference       : u64,
+}
+
+impl SplitRule {
+    /// This function is used to internally create a representation of the SplitRule from the given 'request'
+    /// 'creator' - entity that created the SplitRule
+    pub fn create_split_rule(request : SplitRuleRequest, creator : String) -> Self {
+        let mut sr = Self {
+            match_type      : request.match_type,
+            match_value     : request.match_value,
+            legs            : request.legs,
+            distribution    : request.distribution,
+            creation_date   : env::block_timestamp() as i64,
+            creator,
+            status          : "ACTIVE".to_string(),
+            reference       : 0,
+        };
+        sr.reference = SplitRule::calculate_hash(&sr);
+        sr
+    }
+
+    /// This function returns the amount of the given pay in 'amount' that goes to each leg, in leg order 
+    pub fn get_leg_amounts(&self, amount : u128) -> Vec<u128> {
+        self.legs.iter().map(|leg| amount * leg.basis_points as u128 / 10_000).collect()
+    }
+
+    // This is an internal method to determine a hash to identify this 'SplitRule'
+    fn calculate_hash<T: Hash>(t: &T) -> u64 {
+        let mut s = DefaultHasher::new();
+        t.hash(&mut s);
+        s.finish()
+    }
+}
+
+/// # FeeRate
+/// The FeeRate represents the fee charged on a single kind of payment, a percentage of the payment in basis points i.e. 100 is 1% plus a 'flat' amount. 
+/// The fee never exceeds the payment itself
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct FeeRate {
+    pub basis_points    : u64,
+    pub flat            : u128,
+}
+
+impl FeeRate {
+    /// This function returns the fee charged on a payment of the given 'amount'
+    pub fn get_fee(&self, amount : u128) -> u128 {
+        (amount * self.basis_points as u128 / 10_000 + self.flat).min(amount)
+    }
+}
+
+/// # FeeSchedule
+/// The FeeSchedule represents the fees charged by the bank on 'pay_in's, 'request_debit' claims and 'pay_out's
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct FeeSchedule {
+    pub pay_in          : FeeRate,
+    pub request_debit   : FeeRate,
+    pub pay_out         : FeeRate,
+}
+
+/// # FeeScheduleChange
+/// The FeeScheduleChange represents a change made to the FeeSchedule of the bank, from the 'previous' to the 'current' schedule
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct FeeScheduleChange {
+    pub previous        : FeeSchedule,
+    pub current         : FeeSchedule,
+    pub changed_by      : String,
+    pub change_date     : i64,
+}
+
+/// # Pot
+/// The Pot represents a named internal budget of the bank e.g. 'operations', 'marketing', 'reserve' or 'payroll'. The balances of all Pots always sum to the bank balance. 
+/// Operations on a Pot are governed in the Pot's own 'operation_namespace' i.e. a 'payout' from a Pot with namespace 'marketing' is governed by the 'marketing.payout' operation. 
+/// The 'general' Pot holds everything not placed in another Pot and has an empty namespace, so it is governed by the operations of the bank itself
+#[derive(Default, Eq, PartialEq, Hash, Debug, serde::Serialize, Clone, BorshDeserialize, BorshSerialize)]
+pub struct Pot {
+    pub name                : String,
+    pub operation_namespace : String,
+    pub balance             : u128,
+    pub creation_date       : i64,
+    pub creator             : String,
+}
+
+impl Pot {
+    /// This function is used to internally create a representation of an empty Pot with the given 'name' 
+    /// 'creator' - entity that created the Pot
+    pub fn create_pot(name : String, operation_namespace : String, creator : String) -> Self {
+        Self {
+            name,
+            operation_namespace,
+            balance             : 0,
+            creation_date       : env::block_timestamp() as i64,
+            creator,
+        }
+    }
+
+    /// This function returns the name of the given 'operation' within the namespace of this Pot 
+    pub fn get_operation(&self, operation : &str) -> String {
+        if self.operation_namespace.is_empty() {
+            return operation.to_string();
+        }
+        format!("{}.{}", self.operation_namespace, operation)
+    }
+}
+
+/// # TreasuryPolicy
+/// The TreasuryPolicy represents the treasury rules of the bank. Governed outflows can not take the bank balance below the 'minimum_reserve', 
+/// anything over the 'maximum_balance' is swept to the nominee account as it comes in and a LowBalanceEvent is raised whenever the bank balance drops below the 'warning_level'. 
+/// A 'maximum_balance' or 'warning_level' of 0 switches that rule off
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct TreasuryPolicy {
+    pub minimum_reserve : u128,
+    pub maximum_balance : u128,
+    pub warning_level   : u128,
+}
+
+/// # LowBalanceEvent
+/// The LowBalanceEvent represents the bank balance dropping below the 'warning_level' of the TreasuryPolicy 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct LowBalanceEvent {
+    pub balance         : u128,
+    pub warning_level   : u128,
+    pub event_date      : i64,
+}
+
+/// # JournalEntry
+/// The JournalEntry represents a balanced double entry in the journal of the bank, debiting the 'debit_account' and crediting the 'credit_account' with the same 'amount'. 
+/// Every entry is behind the Payment with the 'payment_reference', other than the opening balance of the bank which carries zero. 
+/// The chart of accounts is: 
+/// - 'CASH' - the bank balance 
+/// - 'HELD_FUNDS' - funds held apart from the bank balance for the liabilities below 
+/// - 'ESCROW' - funds owed to escrow payees or payers 
+/// - 'RESERVES' - funds owed to the beneficiaries of vesting grants and the recipients of payment streams 
+/// - 'FEES' - fees charged that have not been withdrawn 
+/// - 'PAYABLES' - funds owed to accounts i.e. customer credit and accrued revenue splits 
+/// - 'REVENUE' - funds earned from pay ins, invoices, credit charges and subscriptions less what is given back 
+/// - 'EXPENSES' - funds paid out of the bank or set aside for vesting grants, payment streams and escrows 
+/// - 'CAPITAL' - the opening balance and funds deposited, withdrawn or swept by the owners of the bank 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct JournalEntry {
+    pub sequence            : u64,
+    pub debit_account       : String,
+    pub credit_account      : String,
+    pub amount              : u128,
+    pub entry_date          : i64,
+    pub payment_reference   : u64,
+}
+
+/// # LedgerAccount
+/// The LedgerAccount represents the running totals of the debits and credits posted to an account in the journal of the bank 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct LedgerAccount {
+    pub name            : String,
+    pub debits          : u128,
+    pub credits         : u128,
+}
+
+impl LedgerAccount {
+    /// This function is used to internally create an account with nothing posted to it 
+    pub fn create_ledger_account(name : String) -> Self {
+        Self {
+            name,
+            debits          : 0,
+            credits         : 0,
+        }
+    }
+
+    /// This function returns the debits less the credits of this account 
+    pub fn get_balance(&self) -> i128 {
+        self.debits as i128 - self.credits as i128
+    }
+}
+
+/// # PaymentAnnotation
+/// The PaymentAnnotation represents the bookkeeping details of a payment as presented to Open Bank 
+/// 'category' - optional code from the chart of payment categories of the bank 
+/// 'tags' - free-form tags 
+/// 'external_reference' - optional reference of the payment in an external system e.g. an ERP document number 
+/// 'metadata' - optional map of structured details of the payment 
+/// 'encrypted_memo' - optional memo sealed for the bank so that it is not readable on chain 
+#[derive(Default, Eq, PartialEq, Hash, PartialOrd, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct PaymentAnnotation {
+    pub category            : Option<String>,
+    pub tags                : Vec<String>,
+    pub external_reference  : Option<String>,
+    pub metadata            : Option<BTreeMap<String, String>>,
+    pub encrypted_memo      : Option<EncryptedMemo>,
+}
+
+/// # EncryptedMemo
+/// The EncryptedMemo represents a memo sealed off chain with an x25519/XSalsa20Poly1305 box i.e. NaCl 'crypto_box', so that only the holder of the secret key 
+/// matching the 'bank_public_key' can read it. All fields are base64 encoded 
+/// 'bank_public_key' - the memo public key of the bank the memo was sealed for 
+/// 'sender_public_key' - the x25519 public key the memo was sealed with 
+/// 'nonce' - the 24 byte nonce the memo was sealed with 
+/// 'ciphertext' - the sealed memo 
+#[derive(Default, Eq, PartialEq, Hash, PartialOrd, Clone, Debug, serde::Serialize, serde::Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct EncryptedMemo {
+    pub bank_public_key     : String,
+    pub sender_public_key   : String,
+    pub nonce               : String,
+    pub ciphertext          : String,
+}
+
+/// # PaymentCategory
+/// The PaymentCategory represents an entry in the chart of payment categories of the bank. Categories are created 'ACTIVE' and become 'RETIRED' when retired, 
+/// retired categories can no longer be given to new payments 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct PaymentCategory {
+    pub code            : String,
+    pub description     : String,
+    pub creation_date   : i64,
+    pub creator         : String,
+    pub status          : String,
+}
+
+impl PaymentCategory {
+    /// This function is used to internally create an 'ACTIVE' PaymentCategory with the given 'code' 
+    /// 'creator' - entity that created the PaymentCategory
+    pub fn create_payment_category(code : String, description : String, creator : String) -> Self {
+        Self {
+            code,
+            description,
+            creation_date   : env::block_timestamp() as i64,
+            creator,
+            status          : "ACTIVE".to_string(),
+        }
+    }
+}
+
+/// # ActivityTotals
+/// The ActivityTotals represents the number of payments of one kind and their total amount 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct ActivityTotals {
+    pub count           : u64,
+    pub total           : u128,
+}
+
+/// # ActivitySummary
+/// The ActivitySummary represents the pay ins, pay outs, deposits and withdrawals of the bank over the last 'days' days, where 0 'days' covers the life of the bank 
+/// 'pay_outs' - every Payment that leaves the bank other than a withdrawal i.e. request debits, payroll, standing orders, milestones, escrow releases and refunds, vesting, streams, refunds, keeper rewards, sweeps, revenue splits and credit and fee withdrawals 
+/// 'internal' - Payments that move funds within the bank i.e. credit charges, subscriptions, internal transfers, set asides and accrued revenue splits 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct ActivitySummary {
+    pub days            : u64,
+    pub pay_ins         : ActivityTotals,
+    pub pay_outs        : ActivityTotals,
+    pub deposits        : ActivityTotals,
+    pub withdrawals     : ActivityTotals,
+    pub internal        : ActivityTotals,
+}
+
+impl ActivitySummary {
+    /// This function adds the given 'payment' to the summary. It returns false if the payment type is not known 
+    pub fn record(&mut self, payment : &Payment) -> bool {
+        let totals = match (payment.payment_type.as_str(), payment.status.as_str()) {
+            ("REVENUE_SPLIT", "ACCRUED") => &mut self.internal,
+            ("PAY_IN", _) | ("CREDIT_PAY_IN", _) | ("ESCROW_PAY_IN", _) => &mut self.pay_ins,
+            ("PAY_OUT", _) | ("PAY_OUT_MULTI", _) | ("REQUEST_DEBIT", _) | ("PAYROLL", _) | ("STANDING_ORDER", _) | ("MILESTONE", _) 
+                | ("ESCROW_RELEASE", _) | ("ESCROW_REFUND", _) | ("VESTING", _) | ("STREAM", _) | ("REFUND", _) | ("KEEPER_REWARD", _) 
+                | ("SWEEP", _) | ("REVENUE_SPLIT", _) | ("REVENUE_SPLIT_DISTRIBUTION", _) | ("CREDIT_WITHDRAWAL", _) | ("FEE_WITHDRAWAL", _) => &mut self.pay_outs,
+            ("DEPOSIT", _) => &mut self.deposits,
+            ("WITHDRAWAL", _) => &mut self.withdrawals,
+            ("CREDIT_CHARGE", _) | ("SUBSCRIPTION", _) | ("INTERNAL_TRANSFER", _) | ("SET_ASIDE", _) | ("SET_ASIDE_RETURN", _) => &mut self.internal,
+            _ => return false,
+        };
+        totals.count += 1;
+        totals.total += payment.amount;
+        true
+    }
+
+    /// This function adds the totals of the given 'summary' to this summary
+    pub fn add(&mut self, summary : &ActivitySummary) {
+        for (totals, other) in [(&mut self.pay_ins, &summary.pay_ins), (&mut self.pay_outs, &summary.pay_outs), (&mut self.deposits, &summary.deposits), (&mut self.withdrawals, &summary.withdrawals), (&mut self.internal, &summary.internal)] {
+            totals.count += other.count;
+            totals.total += other.total;
+        }
+    }
+}
+
+/// # BankSummary
+/// The BankSummary represents the state of the bank for dashboards 
+/// 'outstanding_obligations' - funds held apart from the balance that are owed or committed i.e. escrow, vesting grants, payment streams, customer credit, accrued revenue splits and fees 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct BankSummary {
+    pub balance                     : u128,
+    pub activity                    : Vec<ActivitySummary>,
+    pub request_debits_by_status    : Vec<(String, u64)>,
+    pub outstanding_obligations     : u128,
+    pub last_activity_time          : i64,
+}
+
+/// # StatementPeriod
+/// The StatementPeriod represents a statement period of the bank from the 'opening_time' to the 'closing_time'. The current period is 'OPEN' and becomes 'CLOSED' when it is closed, 
+/// at which point the next period opens with the closing balance as its opening balance. 
+/// 'totals_by_type' - the number and total amount of the Payments of each payment type made in the period 
+/// 'first_payment_sequence' - 'last_payment_sequence' - the sequence range of the Payments made in the period, the last is one less than the first if no Payments were made 
+/// 'first_journal_sequence' - 'last_journal_sequence' - the sequence range of the journal entries posted in the period, the last is one less than the first if nothing was posted 
+/// 'close_due_time' - the time from which the period can be closed by a keeper, zero if the period is only closed on request 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct StatementPeriod {
+    pub number                  : u64,
+    pub opening_time            : i64,
+    pub closing_time            : i64,
+    pub close_due_time          : i64,
+    pub opening_balance         : u128,
+    pub closing_balance         : u128,
+    pub totals_by_type          : BTreeMap<String, ActivityTotals>,
+    pub first_payment_sequence  : u64,
+    pub last_payment_sequence   : u64,
+    pub first_journal_sequence  : u64,
+    pub last_journal_sequence   : u64,
+    pub closed_by               : String,
+    pub status                  : String,
+}
+
+impl StatementPeriod {
+    /// This function is used to internally open the StatementPeriod with the given 'number' starting now with the given 'opening_balance' 
+    /// 'first_payment_sequence' - sequence the next Payment will be given 
+    /// 'first_journal_sequence' - sequence the next journal entry will be given 
+    pub fn open_statement_period(number : u64, opening_balance : u128, first_payment_sequence : u64, first_journal_sequence : u64) -> Self {
+        Self {
+            number,
+            opening_time            : env::block_timestamp() as i64,
+            opening_balance,
+            first_payment_sequence,
+            last_payment_sequence   : first_payment_sequence - 1,
+            first_journal_sequence,
+            last_journal_sequence   : first_journal_sequence - 1,
+            status                  : "OPEN".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// This function adds a Payment of the given 'payment_type' and 'amount' to the totals of the period 
+    pub fn record(&mut self, payment_type : &str, amount : u128) {
+        let totals = self.totals_by_type.entry(payment_type.to_string()).or_default();
+        totals.count += 1;
+        totals.total += amount;
+    }
+
+    /// This function closes the period at the given 'closing_time' with the given 'closing_balance' and the last Payment and journal entry sequences 
+    /// 'closed_by' - entity that closed the period 
+    pub fn close(&mut self, closing_time : i64, closing_balance : u128, last_payment_sequence : u64, last_journal_sequence : u64, closed_by : String) {
+        self.closing_time = closing_time;
+        self.closing_balance = closing_balance;
+        self.last_payment_sequence = last_payment_sequence;
+        self.last_journal_sequence = last_journal_sequence;
+        self.closed_by = closed_by;
+        self.status = "CLOSED".to_string();
+    }
+}
+
+/// # StatementSchedule
+/// The StatementSchedule represents when statement periods are due to close. The period with the 'first_period_number' is due at the 'first_close_time' 
+/// and each later period one 'frequency' after the one before, where the 'frequency' is one of 'NONE', 'DAILY', 'WEEKLY' or 'MONTHLY'. 
+/// With a 'frequency' of 'NONE' periods are only closed on request 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct StatementSchedule {
+    pub frequency           : String,
+    pub first_close_time    : i64,
+    pub first_period_number : u64,
+}
+
+impl StatementSchedule {
+    /// This function returns the time the statement period with the given 'period_number' is due to close, zero if it is only closed on request 
+    pub fn get_close_time(&self, period_number : u64) -> i64 {
+        if period_number < self.first_period_number {
+            return 0;
+        }
+        let index = (period_number - self.first_period_number) as i64;
+        match self.frequency.as_str() {
+            "DAILY" => self.first_close_time + DAY_MILLIS * index,
+            "WEEKLY" => self.first_close_time + WEEK_MILLIS * index,
+            "MONTHLY" => {
+                // months are always counted from the first close so that a short month does not move later closes
+                add_months(self.first_close_time, index as u64)
+            },
+            _ => 0,
+        }
+    }
+}
+
+/// # AuditCheckpoint
+/// The AuditCheckpoint represents how far the Payments have been audited. The 'rebuilt_balance' is the opening balance of the bank moved by each Payment 
+/// up to and including the one with the 'payment_sequence', so each audit only has to go over the Payments made since. 
+/// 'missing_payments' - the Payments registered up to the 'payment_sequence' that are missing from the Payment records 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct AuditCheckpoint {
+    pub payment_sequence    : u64,
+    pub rebuilt_balance     : i128,
+    pub missing_payments    : u64,
+    pub checkpoint_time     : i64,
+}
+
+/// # AuditReport
+/// The AuditReport represents the result of an audit of the bank. 
+/// 'complete' - whether the Payments have been audited up to the latest, the 'ledger_difference' is only worked out once they have 
+/// 'recomputed_balance' - the balance of the bank rebuilt from its opening balance and the Payments up to the 'audited_payment_sequence' 
+/// 'ledger_difference' - the bank balance less the recomputed balance 
+/// 'payment_difference' - the number of Payments registered that are missing from the Payment records 
+/// 'held_balance' - the bank balance and the funds held apart from it i.e. escrow, vesting grants, payment streams, customer credit, accrued revenue splits and fees 
+/// 'account_balance' - the balance of the account the bank is deployed to, including the 'storage_cost' locked for storage 
+/// 'account_difference' - the account balance less the storage cost and the held balance. Any difference, a surplus e.g. from gas rewards as well as a shortfall, is a mismatch 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct AuditReport {
+    pub audit_time                  : i64,
+    pub complete                    : bool,
+    pub audited_payment_sequence    : u64,
+    pub recomputed_balance          : i128,
+    pub bank_balance                : u128,
+    pub ledger_difference           : i128,
+    pub payment_difference          : i64,
+    pub held_balance                : u128,
+    pub account_balance             : u128,
+    pub storage_cost                : u128,
+    pub account_difference          : i128,
+    pub mismatch                    : bool,
+    pub outflows_paused             : bool,
+}
+
+/// # PaymentConfirmation
+/// The PaymentConfirmation represents the redacted view of a Payment that anyone can see, it confirms the Payment exists without showing who was paid what 
+#[derive(Default, Eq, PartialEq, Hash, Clone, Debug, serde::Serialize, BorshDeserialize, BorshSerialize)]
+pub struct PaymentConfirmation {
+    pub reference       : u64,
+    pub status          : String,
+    pub payment_time    : i64,
 }
@@ -0,0 +1,80 @@
+/// SPDX-License-Identifier: APACHE 2.0
+/// <br/> 
+/// <br/> # Open Bank 'memo' mod for NEAR blockchain 
+/// <br/> 
+/// <br/> @author Block Star Logic 
+/// <br/> @coder T Ushewokunze 
+/// <br/> @license Apache 2.0 
+/// <br/>
+/// <br/> This module helps the back office and Rust payers work with encrypted memos. It is not part of the deployed contract. 
+/// <br/> Memos are sealed with an x25519/XSalsa20Poly1305 box i.e. NaCl 'crypto_box', so memos sealed by other NaCl libraries e.g. tweetnacl can be read here and vice versa. 
+/// <br/> Keys, nonces and ciphertexts are base64 encoded as they are on chain. 
+use crypto_box::aead::{Aead, AeadCore, OsRng};
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+
+use crate::{MEMO_KEY_LENGTH, MEMO_NONCE_LENGTH};
+
+pub use crate::ob_io::EncryptedMemo;
+
+/// This function generates a new memo key pair for the bank 
+/// # Return Value 
+/// base64 secret key to be kept by the back office and base64 public key to be set with **set_memo_public_key** 
+pub fn generate_memo_key_pair() -> (String, String) {
+    let secret_key = SecretKey::generate(&mut OsRng);
+    (base64::encode(secret_key.to_bytes()), base64::encode(secret_key.public_key().as_bytes()))
+}
+
+/// This function returns the base64 public key matching the given base64 'memo_secret_key' 
+pub fn get_memo_public_key(memo_secret_key : &str) -> Result<String, String> {
+    let secret_key = SecretKey::from(decode_key(memo_secret_key)?);
+    Ok(base64::encode(secret_key.public_key().as_bytes()))
+}
+
+/// This function seals the given 'memo' for the bank with the given base64 'bank_public_key' i.e. the key returned by **view_memo_public_key**. 
+/// A new sender key and nonce are used for every memo 
+/// # Return Value 
+/// **EncryptedMemo** to be presented in the annotation of a payment 
+pub fn encrypt_memo(bank_public_key : &str, memo : &str) -> Result<EncryptedMemo, String> {
+    let public_key = PublicKey::from(decode_key(bank_public_key)?);
+    let sender_secret_key = SecretKey::generate(&mut OsRng);
+    let nonce = SalsaBox::generate_nonce(&mut OsRng);
+
+    let ciphertext = SalsaBox::new(&public_key, &sender_secret_key).encrypt(&nonce, memo.as_bytes())
+                        .map_err(|_| "MEMO ENCRYPTION FAILED".to_string())?;
+
+    Ok(EncryptedMemo {
+        bank_public_key     : bank_public_key.to_string(),
+        sender_public_key   : base64::encode(sender_secret_key.public_key().as_bytes()),
+        nonce               : base64::encode(nonce),
+        ciphertext          : base64::encode(ciphertext),
+    })
+}
+
+/// This function opens the given 'encrypted_memo' with the given base64 'memo_secret_key' of the bank 
+/// # Return Value 
+/// the memo 
+/// @error if the memo was not sealed for the key or has been tampered with 
+pub fn decrypt_memo(memo_secret_key : &str, encrypted_memo : &EncryptedMemo) -> Result<String, String> {
+    let secret_key = SecretKey::from(decode_key(memo_secret_key)?);
+    let sender_public_key = PublicKey::from(decode_key(&encrypted_memo.sender_public_key)?);
+    let nonce = base64::decode(&encrypted_memo.nonce).map_err(|_| format!("INVALID MEMO NONCE {} ", encrypted_memo.nonce))?;
+    if nonce.len() != MEMO_NONCE_LENGTH {
+        return Err(format!("INVALID MEMO NONCE {} ", encrypted_memo.nonce));
+    }
+    let ciphertext = base64::decode(&encrypted_memo.ciphertext).map_err(|_| "INVALID MEMO CIPHERTEXT".to_string())?;
+
+    let memo = SalsaBox::new(&sender_public_key, &secret_key).decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+                        .map_err(|_| "MEMO DECRYPTION FAILED".to_string())?;
+    String::from_utf8(memo).map_err(|_| "MEMO DECRYPTION FAILED".to_string())
+}
+
+// decodes a base64 x25519 key 
+fn decode_key(key : &str) -> Result<[u8; MEMO_KEY_LENGTH], String> {
+    let bytes = base64::decode(key).map_err(|_| format!("INVALID MEMO KEY {} ", key))?;
+    if bytes.len() != MEMO_KEY_LENGTH {
+        return Err(format!("INVALID MEMO KEY {} ", key));
+    }
+    let mut decoded = [0u8; MEMO_KEY_LENGTH];
+    decoded.copy_from_slice(&bytes);
+    Ok(decoded)
+}
@@ -27,18 +27,36 @@ pub trait TOpenBank {
     fn find_payment(&self, 
         payment_ref :u64) -> PromiseOrValue<Payment>;
 
+    fn find_payments_by_external_reference(&self, 
+        external_reference : String) -> PromiseOrValue<Vec<Payment>>;
+
     fn is_valid_payment_ref(&self, 
         payment_ref : u64) -> PromiseOrValue<bool>;
 
+    fn verify_payment(&self, 
+        payment_ref : u64) -> PromiseOrValue<PaymentConfirmation>;
+
+    fn is_privacy_mode(&self) -> PromiseOrValue<bool>;
+
+    fn view_memo_public_key(&self) -> PromiseOrValue<String>;
+
     fn pay_in(&mut self, 
         payment_description :  String ,  
         payment_amount : u128, 
+        pay_in_tag : Option<String>, 
+        pot : Option<String>, 
+        annotation : Option<PaymentAnnotation>, 
         nonce : u64)->  PromiseOrValue<Payment>;
 
     fn request_debit(&mut self, 
         request_debit_ref : u64, 
         nonce : u64) -> PromiseOrValue<Payment>;
 
+    fn find_due_request_debits(&self) -> PromiseOrValue<Vec<RequestDebit>>;
+
+    fn process_due_request_debits(&mut self, 
+        limit : u64) -> PromiseOrValue<Vec<Payment>>;
+
     fn register_request_debit(&mut self, 
         payee           : String,
         description     : String, 
@@ -46,11 +64,87 @@ pub trait TOpenBank {
         payout_interval : i64, 
         start_date      : i64, 
         end_date        : i64, 
+        pot             : Option<String>,
+        annotation      : Option<PaymentAnnotation>,
         nonce : u64)-> PromiseOrValue<u64>;
 
     fn cancel_request_debit(mut self, 
         request_debit_ref : u64, 
         nonce : u64) -> PromiseOrValue<u64>;
+
+    fn find_vesting_grants_by_beneficiary(&self, 
+        beneficiary : String) -> PromiseOrValue<Vec<VestingGrant>>;
+
+    fn view_vested_amount(&self, 
+        vesting_grant_ref : u64) -> PromiseOrValue<(u128, u128)>;
+
+    fn claim_vested(&mut self, 
+        vesting_grant_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn find_payment_streams_by_recipient(&self, 
+        recipient : String) -> PromiseOrValue<Vec<PaymentStream>>;
+
+    fn view_payment_stream_balance(&self, 
+        payment_stream_ref : u64) -> PromiseOrValue<(u128, u128)>;
+
+    fn withdraw_from_stream(&mut self, 
+        payment_stream_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn create_escrow_from_pay_in(&mut self, 
+        escrow : EscrowRequest, 
+        nonce : u64) -> PromiseOrValue<u64>;
+
+    fn approve_escrow(&mut self, 
+        escrow_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Escrow>;
+
+    fn release_escrow(&mut self, 
+        escrow_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn find_escrow(&self, 
+        escrow_ref : u64) -> PromiseOrValue<Escrow>;
+
+    fn find_supplier_contracts_by_supplier(&self, 
+        supplier : String) -> PromiseOrValue<Vec<SupplierContract>>;
+
+    fn find_milestone(&self, 
+        milestone_id : u64) -> PromiseOrValue<Milestone>;
+
+    fn mark_milestone_delivered(&mut self, 
+        milestone_id : u64, 
+        nonce : u64) -> PromiseOrValue<Milestone>;
+
+    fn pay_invoice(&mut self, 
+        invoice_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn find_invoice(&self, 
+        invoice_ref : u64) -> PromiseOrValue<Invoice>;
+
+    fn view_customer_credit(&self, 
+        account_id : String) -> PromiseOrValue<u128>;
+
+    fn withdraw_credit(&mut self, 
+        amount : u128, 
+        nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn is_subscribed(&self, 
+        subscriber : String, 
+        subscription_plan_ref : u64) -> PromiseOrValue<bool>;
+
+    fn subscribe(&mut self, 
+        subscription_plan_ref : u64, 
+        nonce : u64) -> PromiseOrValue<Subscription>;
+
+    fn cancel_subscription(&mut self, 
+        subscription_ref : u64, 
+        nonce : u64) -> PromiseOrValue<u64>;
+
+    fn process_due_subscriptions(&mut self, 
+        limit : u64) -> PromiseOrValue<Vec<Payment>>;
 }
 
 #[ext_contract(ext_open_bank_admin)]
@@ -59,6 +153,10 @@ pub trait TOpenBankAdmin {
 
     fn view_balance(&mut self) -> PromiseOrValue<u128>;
 
+    fn view_liquidity_forecast(&mut self, 
+        horizon     : i64, 
+        granularity : String) -> PromiseOrValue<LiquidityForecast>;
+
     fn find_request_debit(&self, 
         request_debit_reference : u64) -> PromiseOrValue<RequestDebit>;
 
@@ -68,6 +166,9 @@ pub trait TOpenBankAdmin {
     fn find_payment(&self, 
         payment_ref :u64) -> PromiseOrValue<Payment>;
 
+    fn find_payments_by_external_reference(&self, 
+        external_reference : String) -> PromiseOrValue<Vec<Payment>>;
+
     fn is_valid_payment_ref(&self, 
         payment_ref : u64) -> PromiseOrValue<bool>;
 
@@ -78,21 +179,255 @@ pub trait TOpenBankAdmin {
         payout_interval : i64, 
         start_date      : i64, 
         end_date        : i64, 
+        pot             : Option<String>,
+        annotation      : Option<PaymentAnnotation>,
         nonce : u64)-> PromiseOrValue<u64>;
 
     fn cancel_request_debit(mut self, 
         request_debit_ref : u64, 
         nonce : u64) -> PromiseOrValue<u64>;
 
-    fn pay_out(&mut self, description : String, amount :u128, account_id : String, nonce : u64) -> PromiseOrValue<Payment>;
+    fn pay_out(&mut self, description : String, amount :u128, account_id : String, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : u64) -> PromiseOrValue<Payment>;
 
-    fn pay_out_multi(&mut self, multi_payment_requests : HashSet<MultiPaymentRequest>, nonce : u64) -> PromiseOrValue<HashSet<Payment>>;
+    fn refund_payment(&mut self, payment_ref : u64, amount : u128, reason : String, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn pay_out_multi(&mut self, multi_payment_requests : HashSet<MultiPaymentRequest>, pot : Option<String>, nonce : u64) -> PromiseOrValue<HashSet<Payment>>;
 
     fn approve_request_debit(mut self, request_debit_ref : u64, nonce: u64) -> PromiseOrValue<u64>;
 
-    fn deposit(&mut self, description : String, amount : u128, nonce : u64) -> PromiseOrValue<Payment>;
+    fn approve_request_debits(&mut self, request_debit_refs : Vec<u64>, strict : bool, nonce : u64) -> PromiseOrValue<Vec<RequestDebitBatchResult>>;
+
+    fn cancel_request_debits(&mut self, request_debit_refs : Vec<u64>, strict : bool, nonce : u64) -> PromiseOrValue<Vec<RequestDebitBatchResult>>;
+
+    fn deposit(&mut self, description : String, amount : u128, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn withdraw(&mut self, description : String, amount : u128, pot : Option<String>, annotation : Option<PaymentAnnotation>, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn create_scheduled_payout(&mut self, 
+        payee               : String,
+        description         : String, 
+        amount              : u128, 
+        frequency           : String, 
+        first_payment_date  : i64, 
+        end_date            : i64, 
+        pot                 : Option<String>,
+        nonce : u64) -> PromiseOrValue<u64>;
+
+    fn cancel_scheduled_payout(&mut self, scheduled_payout_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn find_scheduled_payout(&self, scheduled_payout_ref : u64) -> PromiseOrValue<ScheduledPayout>;
+
+    fn find_scheduled_payouts_by_status(&self, status : String) -> PromiseOrValue<Vec<ScheduledPayout>>;
+
+    fn process_due_scheduled_payouts(&mut self, limit : u64) -> PromiseOrValue<Vec<Payment>>;
+
+    fn create_payroll_run(&mut self, description : String, lines : Vec<MultiPaymentRequest>, pot : Option<String>, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn approve_payroll_run(&mut self, payroll_run_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn cancel_payroll_run(&mut self, payroll_run_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn execute_payroll_run(&mut self, payroll_run_ref : u64, limit : u64, nonce : u64) -> PromiseOrValue<PayrollRun>;
+
+    fn find_payroll_run(&self, payroll_run_ref : u64) -> PromiseOrValue<PayrollRun>;
+
+    fn find_payroll_runs_by_status(&self, status : String) -> PromiseOrValue<Vec<PayrollRun>>;
+
+    fn create_vesting_grant(&mut self, grant : VestingGrantRequest, pot : Option<String>, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn revoke_vesting_grant(&mut self, vesting_grant_ref : u64, nonce : u64) -> PromiseOrValue<u128>;
+
+    fn find_vesting_grant(&self, vesting_grant_ref : u64) -> PromiseOrValue<VestingGrant>;
+
+    fn create_payment_stream(&mut self, recipient : String, rate_per_second : u128, start_time : i64, stop_time : i64, pot : Option<String>, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn pause_payment_stream(&mut self, payment_stream_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn resume_payment_stream(&mut self, payment_stream_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn cancel_payment_stream(&mut self, payment_stream_ref : u64, nonce : u64) -> PromiseOrValue<u128>;
+
+    fn find_payment_stream(&self, payment_stream_ref : u64) -> PromiseOrValue<PaymentStream>;
+
+    fn create_escrow(&mut self, escrow : EscrowRequest, pot : Option<String>, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn refund_escrow(&mut self, escrow_ref : u64, nonce : u64) -> PromiseOrValue<Escrow>;
+
+    fn find_escrows_by_status(&self, status : String) -> PromiseOrValue<Vec<Escrow>>;
+
+    fn view_escrow_balance(&mut self) -> PromiseOrValue<u128>;
+
+    fn create_supplier_contract(&mut self, supplier : String, description : String, milestones : Vec<MilestoneRequest>, pot : Option<String>, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn accept_milestone(&mut self, milestone_id : u64, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn reject_milestone(&mut self, milestone_id : u64, nonce : u64) -> PromiseOrValue<Milestone>;
+
+    fn cancel_supplier_contract(&mut self, supplier_contract_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn find_supplier_contract(&self, supplier_contract_ref : u64) -> PromiseOrValue<SupplierContract>;
+
+    fn create_invoice(&mut self, invoice : InvoiceRequest, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn find_invoices_by_status(&self, status : String) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn find_open_invoices(&self) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn find_overdue_invoices(&self) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn charge_credit(&mut self, account_id : String, amount : u128, memo : String, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn view_credit_balance(&mut self) -> PromiseOrValue<u128>;
+
+    fn find_customer_credits(&self) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn create_subscription_plan(&mut self, name : String, price : u128, period : i64, grace_period : i64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn find_subscription_plan(&self, subscription_plan_ref : u64) -> PromiseOrValue<SubscriptionPlan>;
+
+    fn find_subscription(&self, subscription_ref : u64) -> PromiseOrValue<Subscription>;
+
+    fn find_subscriptions_by_subscriber(&self, subscriber : String) -> PromiseOrValue<Vec<Subscription>>;
+
+    fn set_split_rule(&mut self, split_rule : SplitRuleRequest, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn remove_split_rule(&mut self, split_rule_ref : u64, nonce : u64) -> PromiseOrValue<u64>;
+
+    fn distribute_split_accruals(&mut self, limit : u64, nonce : u64) -> PromiseOrValue<Vec<Payment>>;
+
+    fn find_split_rule(&self, split_rule_ref : u64) -> PromiseOrValue<SplitRule>;
+
+    fn find_active_split_rules(&self) -> PromiseOrValue<Vec<SplitRule>>;
+
+    fn find_split_accruals(&self) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn set_fee_schedule(&mut self, fee_schedule : FeeSchedule, nonce : u64) -> PromiseOrValue<FeeScheduleChange>;
+
+    fn withdraw_fees(&mut self, amount : u128, account_id : String, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn view_fee_balance(&mut self) -> PromiseOrValue<u128>;
+
+    fn view_fee_schedule(&self) -> PromiseOrValue<FeeSchedule>;
+
+    fn find_fee_schedule_changes(&self) -> PromiseOrValue<Vec<FeeScheduleChange>>;
+
+    fn add_payment_category(&mut self, code : String, description : String, nonce : u64) -> PromiseOrValue<PaymentCategory>;
+
+    fn retire_payment_category(&mut self, code : String, nonce : u64) -> PromiseOrValue<PaymentCategory>;
+
+    fn find_payment_categories(&self) -> PromiseOrValue<Vec<PaymentCategory>>;
+
+    fn find_category_totals(&self, from_time : i64, to_time : i64) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn set_treasury_policy(&mut self, treasury_policy : TreasuryPolicy, nonce : u64) -> PromiseOrValue<TreasuryPolicy>;
+
+    fn view_treasury_policy(&self) -> PromiseOrValue<TreasuryPolicy>;
+
+    fn find_low_balance_events(&self) -> PromiseOrValue<Vec<LowBalanceEvent>>;
+
+    fn create_pot(&mut self, name : String, operation_namespace : String, nonce : u64) -> PromiseOrValue<Pot>;
+
+    fn transfer_between_pots(&mut self, from_pot : String, to_pot : String, amount : u128, description : String, nonce : u64) -> PromiseOrValue<Payment>;
+
+    fn view_pot_balance(&mut self, pot : String) -> PromiseOrValue<u128>;
+
+    fn find_pots(&mut self) -> PromiseOrValue<Vec<Pot>>;
+
+    fn get_bank_summary(&mut self, periods : Vec<u64>) -> PromiseOrValue<BankSummary>;
+
+    fn view_trial_balance(&mut self) -> PromiseOrValue<Vec<LedgerAccount>>;
+
+    fn find_ledger_entries(&mut self, account : String) -> PromiseOrValue<Vec<JournalEntry>>;
+
+    fn check_ledger(&self) -> PromiseOrValue<bool>;
+
+    fn set_statement_schedule(&mut self, frequency : String, first_close_time : i64, nonce : u64) -> PromiseOrValue<StatementSchedule>;
+
+    fn close_statement_period(&mut self, nonce : u64) -> PromiseOrValue<StatementPeriod>;
+
+    fn process_due_statement_period(&mut self) -> PromiseOrValue<Option<StatementPeriod>>;
+
+    fn view_open_statement_period(&mut self) -> PromiseOrValue<StatementPeriod>;
+
+    fn find_statement_periods(&mut self) -> PromiseOrValue<Vec<StatementPeriod>>;
+
+    fn check_statement_period(&self, statement_period_number : u64) -> PromiseOrValue<bool>;
+
+    fn audit(&mut self, limit : u64, strict : bool, nonce : u64) -> PromiseOrValue<AuditReport>;
+
+    fn find_request_debit_private(&mut self, request_debit_reference : u64) -> PromiseOrValue<RequestDebit>;
+
+    fn find_request_debits_by_status_private(&mut self, status : String) -> PromiseOrValue<HashSet<RequestDebit>>;
+
+    fn find_due_request_debits_private(&mut self) -> PromiseOrValue<Vec<RequestDebit>>;
+
+    fn find_payment_private(&mut self, payment_ref : u64) -> PromiseOrValue<Payment>;
+
+    fn find_payments_by_external_reference_private(&mut self, external_reference : String) -> PromiseOrValue<Vec<Payment>>;
+
+    fn find_scheduled_payout_private(&mut self, scheduled_payout_ref : u64) -> PromiseOrValue<ScheduledPayout>;
+
+    fn find_scheduled_payouts_by_status_private(&mut self, status : String) -> PromiseOrValue<Vec<ScheduledPayout>>;
+
+    fn find_payroll_run_private(&mut self, payroll_run_ref : u64) -> PromiseOrValue<PayrollRun>;
+
+    fn find_payroll_runs_by_status_private(&mut self, status : String) -> PromiseOrValue<Vec<PayrollRun>>;
+
+    fn find_vesting_grant_private(&mut self, vesting_grant_ref : u64) -> PromiseOrValue<VestingGrant>;
+
+    fn find_vesting_grants_by_beneficiary_private(&mut self, beneficiary : String) -> PromiseOrValue<Vec<VestingGrant>>;
+
+    fn view_vested_amount_private(&mut self, vesting_grant_ref : u64) -> PromiseOrValue<(u128, u128)>;
+
+    fn find_payment_stream_private(&mut self, payment_stream_ref : u64) -> PromiseOrValue<PaymentStream>;
+
+    fn find_payment_streams_by_recipient_private(&mut self, recipient : String) -> PromiseOrValue<Vec<PaymentStream>>;
+
+    fn view_payment_stream_balance_private(&mut self, payment_stream_ref : u64) -> PromiseOrValue<(u128, u128)>;
+
+    fn find_escrow_private(&mut self, escrow_ref : u64) -> PromiseOrValue<Escrow>;
+
+    fn find_escrows_by_status_private(&mut self, status : String) -> PromiseOrValue<Vec<Escrow>>;
+
+    fn find_supplier_contract_private(&mut self, supplier_contract_ref : u64) -> PromiseOrValue<SupplierContract>;
+
+    fn find_supplier_contracts_by_supplier_private(&mut self, supplier : String) -> PromiseOrValue<Vec<SupplierContract>>;
+
+    fn find_milestone_private(&mut self, milestone_id : u64) -> PromiseOrValue<Milestone>;
+
+    fn find_invoice_private(&mut self, invoice_ref : u64) -> PromiseOrValue<Invoice>;
+
+    fn find_invoices_by_status_private(&mut self, status : String) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn find_open_invoices_private(&mut self) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn find_overdue_invoices_private(&mut self) -> PromiseOrValue<Vec<Invoice>>;
+
+    fn view_customer_credit_private(&mut self, account_id : String) -> PromiseOrValue<u128>;
+
+    fn find_customer_credits_private(&mut self) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn find_subscription_private(&mut self, subscription_ref : u64) -> PromiseOrValue<Subscription>;
+
+    fn find_subscriptions_by_subscriber_private(&mut self, subscriber : String) -> PromiseOrValue<Vec<Subscription>>;
+
+    fn find_split_rule_private(&mut self, split_rule_ref : u64) -> PromiseOrValue<SplitRule>;
+
+    fn find_active_split_rules_private(&mut self) -> PromiseOrValue<Vec<SplitRule>>;
+
+    fn find_split_accruals_private(&mut self) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn find_category_totals_private(&mut self, from_time : i64, to_time : i64) -> PromiseOrValue<Vec<(String, u128)>>;
+
+    fn resume_outflows(&mut self, nonce : u64) -> PromiseOrValue<bool>;
+
+    fn is_outflows_paused(&self) -> PromiseOrValue<bool>;
+
+    fn set_privacy_mode(&mut self, privacy_mode : bool, nonce : u64) -> PromiseOrValue<bool>;
+
+    fn set_memo_public_key(&mut self, memo_public_key : String, nonce : u64) -> PromiseOrValue<String>;
 
-    fn withdraw(&mut self, description : String, amount : u128, nonce : u64) -> PromiseOrValue<Payment>;
+    fn set_keeper_reward(&mut self, reward : u128, budget : u128, pot : Option<String>) -> PromiseOrValue<bool>;
 
     fn set_open_bank_nominee_account(&mut self, nominee_account_id : String) -> PromiseOrValue<bool>;
 
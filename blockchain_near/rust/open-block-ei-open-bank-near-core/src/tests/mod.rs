@@ -32,6 +32,11 @@ fn get_context_with_deposit(input: Vec<u8>, is_view: bool, attached_deposit : u1
     }
 }
 
+// payments are made through promises, this sets up the mocked blockchain to report each transfer as successful
+fn set_context_with_transfer_results(context : VMContext) {
+    testing_env!(context, Default::default(), Default::default(), Default::default(), vec![near_sdk::PromiseResult::Successful(vec![1])]);
+}
+
 fn get_default_ob() -> super::OpenBank {
 
     let start_date = I64(Utc::now().timestamp_millis());
@@ -57,9 +62,9 @@ fn get_default_ob() -> super::OpenBank {
     ob.set_obei_open_roles(mock_or_account.to_string());
     ob.set_open_bank_name("test_bank".to_string());
 
-    ob.pay_in("test_payment".to_string(), pay_in_amount, nonce);
+    ob.pay_in("test_payment".to_string(), pay_in_amount, None, None, None, nonce);
     let new_nonce = U64(env::block_timestamp()+11);
-    ob.register_request_debit("testaccount.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, new_nonce);
+    ob.register_request_debit("testaccount.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, new_nonce);
            
     ob
 }
@@ -95,7 +100,7 @@ fn test_find_request_debit () {
     let nonce = U64((Utc::now().timestamp_millis()+16) as u64);
     let request_debit_amount = U128(1); 
 
-    let request_debit_reference = ob.register_request_debit("testaccount.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, nonce);
+    let request_debit_reference = ob.register_request_debit("testaccount.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, nonce);
 
     let request_debit = ob.find_request_debit(request_debit_reference);
     assert_eq!("testaccount.testnet", request_debit.payee)
@@ -122,7 +127,7 @@ fn test_find_payment () {
 
     let nonce = U64((Utc::now().timestamp_millis()+12) as u64);
     let pay_in_amount = U128(10); 
-    let payment_ref = U64(ob.pay_in("test_payment".to_string(), pay_in_amount, nonce).reference); 
+    let payment_ref = U64(ob.pay_in("test_payment".to_string(), pay_in_amount, None, None, None, nonce).reference); 
     let payment = ob.find_payment(payment_ref);
     
     assert_eq!(10, payment.amount)
@@ -137,7 +142,7 @@ fn test_is_valid_payment_ref () {
     let mut ob = get_default_ob(); 
     let nonce = U64((Utc::now().timestamp_millis()+13) as u64);
     let pay_in_amount = U128(10);
-    let payment_ref = U64(ob.pay_in("test_payment".to_string(), pay_in_amount, nonce).reference); 
+    let payment_ref = U64(ob.pay_in("test_payment".to_string(), pay_in_amount, None, None, None, nonce).reference); 
 
     assert!(ob.is_valid_payment_ref(payment_ref))
 }
@@ -150,12 +155,11 @@ fn test_pay_in () {
 
     let mut ob = get_default_ob();     
 
-    ob.pay_in("next_test_payment".to_string(), U128(10) ,U64((Utc::now().timestamp_millis()+14) as u64));
+    ob.pay_in("next_test_payment".to_string(), U128(10), None, None, None, U64((Utc::now().timestamp_millis()+14) as u64));
 
     assert_eq!(30,u128::from(ob.view_balance()));
 }
 
-
 #[test] // @done
 fn test_register_request_debit (){
 
@@ -169,7 +173,7 @@ fn test_register_request_debit (){
     let interval  = I64(60);
     let nonce = U64((Utc::now().timestamp_millis()+15) as u64);
 
-    let rd_ref  = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, nonce);
+    let rd_ref  = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, nonce);
 
     let rd = ob.find_request_debit(rd_ref);
 
@@ -191,7 +195,7 @@ fn test_approve_request_debit (){
     let interval  = I64(60);
     let nonce = U64(Utc::now().timestamp_millis() as u64);
     
-    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, nonce);
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, nonce);
 
     let rd = ob.find_request_debit(rd_ref);
 
@@ -217,7 +221,7 @@ fn test_cancel_request_debit () {
     let interval  = I64(60);
     let nonce = U64(Utc::now().timestamp_millis() as u64);
 
-    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, nonce);
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, nonce);
 
     let rd = ob.find_request_debit(rd_ref);
 
@@ -249,7 +253,7 @@ fn test_deposit () {
     
     let amount = U128(10);
 
-    let payment = ob.deposit("test deposit".to_string(),amount, U64((Utc::now().timestamp_millis()+17) as u64));
+    let payment = ob.deposit("test deposit".to_string(),amount, None, None, U64((Utc::now().timestamp_millis()+17) as u64));
 
     let test_payment = ob.find_payment(U64(payment.reference));
 
@@ -425,7 +429,7 @@ fn test_check_request_debit_interval () {
     let interval  = I64(30000000000);
     let nonce = U64(Utc::now().timestamp_millis() as u64);
     
-    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, nonce);
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, nonce);
 
     let request_debit = ob.find_request_debit(rd_ref); 
 
@@ -442,7 +446,7 @@ fn test_decrement_bank_balance () {
     
     let bal = ob.view_balance(); 
 
-    ob.decrement_bank_balance(1);
+    ob.decrement_bank_balance(1, "EXPENSES");
 
     let total =U128(u128::from(bal) - 1); 
 
@@ -459,14 +463,13 @@ fn test_increment_bank_balance () {
     
     let bal = ob.view_balance(); 
 
-    ob.increment_bank_balance(1);
+    ob.increment_bank_balance(1, "REVENUE");
 
     let total = U128(u128::from(bal) + 1);
 
     assert_eq!(total, ob.view_balance());
 }
 
-
 #[test] // @internal @ done
 fn test_move_request_debit_by_status () {
 
@@ -480,7 +483,7 @@ fn test_move_request_debit_by_status () {
     let interval  = I64(60);
     let nonce = U64(Utc::now().timestamp_millis() as u64);
     let new_nonce = U64((Utc::now().timestamp_millis()+10) as u64);
-    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, new_nonce);
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), request_debit_amount, interval, start_date, end_date, None, None, new_nonce);
 
     let rd = ob.find_request_debit(rd_ref);     
 
@@ -488,3 +491,3106 @@ fn test_move_request_debit_by_status () {
 
     ob.move_request_debit_by_status("PENDING".to_string(), "APPROVED".to_string(), rd);
 }
+
+#[test]
+fn test_view_liquidity_forecast () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let interval = I64(24*60*60*1000);
+    let start_date = I64(time_now);
+    let end_date = I64(time_now + (10*24*60*60*1000));
+
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "daily supplier".to_string(), U128(5), interval, start_date, end_date, None, None, U64(env::block_timestamp()+100));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+101));
+
+    let forecast = ob.view_liquidity_forecast(I64(7*24*60*60*1000), "DAILY".to_string());
+
+    assert_eq!(forecast.opening_balance, 20);
+    assert_eq!(forecast.periods.len(), 7);
+    assert_eq!(forecast.periods[0].obligations, 5);
+    assert_eq!(forecast.periods[3].projected_balance, 0);
+    assert_eq!(forecast.periods[4].shortfall, 5);
+    assert_eq!(forecast.total_obligations, 35);
+    assert_eq!(forecast.shortfall_date, Some(forecast.periods[4].period_start));
+
+    let weekly_forecast = ob.view_liquidity_forecast(I64(14*24*60*60*1000), "WEEKLY".to_string());
+
+    assert_eq!(weekly_forecast.periods.len(), 2);
+    // only ten payments remain before the end date
+    assert_eq!(weekly_forecast.total_obligations, 50);
+    assert_eq!(weekly_forecast.shortfall_date, Some(weekly_forecast.periods[0].period_start));
+}
+
+#[test]
+#[should_panic (expected = "UNKNOWN FORECAST GRANULARITY")]
+fn test_view_liquidity_forecast_unknown_granularity () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.view_liquidity_forecast(I64(24*60*60*1000), "MONTHLY".to_string());
+}
+
+#[test]
+fn test_count_request_debit_payments_due_by () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), U128(1), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+102));
+    let rd = ob.find_request_debit(rd_ref);
+
+    // the three overdue payments collapse into one claim now and the end date caps the rest
+    assert_eq!(super::OpenBank::count_request_debit_payments_due_by(&rd, time_now), 1);
+    assert_eq!(super::OpenBank::count_request_debit_payments_due_by(&rd, time_now + 100000), 11);
+    assert_eq!(super::OpenBank::count_request_debit_payments_due_by(&rd, time_now - 3000), 0);
+}
+
+#[test]
+fn test_approve_request_debits () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let start_date = I64(Utc::now().timestamp_millis());
+    let end_date = I64(i64::from(start_date) + (24*60*60*1000));
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), U128(1), I64(60), start_date, end_date, None, None, U64(env::block_timestamp()+200));
+
+    let results = ob.approve_request_debits(vec![rd_ref, U64(0), rd_ref], false, U64(env::block_timestamp()+201));
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].result, "APPROVED");
+    assert_eq!(results[1].result, "SKIPPED");
+    assert_eq!(results[1].reason, "UNKNOWN REQUEST DEBIT REFERENCE");
+    assert_eq!(results[2].result, "SKIPPED");
+
+    let rd = ob.find_request_debit(rd_ref);
+    assert_eq!(rd.status, "APPROVED");
+    assert_eq!(rd.approved_by, "robert.testnet");
+    assert_eq!(ob.find_request_debits_by_status("APPROVED".to_string()).len(), 1);
+    // only the request debit registered by the default bank remains pending
+    assert_eq!(ob.find_request_debits_by_status("PENDING".to_string()).len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "BATCH STOPPED")]
+fn test_approve_request_debits_strict () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let start_date = I64(Utc::now().timestamp_millis());
+    let end_date = I64(i64::from(start_date) + (24*60*60*1000));
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), U128(1), I64(60), start_date, end_date, None, None, U64(env::block_timestamp()+202));
+
+    ob.approve_request_debits(vec![rd_ref, U64(0)], true, U64(env::block_timestamp()+203));
+}
+
+#[test]
+fn test_cancel_request_debits () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let start_date = I64(Utc::now().timestamp_millis());
+    let end_date = I64(i64::from(start_date) + (24*60*60*1000));
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "test request debit".to_string(), U128(1), I64(60), start_date, end_date, None, None, U64(env::block_timestamp()+204));
+    ob.cancel_request_debit(rd_ref, U64(env::block_timestamp()+205));
+
+    let pending : Vec<U64> = ob.find_request_debits_by_status("PENDING".to_string()).iter().map(|rd| U64(rd.reference)).collect();
+    let mut refs = pending.clone();
+    refs.push(rd_ref);
+
+    let results = ob.cancel_request_debits(refs, false, U64(env::block_timestamp()+206));
+
+    assert_eq!(results[0].result, "CANCELLED");
+    assert_eq!(results[1].result, "SKIPPED");
+    assert_eq!(results[1].reason, "REQUEST DEBIT ALREADY CANCELLED");
+    assert_eq!(ob.find_request_debits_by_status("CANCELLED".to_string()).len(), 2);
+    assert!(ob.find_request_debits_by_status("PENDING".to_string()).is_empty());
+}
+
+#[test]
+fn test_find_due_request_debits () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let due_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+300));
+    let not_due_ref = ob.register_request_debit("test_account_3.testnet".to_string(), "future request debit".to_string(), U128(3), I64(1000), I64(time_now + 500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+301));
+    ob.approve_request_debits(vec![due_ref, not_due_ref], true, U64(env::block_timestamp()+302));
+
+    let due_debits = ob.find_due_request_debits();
+
+    assert_eq!(due_debits.len(), 1);
+    assert_eq!(due_debits[0].reference, u64::from(due_ref));
+}
+
+#[test]
+fn test_process_due_request_debits () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+303));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+304));
+    ob.set_keeper_reward(U128(1), U128(1), None);
+
+    let payments = ob.process_due_request_debits(U64(10));
+
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].payment_type, "REQUEST_DEBIT");
+    assert_eq!(payments[0].payee, "test_account_2.testnet");
+    assert_eq!(payments[1].payment_type, "KEEPER_REWARD");
+    assert_eq!(payments[1].payee, "robert.testnet");
+    assert_eq!(u128::from(ob.view_balance()), 16);
+    assert_eq!(ob.view_keeper_reward(), (U128(1), U128(0)));
+    assert_eq!(ob.find_request_debit(rd_ref).last_paid, time_now);
+
+    // settled request debits are not due again until the next interval
+    assert!(ob.find_due_request_debits().is_empty());
+    assert!(ob.process_due_request_debits(U64(10)).is_empty());
+}
+
+#[test]
+fn test_process_due_request_debits_keeper_reward_above_minimum_reserve () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2647));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+2648));
+    ob.set_keeper_reward(U128(1), U128(1), None);
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 17, maximum_balance : 0, warning_level : 0 }, U64(env::block_timestamp()+2649));
+
+    let payments = ob.process_due_request_debits(U64(10));
+
+    // the request debit is settled but the reward would take the bank below its reserve 
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].payment_type, "REQUEST_DEBIT");
+    assert_eq!(u128::from(ob.view_balance()), 17);
+    assert_eq!(ob.view_keeper_reward(), (U128(1), U128(1)));
+}
+
+#[test]
+fn test_process_due_request_debits_outflows_paused () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2650));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+2651));
+    ob.outflows_paused = true;
+
+    assert!(ob.process_due_request_debits(U64(10)).is_empty());
+    assert_eq!(u128::from(ob.view_balance()), 20);
+    assert_eq!(ob.find_due_request_debits().len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "PAY OUT INTERVAL NOT REACHED")]
+fn test_request_debit () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+305));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+306));
+
+    let payment = ob.request_debit(rd_ref, U64(env::block_timestamp()+307));
+
+    assert_eq!(payment.amount, 3);
+    assert_eq!(u128::from(ob.view_balance()), 17);
+
+    // a second claim within the interval is refused
+    ob.request_debit(rd_ref, U64(env::block_timestamp()+308));
+}
+
+#[test]
+fn test_create_scheduled_payout () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let first_payment_date = I64(env::block_timestamp() as i64 + 1000);
+    let sp_ref = ob.create_scheduled_payout("contractor.testnet".to_string(), "monthly retainer".to_string(), U128(5), "MONTHLY".to_string(), first_payment_date, I64(0), None, U64(env::block_timestamp()+400));
+
+    let sp = ob.find_scheduled_payout(sp_ref);
+
+    assert_eq!(sp.payee, "contractor.testnet");
+    assert_eq!(sp.status, "ACTIVE");
+    assert_eq!(sp.next_payment_date, i64::from(first_payment_date));
+    assert_eq!(ob.find_scheduled_payouts_by_status("ACTIVE".to_string()).len(), 1);
+
+    // the retainer shows up in the forecast once it falls due
+    let forecast = ob.view_liquidity_forecast(I64(24*60*60*1000), "DAILY".to_string());
+    assert_eq!(forecast.total_obligations, 5);
+}
+
+#[test]
+#[should_panic (expected = "UNKNOWN PAYOUT FREQUENCY")]
+fn test_create_scheduled_payout_unknown_frequency () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_scheduled_payout("contractor.testnet".to_string(), "retainer".to_string(), U128(5), "FORTNIGHTLY".to_string(), I64(env::block_timestamp() as i64), I64(0), None, U64(env::block_timestamp()+401));
+}
+
+#[test]
+#[should_panic (expected = "INVALID SCHEDULE. FIRST PAYMENT DATE")]
+fn test_create_scheduled_payout_invalid_first_payment_date () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_scheduled_payout("contractor.testnet".to_string(), "retainer".to_string(), U128(5), "MONTHLY".to_string(), I64(i64::MAX), I64(0), None, U64(env::block_timestamp()+2600));
+}
+
+#[test]
+#[should_panic (expected = "INVALID SCHEDULE. END DATE")]
+fn test_create_scheduled_payout_invalid_end_date () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_scheduled_payout("contractor.testnet".to_string(), "retainer".to_string(), U128(5), "MONTHLY".to_string(), I64(env::block_timestamp() as i64), I64(i64::MAX), None, U64(env::block_timestamp()+2601));
+}
+
+#[test]
+fn test_cancel_scheduled_payout () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let sp_ref = ob.create_scheduled_payout("contractor.testnet".to_string(), "monthly retainer".to_string(), U128(5), "MONTHLY".to_string(), I64(env::block_timestamp() as i64), I64(0), None, U64(env::block_timestamp()+402));
+
+    ob.cancel_scheduled_payout(sp_ref, U64(env::block_timestamp()+403));
+
+    assert_eq!(ob.find_scheduled_payout(sp_ref).status, "CANCELLED");
+    assert!(ob.find_scheduled_payouts_by_status("ACTIVE".to_string()).is_empty());
+}
+
+#[test]
+fn test_process_due_scheduled_payouts () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let once_ref = ob.create_scheduled_payout("supplier.testnet".to_string(), "one off".to_string(), U128(4), "ONCE".to_string(), I64(time_now - 1000), I64(0), None, U64(env::block_timestamp()+404));
+    let monthly_ref = ob.create_scheduled_payout("contractor.testnet".to_string(), "monthly retainer".to_string(), U128(5), "MONTHLY".to_string(), I64(time_now - 2000), I64(0), None, U64(env::block_timestamp()+405));
+    ob.create_scheduled_payout("contractor.testnet".to_string(), "not yet due".to_string(), U128(5), "WEEKLY".to_string(), I64(time_now + 2000), I64(0), None, U64(env::block_timestamp()+406));
+
+    let payments = ob.process_due_scheduled_payouts(U64(10));
+
+    assert_eq!(payments.len(), 2);
+    // the oldest payout is paid first
+    assert_eq!(payments[0].linked_reference, u64::from(monthly_ref));
+    assert_eq!(payments[0].payment_type, "STANDING_ORDER");
+    assert_eq!(payments[1].linked_reference, u64::from(once_ref));
+    assert_eq!(ob.find_payment(U64(payments[1].reference)).linked_reference, u64::from(once_ref));
+    assert_eq!(u128::from(ob.view_balance()), 11);
+
+    assert_eq!(ob.find_scheduled_payout(once_ref).status, "COMPLETED");
+    let monthly = ob.find_scheduled_payout(monthly_ref);
+    assert_eq!(monthly.payments_made, 1);
+    assert_eq!(monthly.next_payment_date, monthly.get_payment_date(1));
+
+    assert!(ob.process_due_scheduled_payouts(U64(10)).is_empty());
+}
+
+#[test]
+fn test_get_payment_date () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+
+    // 2023-01-31T09:00:00Z
+    let first_payment_date = 1675155600000;
+    let sp = super::ob_io::ScheduledPayout::create_scheduled_payout("contractor.testnet".to_string(), 5, "retainer".to_string(), "MONTHLY".to_string(), first_payment_date, 0, "robert.testnet".to_string());
+
+    // 2023-02-28T09:00:00Z then back to 2023-03-31T09:00:00Z
+    assert_eq!(sp.get_payment_date(1), 1677574800000);
+    assert_eq!(sp.get_payment_date(2), 1680253200000);
+}
+
+#[test]
+fn test_create_and_register_payment_sequence () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let first = ob.create_and_register_payment("test_payee.testnet".to_string(), "test_payer.testnet".to_string(), "test_signer.testnet".to_string(), 1, "test payment".to_string(), "TEST".to_string(), "TEST".to_string());
+    let second = ob.create_and_register_payment("test_payee.testnet".to_string(), "test_payer.testnet".to_string(), "test_signer.testnet".to_string(), 1, "test payment".to_string(), "TEST".to_string(), "TEST".to_string());
+
+    // identical payments in the same block are kept apart
+    assert_ne!(first.reference, second.reference);
+    assert_eq!(second.sequence, first.sequence + 1);
+    assert_eq!(ob.find_payment(U64(first.reference)), first);
+    assert_eq!(ob.find_payment(U64(second.reference)), second);
+}
+
+#[test]
+fn test_create_payroll_run () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3, 3, 4].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+500));
+
+    let pr = ob.find_payroll_run(pr_ref);
+
+    assert_eq!(pr.status, "DRAFT");
+    assert_eq!(pr.lines.len(), 3);
+    assert_eq!(pr.lines[2].line_number, 3);
+    assert_eq!(pr.total, 10);
+    assert_eq!(ob.find_payroll_runs_by_status("DRAFT".to_string()).len(), 1);
+}
+
+#[test]
+fn test_approve_payroll_run () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+501));
+    ob.approve_payroll_run(pr_ref, U64(env::block_timestamp()+502));
+
+    let pr = ob.find_payroll_run(pr_ref);
+
+    assert_eq!(pr.status, "APPROVED");
+    assert_eq!(pr.approved_by, "robert.testnet");
+}
+
+#[test]
+fn test_cancel_payroll_run () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+503));
+    ob.cancel_payroll_run(pr_ref, U64(env::block_timestamp()+504));
+
+    assert_eq!(ob.find_payroll_run(pr_ref).status, "CANCELLED");
+}
+
+#[test]
+fn test_execute_payroll_run () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3, 3, 4].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+505));
+    ob.approve_payroll_run(pr_ref, U64(env::block_timestamp()+506));
+
+    // executed in chunks
+    let pr = ob.execute_payroll_run(pr_ref, U64(2), U64(env::block_timestamp()+507));
+    assert_eq!(pr.status, "APPROVED");
+    assert_eq!(pr.lines[1].status, "PAID");
+    assert_eq!(pr.lines[2].status, "PENDING");
+
+    let pr = ob.execute_payroll_run(pr_ref, U64(2), U64(env::block_timestamp()+508));
+    assert_eq!(pr.status, "EXECUTED");
+    assert_eq!(pr.paid_total, 10);
+    assert_eq!(u128::from(ob.view_balance()), 10);
+
+    // the identical lines are separate payments, both linked to the run
+    assert_ne!(pr.lines[0].payment_reference, pr.lines[1].payment_reference);
+    let payment = ob.find_payment(U64(pr.lines[1].payment_reference));
+    assert_eq!(payment.payment_type, "PAYROLL");
+    assert_eq!(payment.linked_reference, u64::from(pr_ref));
+}
+
+#[test]
+fn test_execute_payroll_run_retries_failed_lines () {
+
+    let mut context = get_context_with_deposit(vec![], false, 10);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    // the bank account holds more than the bank balance so the transfers themselves succeed
+    context.account_balance = 100;
+    set_context_with_transfer_results(context);
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![15, 10].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+509));
+    ob.approve_payroll_run(pr_ref, U64(env::block_timestamp()+510));
+
+    let pr = ob.execute_payroll_run(pr_ref, U64(10), U64(env::block_timestamp()+511));
+    assert_eq!(pr.status, "APPROVED");
+    assert_eq!(pr.lines[0].status, "PAID");
+    assert_eq!(pr.lines[1].status, "FAILED");
+    assert!(pr.lines[1].failure_reason.starts_with("INSUFFICIENT FUNDS AVAILABLE"));
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+512));
+
+    let pr = ob.execute_payroll_run(pr_ref, U64(10), U64(env::block_timestamp()+513));
+    assert_eq!(pr.status, "EXECUTED");
+    assert_eq!(pr.lines[0].attempts, 1);
+    assert_eq!(pr.lines[1].attempts, 2);
+    assert_eq!(pr.lines[1].failure_reason, "");
+}
+
+#[test]
+#[should_panic (expected = "INVALID STATUS FOR ACTION")]
+fn test_execute_payroll_run_requires_approval () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+514));
+    ob.execute_payroll_run(pr_ref, U64(10), U64(env::block_timestamp()+515));
+}
+
+#[test]
+#[should_panic (expected = "ZERO AMOUNT LINE FOR employee.testnet")]
+fn test_create_payroll_run_zero_amount_line () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_payroll_run("march payroll".to_string(), vec![3, 0].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+2652));
+}
+
+#[test]
+fn test_execute_payroll_run_above_minimum_reserve () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 14, maximum_balance : 0, warning_level : 0 }, U64(env::block_timestamp()+2653));
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3, 4].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), None, U64(env::block_timestamp()+2654));
+    ob.approve_payroll_run(pr_ref, U64(env::block_timestamp()+2655));
+
+    // the second line would take the bank below its reserve 
+    let pr = ob.execute_payroll_run(pr_ref, U64(10), U64(env::block_timestamp()+2656));
+    assert_eq!(pr.status, "APPROVED");
+    assert_eq!(pr.lines[0].status, "PAID");
+    assert_eq!(pr.lines[1].status, "FAILED");
+    assert!(pr.lines[1].failure_reason.starts_with("MINIMUM RESERVE BREACHED"));
+    assert_eq!(u128::from(ob.view_balance()), 17);
+}
+
+#[test]
+fn test_create_vesting_grant () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+600));
+
+    let vg = ob.find_vesting_grant(vg_ref);
+
+    assert_eq!(vg.status, "ACTIVE");
+    assert_eq!(vg.total, 10);
+    // the grant is set aside from the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 10);
+    assert_eq!(ob.find_vesting_grants_by_beneficiary("robert.testnet".to_string()).len(), 1);
+    assert_eq!(ob.view_vested_amount(vg_ref), (U128(5), U128(5)));
+}
+
+#[test]
+#[should_panic (expected = "UNKNOWN RELEASE TYPE")]
+fn test_create_vesting_grant_unknown_release_type () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "EXPONENTIAL".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+601));
+}
+
+#[test]
+fn test_claim_vested () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+602));
+
+    let payment = ob.claim_vested(vg_ref, U64(env::block_timestamp()+603));
+
+    assert_eq!(payment.amount, 5);
+    assert_eq!(payment.payment_type, "VESTING");
+    assert_eq!(payment.linked_reference, u64::from(vg_ref));
+    assert_eq!(ob.find_vesting_grant(vg_ref).claimed, 5);
+    assert_eq!(ob.view_vested_amount(vg_ref), (U128(5), U128(0)));
+    // claims come out of the grant not the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 10);
+}
+
+#[test]
+#[should_panic (expected = "IS NOT THE BENEFICIARY")]
+fn test_claim_vested_by_other_account () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "partner.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+604));
+
+    ob.claim_vested(vg_ref, U64(env::block_timestamp()+605));
+}
+
+#[test]
+fn test_revoke_vesting_grant () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+606));
+
+    let returned = ob.revoke_vesting_grant(vg_ref, U64(env::block_timestamp()+607));
+
+    assert_eq!(returned, U128(5));
+    assert_eq!(u128::from(ob.view_balance()), 15);
+
+    let vg = ob.find_vesting_grant(vg_ref);
+    assert_eq!(vg.status, "REVOKED");
+    assert_eq!(vg.total, 5);
+
+    // what had vested remains claimable
+    assert_eq!(ob.claim_vested(vg_ref, U64(env::block_timestamp()+608)).amount, 5);
+}
+
+#[test]
+#[should_panic (expected = "INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: 10 AVAILABLE AMOUNT: 4")]
+fn test_create_vesting_grant_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("payroll".to_string(), "payroll".to_string(), U64(env::block_timestamp()+2671));
+    ob.transfer_between_pots("general".to_string(), "payroll".to_string(), U128(4), "grants".to_string(), U64(env::block_timestamp()+2672));
+
+    // the grant can only draw on the payroll pot even though the bank holds enough 
+    ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, Some("payroll".to_string()), U64(env::block_timestamp()+2673));
+}
+
+#[test]
+#[should_panic (expected = "IS NOT REVOCABLE")]
+fn test_revoke_vesting_grant_not_revocable () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : false }, None, U64(env::block_timestamp()+609));
+
+    ob.revoke_vesting_grant(vg_ref, U64(env::block_timestamp()+610));
+}
+
+#[test]
+fn test_get_vested_amount () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+
+    let time_now = env::block_timestamp() as i64;
+    let mut request = super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "STEPPED".to_string(), step_interval : 4000, revocable : true };
+    request.cliff_date = time_now - 1000;
+    let vg = super::ob_io::VestingGrant::create_vesting_grant(request, "robert.testnet".to_string());
+
+    // three steps of 4000 millis cover the ten second grant
+    assert_eq!(vg.get_vested_amount(time_now - 2000), 0);
+    assert_eq!(vg.get_vested_amount(time_now), 3);
+    assert_eq!(vg.get_vested_amount(time_now + 3000), 6);
+    assert_eq!(vg.get_vested_amount(time_now + 5000), 10);
+}
+
+#[test]
+fn test_get_vested_amount_large_total () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+
+    let time_now = env::block_timestamp() as i64;
+    // a million NEAR in yocto vesting over one year
+    let mut request = super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true };
+    request.total = 1_000_000_000_000_000_000_000_000_000_000;
+    request.start_date = time_now - 182 * 86_400_000;
+    request.cliff_date = request.start_date;
+    request.end_date = time_now + 183 * 86_400_000;
+    let vg = super::ob_io::VestingGrant::create_vesting_grant(request.clone(), "robert.testnet".to_string());
+
+    assert_eq!(vg.get_vested_amount(time_now), 498_630_136_986_301_369_863_013_698_630);
+    assert_eq!(vg.get_vested_amount(request.end_date - 1), 999_999_999_968_290_208_016_235_413_495);
+
+    request.release_type = "STEPPED".to_string();
+    request.step_interval = 30 * 86_400_000;
+    let vg = super::ob_io::VestingGrant::create_vesting_grant(request, "robert.testnet".to_string());
+
+    // six of thirteen steps have been released
+    assert_eq!(vg.get_vested_amount(time_now), 461_538_461_538_461_538_461_538_461_538);
+}
+
+#[test]
+fn test_create_payment_stream () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("robert.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+700));
+
+    let ps = ob.find_payment_stream(ps_ref);
+
+    assert_eq!(ps.status, "ACTIVE");
+    assert_eq!(ps.deposit, 10);
+    // the deposit is set aside from the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 10);
+    assert_eq!(ob.find_payment_streams_by_recipient("robert.testnet".to_string()).len(), 1);
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(5), U128(0)));
+}
+
+#[test]
+#[should_panic (expected = "INVALID STREAM TERMS")]
+fn test_create_payment_stream_invalid_terms () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    ob.create_payment_stream("robert.testnet".to_string(), U128(1), I64(time_now + 5000), I64(time_now), None, U64(env::block_timestamp()+701));
+}
+
+#[test]
+fn test_withdraw_from_stream () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("robert.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+702));
+
+    let payment = ob.withdraw_from_stream(ps_ref, U64(env::block_timestamp()+703));
+
+    assert_eq!(payment.amount, 5);
+    assert_eq!(payment.payment_type, "STREAM");
+    assert_eq!(payment.linked_reference, u64::from(ps_ref));
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(5), U128(5)));
+    // withdrawals come out of the stream not the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 10);
+}
+
+#[test]
+#[should_panic (expected = "IS NOT THE RECIPIENT")]
+fn test_withdraw_from_stream_by_other_account () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("partner.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+704));
+
+    ob.withdraw_from_stream(ps_ref, U64(env::block_timestamp()+705));
+}
+
+#[test]
+fn test_pause_and_resume_payment_stream () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("robert.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+706));
+
+    ob.pause_payment_stream(ps_ref, U64(env::block_timestamp()+707));
+    assert_eq!(ob.find_payment_stream(ps_ref).status, "PAUSED");
+
+    // nothing accrues while paused
+    context.block_timestamp += 2000;
+    testing_env!(context.clone());
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(5), U128(0)));
+
+    ob.resume_payment_stream(ps_ref, U64(env::block_timestamp()+708));
+    context.block_timestamp += 2000;
+    testing_env!(context.clone());
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(7), U128(0)));
+
+    // the stop time is put back by the two seconds paused so the whole deposit still accrues
+    context.block_timestamp += 2000;
+    testing_env!(context.clone());
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(9), U128(0)));
+
+    context.block_timestamp += 1000;
+    testing_env!(context);
+    assert_eq!(ob.view_payment_stream_balance(ps_ref), (U128(10), U128(0)));
+}
+
+#[test]
+#[should_panic (expected = "INVALID STREAM TERMS. DEPOSIT FOR RATE PER SECOND")]
+fn test_create_payment_stream_deposit_out_of_range () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    ob.create_payment_stream("robert.testnet".to_string(), U128(u128::MAX / 1000), I64(time_now), I64(time_now + 1_000_000), None, U64(env::block_timestamp()+2602));
+}
+
+#[test]
+fn test_cancel_payment_stream () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("robert.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+709));
+
+    let returned = ob.cancel_payment_stream(ps_ref, U64(env::block_timestamp()+710));
+
+    assert_eq!(returned, U128(5));
+    assert_eq!(u128::from(ob.view_balance()), 15);
+
+    let ps = ob.find_payment_stream(ps_ref);
+    assert_eq!(ps.status, "CANCELLED");
+    assert_eq!(ps.deposit, 5);
+
+    // what had accrued remains withdrawable
+    assert_eq!(ob.withdraw_from_stream(ps_ref, U64(env::block_timestamp()+711)).amount, 5);
+}
+
+#[test]
+fn test_get_accrued_amount () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+
+    let time_now = env::block_timestamp() as i64;
+    let ps = super::ob_io::PaymentStream::create_payment_stream("robert.testnet".to_string(), 2, time_now, time_now + 10000, "robert.testnet".to_string());
+
+    assert_eq!(ps.deposit, 20);
+    assert_eq!(ps.get_accrued_amount(time_now - 1000), 0);
+    assert_eq!(ps.get_accrued_amount(time_now + 2500), 5);
+    // accrual stops at the stop time
+    assert_eq!(ps.get_accrued_amount(time_now + 20000), 20);
+}
+
+fn get_escrow_request(approvers : Vec<&str>, approvals_required : u64, release_operation : &str, timeout_action : &str) -> super::EscrowRequest {
+    super::EscrowRequest {
+        payee : "partner.testnet".to_string(),
+        amount : 5,
+        description : "delivery escrow".to_string(),
+        expiry : env::block_timestamp() as i64 + 5000,
+        approvers : approvers.iter().map(|a| a.to_string()).collect(),
+        approvals_required,
+        release_operation : release_operation.to_string(),
+        timeout_action : timeout_action.to_string(),
+    }
+}
+
+#[test]
+fn test_create_escrow () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+800));
+
+    let es = ob.find_escrow(es_ref);
+
+    assert_eq!(es.status, "HELD");
+    assert_eq!(es.funding, "BANK");
+    // the escrowed amount is not spendable
+    assert_eq!(u128::from(ob.view_balance()), 15);
+    assert_eq!(u128::from(ob.view_escrow_balance()), 5);
+    assert_eq!(ob.find_escrows_by_status("HELD".to_string()).len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "NO RELEASE CONDITION")]
+fn test_create_escrow_without_release_condition () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_escrow(get_escrow_request(vec![], 0, "", "REFUND"), None, U64(env::block_timestamp()+801));
+}
+
+#[test]
+fn test_create_escrow_from_pay_in () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    // the default bank is funded with a pay in, the escrow attaches its own amount
+    testing_env!(get_context_with_deposit(vec![], false, 5));
+
+    let es_ref = ob.create_escrow_from_pay_in(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), U64(env::block_timestamp()+802));
+
+    let es = ob.find_escrow(es_ref);
+
+    assert_eq!(es.funding, "PAY_IN");
+    assert_eq!(es.payer, "robert.testnet");
+    assert_eq!(ob.find_payment(U64(es.funding_payment_reference)).linked_reference, es.reference);
+    // the pay in is held in escrow not added to the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 20);
+    assert_eq!(u128::from(ob.view_escrow_balance()), 5);
+}
+
+#[test]
+fn test_approve_escrow () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["robert.testnet", "jane.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+803));
+
+    let es = ob.approve_escrow(es_ref, U64(env::block_timestamp()+804));
+
+    // one approval of two named approvers is enough to release
+    assert_eq!(es.status, "RELEASED");
+    assert_eq!(es.approvals, vec!["robert.testnet".to_string()]);
+
+    let payment = ob.find_payment(U64(es.settlement_payment_reference));
+    assert_eq!(payment.payee, "partner.testnet");
+    assert_eq!(payment.payment_type, "ESCROW_RELEASE");
+    assert_eq!(u128::from(ob.view_escrow_balance()), 0);
+    assert_eq!(u128::from(ob.view_balance()), 15);
+}
+
+#[test]
+#[should_panic (expected = "IS NOT AN APPROVER")]
+fn test_approve_escrow_by_other_account () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["jane.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+805));
+
+    ob.approve_escrow(es_ref, U64(env::block_timestamp()+806));
+}
+
+#[test]
+fn test_release_escrow () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec![], 0, "release_delivery_escrow", "REFUND"), None, U64(env::block_timestamp()+807));
+
+    let payment = ob.release_escrow(es_ref, U64(env::block_timestamp()+808));
+
+    assert_eq!(payment.amount, 5);
+    assert_eq!(payment.linked_reference, u64::from(es_ref));
+    assert_eq!(ob.find_escrow(es_ref).status, "RELEASED");
+}
+
+#[test]
+#[should_panic (expected = "RELEASE CONDITIONS NOT MET")]
+fn test_release_escrow_before_timeout () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    // released only on approval or timeout
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["jane.testnet"], 1, "", "RELEASE"), None, U64(env::block_timestamp()+809));
+
+    ob.release_escrow(es_ref, U64(env::block_timestamp()+810));
+}
+
+#[test]
+fn test_release_escrow_on_timeout () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["jane.testnet"], 1, "", "RELEASE"), None, U64(env::block_timestamp()+811));
+
+    context.block_timestamp += 6000;
+    set_context_with_transfer_results(context);
+
+    ob.release_escrow(es_ref, U64(env::block_timestamp()+812));
+    assert_eq!(ob.find_escrow(es_ref).status, "RELEASED");
+}
+
+#[test]
+fn test_refund_escrow () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["jane.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+813));
+
+    let es = ob.refund_escrow(es_ref, U64(env::block_timestamp()+814));
+
+    // bank funded escrows go back to the bank balance
+    assert_eq!(es.status, "REFUNDED");
+    assert_eq!(u128::from(ob.view_balance()), 20);
+    assert_eq!(u128::from(ob.view_escrow_balance()), 0);
+}
+
+#[test]
+fn test_refund_escrow_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("marketing".to_string(), "marketing".to_string(), U64(env::block_timestamp()+2674));
+    ob.transfer_between_pots("general".to_string(), "marketing".to_string(), U128(8), "campaign budget".to_string(), U64(env::block_timestamp()+2675));
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["jane.testnet"], 1, "", "REFUND"), Some("marketing".to_string()), U64(env::block_timestamp()+2676));
+    assert_eq!(ob.find_escrow(es_ref).pot, "marketing");
+    assert_eq!(u128::from(ob.view_pot_balance("marketing".to_string())), 3);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 12);
+
+    ob.refund_escrow(es_ref, U64(env::block_timestamp()+2677));
+
+    // the refund goes back to the pot the escrow was drawn from 
+    assert_eq!(u128::from(ob.view_pot_balance("marketing".to_string())), 8);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 12);
+    assert_eq!(u128::from(ob.view_balance()), 20);
+}
+
+#[test]
+fn test_create_escrow_from_pay_in_twice_in_one_block () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    testing_env!(get_context_with_deposit(vec![], false, 5));
+
+    // identical escrows created in the same block are told apart by the record sequence
+    let first_ref = ob.create_escrow_from_pay_in(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), U64(env::block_timestamp()+2603));
+    let second_ref = ob.create_escrow_from_pay_in(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), U64(env::block_timestamp()+2604));
+
+    assert_ne!(first_ref, second_ref);
+    assert_eq!(ob.find_escrow(first_ref).amount, 5);
+    assert_eq!(ob.find_escrow(second_ref).amount, 5);
+    assert_eq!(u128::from(ob.view_escrow_balance()), 10);
+}
+
+#[test]
+fn test_refund_escrow_from_pay_in () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    set_context_with_transfer_results(get_context_with_deposit(vec![], false, 5));
+
+    let es_ref = ob.create_escrow_from_pay_in(get_escrow_request(vec!["jane.testnet"], 1, "", "REFUND"), U64(env::block_timestamp()+815));
+
+    let es = ob.refund_escrow(es_ref, U64(env::block_timestamp()+816));
+
+    // pay in funded escrows go back to the payer
+    let payment = ob.find_payment(U64(es.settlement_payment_reference));
+    assert_eq!(payment.payee, "robert.testnet");
+    assert_eq!(payment.payment_type, "ESCROW_REFUND");
+    assert_eq!(u128::from(ob.view_balance()), 20);
+}
+
+#[test]
+fn test_create_supplier_contract () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+900));
+
+    let sc = ob.find_supplier_contract(sc_ref);
+
+    assert_eq!(sc.status, "ACTIVE");
+    assert_eq!(sc.total, 5);
+    assert_eq!(sc.milestones.len(), 2);
+    assert_ne!(sc.milestones[0].id, sc.milestones[1].id);
+    assert_eq!(ob.find_milestone(U64(sc.milestones[1].id)).amount, 3);
+    assert_eq!(ob.find_supplier_contracts_by_supplier("robert.testnet".to_string()).len(), 1);
+}
+
+#[test]
+fn test_accept_milestone () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+901));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    let milestone = ob.mark_milestone_delivered(milestone_id, U64(env::block_timestamp()+902));
+    assert_eq!(milestone.status, "DELIVERED");
+
+    let payment = ob.accept_milestone(milestone_id, U64(env::block_timestamp()+903));
+
+    assert_eq!(payment.amount, 2);
+    assert_eq!(payment.payment_type, "MILESTONE");
+    assert_eq!(payment.linked_reference, u64::from(milestone_id));
+    assert_eq!(ob.find_milestone(milestone_id).status, "PAID");
+    assert_eq!(u128::from(ob.view_balance()), 18);
+
+    let sc = ob.find_supplier_contract(sc_ref);
+    assert_eq!(sc.paid_total, 2);
+    assert_eq!(sc.status, "ACTIVE");
+}
+
+#[test]
+fn test_accept_milestone_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("operations".to_string(), "operations".to_string(), U64(env::block_timestamp()+2678));
+    ob.transfer_between_pots("general".to_string(), "operations".to_string(), U128(5), "build budget".to_string(), U64(env::block_timestamp()+2679));
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![super::MilestoneRequest { amount : 2, description : "milestone 1".to_string(), due_date : env::block_timestamp() as i64 + super::WEEK_MILLIS }], Some("operations".to_string()), U64(env::block_timestamp()+2680));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    ob.mark_milestone_delivered(milestone_id, U64(env::block_timestamp()+2681));
+    ob.accept_milestone(milestone_id, U64(env::block_timestamp()+2682));
+
+    // the milestone is paid from the pot of the contract 
+    assert_eq!(u128::from(ob.view_pot_balance("operations".to_string())), 3);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 15);
+}
+
+#[test]
+#[should_panic (expected = "REQUIRED STATUS : DELIVERED")]
+fn test_accept_milestone_not_delivered () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+904));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    ob.accept_milestone(milestone_id, U64(env::block_timestamp()+905));
+}
+
+#[test]
+#[should_panic (expected = "IS NOT THE SUPPLIER")]
+fn test_mark_milestone_delivered_by_other_account () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("partner.testnet".to_string(), "website build".to_string(), vec![2].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+906));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    ob.mark_milestone_delivered(milestone_id, U64(env::block_timestamp()+907));
+}
+
+#[test]
+fn test_reject_milestone () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+908));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    ob.mark_milestone_delivered(milestone_id, U64(env::block_timestamp()+909));
+    let milestone = ob.reject_milestone(milestone_id, U64(env::block_timestamp()+910));
+
+    assert_eq!(milestone.status, "PENDING");
+    assert_eq!(milestone.delivered_date, 0);
+}
+
+#[test]
+fn test_complete_supplier_contract () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+911));
+
+    let milestone_ids : Vec<u64> = ob.find_supplier_contract(sc_ref).milestones.iter().map(|m| m.id).collect();
+    for (i, milestone_id) in milestone_ids.iter().enumerate() {
+        ob.mark_milestone_delivered(U64(*milestone_id), U64(env::block_timestamp()+912+(i as u64)*2));
+        ob.accept_milestone(U64(*milestone_id), U64(env::block_timestamp()+913+(i as u64)*2));
+    }
+
+    let sc = ob.find_supplier_contract(sc_ref);
+    assert_eq!(sc.status, "COMPLETED");
+    assert_eq!(sc.paid_total, 5);
+}
+
+#[test]
+fn test_cancel_supplier_contract () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("robert.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+920));
+    let milestone_id = U64(ob.find_supplier_contract(sc_ref).milestones[0].id);
+
+    ob.mark_milestone_delivered(milestone_id, U64(env::block_timestamp()+921));
+    ob.accept_milestone(milestone_id, U64(env::block_timestamp()+922));
+
+    ob.cancel_supplier_contract(sc_ref, U64(env::block_timestamp()+923));
+
+    let sc = ob.find_supplier_contract(sc_ref);
+    assert_eq!(sc.status, "CANCELLED");
+    // paid milestones are kept
+    assert_eq!(sc.milestones[0].status, "PAID");
+    assert_eq!(sc.milestones[1].status, "CANCELLED");
+}
+
+#[test]
+fn test_refund_payment () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1000));
+
+    let refund = ob.refund_payment(U64(pay_in.reference), U128(4), "failed mint".to_string(), U64(env::block_timestamp()+1001));
+
+    assert_eq!(refund.payee, "robert.testnet");
+    assert_eq!(refund.amount, 4);
+    assert_eq!(refund.payment_type, "REFUND");
+    assert_eq!(refund.description, "failed mint");
+    assert_eq!(refund.linked_reference, pay_in.reference);
+    assert_eq!(ob.find_payment(U64(pay_in.reference)).refunded_amount, 4);
+    assert_eq!(u128::from(ob.view_balance()), 26);
+
+    // partial refunds add up to the original amount
+    ob.refund_payment(U64(pay_in.reference), U128(6), "failed mint".to_string(), U64(env::block_timestamp()+1002));
+    assert_eq!(ob.find_payment(U64(pay_in.reference)).refunded_amount, 10);
+}
+
+#[test]
+#[should_panic (expected = "EXCEEDS REFUNDABLE AMOUNT")]
+fn test_refund_payment_over_original_amount () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1003));
+
+    ob.refund_payment(U64(pay_in.reference), U128(6), "failed mint".to_string(), U64(env::block_timestamp()+1004));
+    ob.refund_payment(U64(pay_in.reference), U128(5), "failed mint".to_string(), U64(env::block_timestamp()+1005));
+}
+
+#[test]
+#[should_panic (expected = "REFUND AMOUNT 9 EXCEEDS REFUNDABLE AMOUNT 8")]
+fn test_refund_payment_over_net_amount () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    // a fee of 1 plus 10% is withheld from the pay in 
+    let fee_rate = super::ob_io::FeeRate { basis_points : 1000, flat : 1 };
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : fee_rate.clone(), request_debit : fee_rate.clone(), pay_out : fee_rate }, U64(env::block_timestamp()+2657));
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+2658));
+
+    ob.refund_payment(U64(pay_in.reference), U128(9), "failed mint".to_string(), U64(env::block_timestamp()+2659));
+}
+
+#[test]
+#[should_panic (expected = "REFUND AMOUNT 8 EXCEEDS REFUNDABLE AMOUNT 7")]
+fn test_refund_payment_after_revenue_split () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    // 20% and 10% of the pay in are split off to the partners 
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "ACCRUED".to_string() }, U64(env::block_timestamp()+2660));
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), Some("mint".to_string()), None, None, U64(env::block_timestamp()+2661));
+
+    ob.refund_payment(U64(pay_in.reference), U128(8), "failed mint".to_string(), U64(env::block_timestamp()+2662));
+}
+
+#[test]
+fn test_refund_payment_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("marketing".to_string(), "marketing".to_string(), U64(env::block_timestamp()+2663));
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, Some("marketing".to_string()), None, U64(env::block_timestamp()+2664));
+    assert_eq!(pay_in.pot, "marketing");
+
+    ob.refund_payment(U64(pay_in.reference), U128(4), "failed mint".to_string(), U64(env::block_timestamp()+2665));
+
+    // the refund comes out of the pot the pay in went to 
+    assert_eq!(u128::from(ob.view_pot_balance("marketing".to_string())), 6);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 20);
+    assert_eq!(u128::from(ob.view_balance()), 26);
+}
+
+#[test]
+#[should_panic (expected = "MINIMUM RESERVE BREACHED. REQUIRED AMOUNT: 4 BALANCE: 30 MINIMUM RESERVE: 27")]
+fn test_refund_payment_below_minimum_reserve () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+2666));
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 27, maximum_balance : 0, warning_level : 0 }, U64(env::block_timestamp()+2667));
+
+    ob.refund_payment(U64(pay_in.reference), U128(4), "failed mint".to_string(), U64(env::block_timestamp()+2668));
+}
+
+#[test]
+#[should_panic (expected = "IS NOT A PAY IN")]
+fn test_refund_payment_not_pay_in () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let pay_out = ob.pay_out("supplies".to_string(), U128(2), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1006));
+
+    ob.refund_payment(U64(pay_out.reference), U128(1), "wrong payment".to_string(), U64(env::block_timestamp()+1007));
+}
+
+#[test]
+fn test_create_invoice () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : Some("robert.testnet".to_string()), amount : 15, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+1100));
+
+    let invoice = ob.find_invoice(inv_ref);
+
+    assert_eq!(invoice.status, "OPEN");
+    assert_eq!(invoice.external_reference, "INV-0001");
+    assert_eq!(ob.find_open_invoices().len(), 1);
+    assert_eq!(ob.find_overdue_invoices().len(), 0);
+}
+
+#[test]
+fn test_pay_invoice_in_part () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : None, amount : 15, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+1101));
+
+    let payment = ob.pay_invoice(inv_ref, U64(env::block_timestamp()+1102));
+
+    assert_eq!(payment.amount, 10);
+    assert_eq!(payment.payment_type, "PAY_IN");
+    assert_eq!(payment.linked_reference, u64::from(inv_ref));
+
+    let invoice = ob.find_invoice(inv_ref);
+    assert_eq!(invoice.status, "PARTIALLY_PAID");
+    assert_eq!(invoice.get_outstanding_amount(), 5);
+    assert_eq!(invoice.payment_references, vec![payment.reference]);
+    assert_eq!(u128::from(ob.view_balance()), 30);
+    assert_eq!(ob.find_open_invoices().len(), 1);
+}
+
+#[test]
+fn test_pay_invoice_overpayment_refunded () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : None, amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+1103));
+
+    let payment = ob.pay_invoice(inv_ref, U64(env::block_timestamp()+1104));
+
+    // the overpayment is refunded against the pay in
+    assert_eq!(payment.refunded_amount, 4);
+    assert_eq!(ob.find_invoice(inv_ref).status, "PAID");
+    assert_eq!(ob.find_invoices_by_status("PAID".to_string()).len(), 1);
+    assert_eq!(u128::from(ob.view_balance()), 26);
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(0));
+}
+
+#[test]
+fn test_pay_invoice_overpayment_credited () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : None, amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "CREDIT".to_string() }, U64(env::block_timestamp()+1105));
+
+    let payment = ob.pay_invoice(inv_ref, U64(env::block_timestamp()+1106));
+
+    // the overpayment is kept as credit and is not part of the bank balance
+    assert_eq!(payment.amount, 6);
+    assert_eq!(payment.refunded_amount, 0);
+    assert_eq!(ob.find_invoice(inv_ref).status, "PAID");
+    assert_eq!(u128::from(ob.view_balance()), 26);
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(4));
+
+    // the credited part is a pay in of its own
+    let credit_pay_in = ob.payments_by_reference.values().find(|p| p.payment_type == "CREDIT_PAY_IN").unwrap();
+    assert_eq!(credit_pay_in.amount, 4);
+    assert_eq!(credit_pay_in.linked_reference, u64::from(inv_ref));
+}
+
+#[test]
+#[should_panic (expected = "REFUND STOPPED. PAYMENT")]
+fn test_refund_invoice_payment () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : None, amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "CREDIT".to_string() }, U64(env::block_timestamp()+2605));
+
+    let payment = ob.pay_invoice(inv_ref, U64(env::block_timestamp()+2606));
+
+    ob.refund_payment(U64(payment.reference), U128(6), "refund invoice".to_string(), U64(env::block_timestamp()+2607));
+}
+
+#[test]
+fn test_pay_invoice_with_fee () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+2608));
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : None, amount : 15, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+2609));
+
+    let payment = ob.pay_invoice(inv_ref, U64(env::block_timestamp()+2610));
+
+    // the whole payment is applied to the invoice, the fee is kept by the bank
+    assert_eq!(payment.fee_amount, 2);
+    assert_eq!(payment.net_amount, 8);
+    assert_eq!(ob.find_invoice(inv_ref).get_outstanding_amount(), 5);
+    assert_eq!(u128::from(ob.view_balance()), 28);
+    assert_eq!(u128::from(ob.view_fee_balance()), 2);
+}
+
+#[test]
+#[should_panic (expected = "IS NOT THE PAYER")]
+fn test_pay_invoice_by_other_account () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let inv_ref = ob.create_invoice(super::InvoiceRequest { payer : Some("partner.testnet".to_string()), amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+1107));
+
+    ob.pay_invoice(inv_ref, U64(env::block_timestamp()+1108));
+}
+
+#[test]
+fn test_find_overdue_invoices () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let mut invoice = super::InvoiceRequest { payer : None, amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() };
+    invoice.due_date = env::block_timestamp() as i64 - super::DAY_MILLIS;
+    let inv_ref = ob.create_invoice(invoice, U64(env::block_timestamp()+1109));
+    ob.create_invoice(super::InvoiceRequest { payer : None, amount : 6, due_date : env::block_timestamp() as i64 + super::DAY_MILLIS, external_reference : "INV-0001".to_string(), description : "minting services".to_string(), overpayment_action : "REFUND".to_string() }, U64(env::block_timestamp()+1110));
+
+    let overdue = ob.find_overdue_invoices();
+    assert_eq!(overdue.len(), 1);
+    assert_eq!(overdue[0].reference, u64::from(inv_ref));
+}
+
+#[test]
+fn test_pay_in_credit () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1200));
+
+    assert_eq!(payment.payment_type, "CREDIT_PAY_IN");
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(10));
+    // credit is a liability and not part of the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 20);
+    assert_eq!(u128::from(ob.view_credit_balance()), 10);
+    assert_eq!(ob.find_customer_credits(), vec![("robert.testnet".to_string(), U128(10))]);
+}
+
+#[test]
+fn test_charge_credit () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1201));
+
+    let charge = ob.charge_credit("robert.testnet".to_string(), U128(3), "api usage".to_string(), U64(env::block_timestamp()+1202));
+
+    assert_eq!(charge.payment_type, "CREDIT_CHARGE");
+    assert_eq!(charge.payer, "robert.testnet");
+    assert_eq!(charge.description, "api usage");
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(7));
+    assert_eq!(u128::from(ob.view_credit_balance()), 7);
+    assert_eq!(u128::from(ob.view_balance()), 23);
+}
+
+#[test]
+#[should_panic (expected = "INSUFFICIENT CREDIT AVAILABLE")]
+fn test_charge_credit_over_credit () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1203));
+
+    ob.charge_credit("robert.testnet".to_string(), U128(11), "api usage".to_string(), U64(env::block_timestamp()+1204));
+}
+
+#[test]
+fn test_withdraw_credit () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1205));
+
+    let payment = ob.withdraw_credit(U128(10), U64(env::block_timestamp()+1206));
+
+    assert_eq!(payment.payee, "robert.testnet");
+    assert_eq!(payment.payment_type, "CREDIT_WITHDRAWAL");
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(0));
+    assert_eq!(ob.find_customer_credits().len(), 0);
+    assert_eq!(u128::from(ob.view_balance()), 20);
+}
+
+#[test]
+fn test_subscribe () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+1300));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1301));
+
+    let subscription = ob.subscribe(plan_ref, U64(env::block_timestamp()+1302));
+
+    assert_eq!(subscription.status, "ACTIVE");
+    assert_eq!(subscription.periods_paid, 1);
+    assert_eq!(subscription.paid_until, env::block_timestamp() as i64 + super::DAY_MILLIS);
+    // the first period is charged against credit
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(7));
+    assert_eq!(u128::from(ob.view_balance()), 23);
+    assert!(ob.is_subscribed("robert.testnet".to_string(), plan_ref));
+    assert!(!ob.is_subscribed("partner.testnet".to_string(), plan_ref));
+    assert_eq!(ob.find_subscriptions_by_subscriber("robert.testnet".to_string()).len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "INSUFFICIENT CREDIT AVAILABLE")]
+fn test_subscribe_without_credit () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+1303));
+
+    ob.subscribe(plan_ref, U64(env::block_timestamp()+1304));
+}
+
+#[test]
+fn test_process_due_subscriptions () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+1305));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1306));
+    let sub_ref = U64(ob.subscribe(plan_ref, U64(env::block_timestamp()+1307)).reference);
+
+    context.block_timestamp += super::DAY_MILLIS as u64;
+    testing_env!(context);
+
+    let payments = ob.process_due_subscriptions(U64(10));
+
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].payment_type, "SUBSCRIPTION");
+    assert_eq!(payments[0].payer, "robert.testnet");
+    assert_eq!(payments[0].linked_reference, u64::from(sub_ref));
+    assert_eq!(ob.find_subscription(sub_ref).periods_paid, 2);
+    assert_eq!(ob.view_customer_credit("robert.testnet".to_string()), U128(4));
+}
+
+#[test]
+fn test_process_due_subscriptions_lapse () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+1308));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1309));
+    ob.charge_credit("robert.testnet".to_string(), U128(5), "api usage".to_string(), U64(env::block_timestamp()+1310));
+    let sub_ref = U64(ob.subscribe(plan_ref, U64(env::block_timestamp()+1311)).reference);
+
+    // out of credit the subscription is kept usable through the grace period
+    context.block_timestamp += super::DAY_MILLIS as u64;
+    testing_env!(context.clone());
+    assert_eq!(ob.process_due_subscriptions(U64(10)).len(), 0);
+    assert_eq!(ob.find_subscription(sub_ref).status, "ACTIVE");
+    assert!(ob.is_subscribed("robert.testnet".to_string(), plan_ref));
+
+    context.block_timestamp += super::DAY_MILLIS as u64;
+    testing_env!(context);
+    ob.process_due_subscriptions(U64(10));
+    assert_eq!(ob.find_subscription(sub_ref).status, "LAPSED");
+    assert!(!ob.is_subscribed("robert.testnet".to_string(), plan_ref));
+}
+
+#[test]
+fn test_cancel_subscription () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+1312));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1313));
+    let sub_ref = U64(ob.subscribe(plan_ref, U64(env::block_timestamp()+1314)).reference);
+
+    ob.cancel_subscription(sub_ref, U64(env::block_timestamp()+1315));
+
+    // usable until the end of the paid period and not renewed
+    assert!(ob.is_subscribed("robert.testnet".to_string(), plan_ref));
+    context.block_timestamp += super::DAY_MILLIS as u64;
+    testing_env!(context);
+    assert_eq!(ob.process_due_subscriptions(U64(10)).len(), 0);
+    assert!(!ob.is_subscribed("robert.testnet".to_string(), plan_ref));
+}
+
+
+
+#[test]
+fn test_set_split_rule () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let first_ref = ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() }, U64(env::block_timestamp()+1400));
+    let second_ref = ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "ACCRUED".to_string() }, U64(env::block_timestamp()+1401));
+
+    // a new rule for the same match replaces the old one
+    assert_eq!(ob.find_split_rule(first_ref).status, "REPLACED");
+    let active = ob.find_active_split_rules();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].reference, u64::from(second_ref));
+}
+
+#[test]
+#[should_panic (expected = "INVALID SPLIT LEGS")]
+fn test_set_split_rule_over_allocated () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let mut split_rule = super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() };
+    split_rule.legs[0].basis_points = 9500;
+    ob.set_split_rule(split_rule, U64(env::block_timestamp()+1402));
+}
+
+#[test]
+fn test_pay_in_split_immediately () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() }, U64(env::block_timestamp()+1403));
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), Some("mint".to_string()), None, None, U64(env::block_timestamp()+1404));
+
+    let mut legs : Vec<super::Payment> = ob.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == pay_in.reference).cloned().collect();
+    legs.sort_by_key(|p| p.sequence);
+    assert_eq!(legs.len(), 2);
+    assert_eq!((legs[0].payee.as_str(), legs[0].amount), ("partner_a.testnet", 2));
+    assert_eq!((legs[1].payee.as_str(), legs[1].amount), ("partner_b.testnet", 1));
+    // the bank keeps what is not allocated
+    assert_eq!(u128::from(ob.view_balance()), 27);
+}
+
+#[test]
+fn test_pay_in_split_while_outflows_paused () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() }, U64(env::block_timestamp()+2669));
+    ob.outflows_paused = true;
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), Some("mint".to_string()), None, None, U64(env::block_timestamp()+2670));
+
+    // the legs are accrued for distribution once outflows resume 
+    assert!(ob.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == pay_in.reference).all(|p| p.status == "ACCRUED"));
+    assert_eq!(ob.find_split_accruals(), vec![("partner_a.testnet".to_string(), U128(2)), ("partner_b.testnet".to_string(), U128(1))]);
+    assert_eq!(u128::from(ob.view_balance()), 27);
+}
+
+#[test]
+fn test_pay_in_split_by_dapp () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "DAPP".to_string(), match_value : "jane.testnet".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() }, U64(env::block_timestamp()+1405));
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1406));
+
+    assert_eq!(ob.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == pay_in.reference).count(), 2);
+    assert_eq!(u128::from(ob.view_balance()), 27);
+}
+
+#[test]
+fn test_distribute_split_accruals () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "ACCRUED".to_string() }, U64(env::block_timestamp()+1407));
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), Some("mint".to_string()), None, None, U64(env::block_timestamp()+1408));
+
+    let mut legs : Vec<super::Payment> = ob.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == pay_in.reference).cloned().collect();
+    legs.sort_by_key(|p| p.sequence);
+    assert_eq!(legs[0].status, "ACCRUED");
+    assert_eq!(ob.find_split_accruals(), vec![("partner_a.testnet".to_string(), U128(2)), ("partner_b.testnet".to_string(), U128(1))]);
+    assert_eq!(u128::from(ob.view_balance()), 27);
+
+    let payments = ob.distribute_split_accruals(U64(10), U64(env::block_timestamp()+1409));
+
+    assert_eq!(payments.len(), 2);
+    assert_eq!(payments[0].payment_type, "REVENUE_SPLIT_DISTRIBUTION");
+    assert_eq!(ob.find_split_accruals().len(), 0);
+}
+
+#[test]
+fn test_remove_split_rule () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let sr_ref = ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner_a.testnet".to_string(), basis_points : 2000 }, super::ob_io::SplitLeg { payee : "partner_b.testnet".to_string(), basis_points : 1000 }], distribution : "IMMEDIATE".to_string() }, U64(env::block_timestamp()+1410));
+    ob.remove_split_rule(sr_ref, U64(env::block_timestamp()+1411));
+
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), Some("mint".to_string()), None, None, U64(env::block_timestamp()+1412));
+
+    assert_eq!(ob.payments.iter().filter(|p| p.payment_type == "REVENUE_SPLIT" && p.linked_reference == pay_in.reference).count(), 0);
+    assert_eq!(u128::from(ob.view_balance()), 30);
+}
+
+#[test]
+fn test_set_fee_schedule () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let change = ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+1500));
+
+    assert_eq!(change.previous, super::FeeSchedule::default());
+    assert_eq!(change.changed_by, "robert.testnet");
+    assert_eq!(ob.view_fee_schedule(), super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } });
+    assert_eq!(ob.find_fee_schedule_changes().len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "INVALID FEE RATE")]
+fn test_set_fee_schedule_over_one_hundred_percent () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 10_001, flat : 0 }, request_debit : super::ob_io::FeeRate { basis_points : 10_001, flat : 0 }, pay_out : super::ob_io::FeeRate { basis_points : 10_001, flat : 0 } }, U64(env::block_timestamp()+1501));
+}
+
+#[test]
+fn test_pay_in_with_fee () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+1502));
+
+    let payment = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1503));
+
+    // 10% plus 1 flat
+    assert_eq!((payment.gross_amount, payment.fee_amount, payment.net_amount), (10, 2, 8));
+    assert_eq!(ob.find_payment(U64(payment.reference)).fee_amount, 2);
+    assert_eq!(u128::from(ob.view_balance()), 28);
+    assert_eq!(u128::from(ob.view_fee_balance()), 2);
+}
+
+#[test]
+fn test_pay_out_with_fee () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+1504));
+
+    let payment = ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1505));
+
+    // the fee is deducted from what the payee receives
+    assert_eq!((payment.gross_amount, payment.fee_amount, payment.net_amount), (5, 1, 4));
+    assert_eq!(u128::from(ob.view_balance()), 15);
+    assert_eq!(u128::from(ob.view_fee_balance()), 1);
+}
+
+#[test]
+fn test_request_debit_with_fee () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 0, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 0, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 0, flat : 1 } }, U64(env::block_timestamp()+1506));
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("test_account_2.testnet".to_string(), "due request debit".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+1507));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+1508));
+
+    let payment = ob.request_debit(rd_ref, U64(env::block_timestamp()+1509));
+
+    assert_eq!((payment.gross_amount, payment.fee_amount, payment.net_amount), (3, 1, 2));
+    assert_eq!(u128::from(ob.view_balance()), 17);
+    assert_eq!(u128::from(ob.view_fee_balance()), 1);
+}
+
+#[test]
+fn test_withdraw_fees () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+1510));
+    ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1511));
+
+    let payment = ob.withdraw_fees(U128(2), "fees.testnet".to_string(), U64(env::block_timestamp()+1512));
+
+    assert_eq!(payment.payee, "fees.testnet");
+    assert_eq!(payment.payment_type, "FEE_WITHDRAWAL");
+    assert_eq!(u128::from(ob.view_fee_balance()), 0);
+}
+
+#[test]
+fn test_create_pot () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let pot = ob.create_pot("marketing".to_string(), "marketing".to_string(), U64(env::block_timestamp()+1600));
+
+    assert_eq!(pot.balance, 0);
+    assert_eq!(pot.get_operation("payout"), "marketing.payout");
+
+    // everything else is in the 'general' pot
+    let pots = ob.find_pots();
+    assert_eq!(pots.iter().map(|p| p.name.clone()).collect::<Vec<String>>(), vec!["general".to_string(), "marketing".to_string()]);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), u128::from(ob.view_balance()));
+    assert_eq!(pots[0].get_operation("payout"), "payout");
+}
+
+#[test]
+#[should_panic(expected = "POT CREATION CANCELLED. POT general ALREADY EXISTS")]
+fn test_create_duplicate_pot () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("general".to_string(), "general".to_string(), U64(env::block_timestamp()+1601));
+}
+
+#[test]
+fn test_pay_in_and_deposit_to_pot () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("payroll".to_string(), "payroll".to_string(), U64(env::block_timestamp()+1602));
+    ob.pay_in("mint".to_string(), U128(10), None, Some("payroll".to_string()), None, U64(env::block_timestamp()+1603));
+    ob.deposit("top up".to_string(), U128(10), Some("payroll".to_string()), None, U64(env::block_timestamp()+1604));
+
+    assert_eq!(u128::from(ob.view_pot_balance("payroll".to_string())), 20);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 20);
+    assert_eq!(u128::from(ob.view_balance()), 40);
+}
+
+#[test]
+fn test_pay_out_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("marketing".to_string(), "marketing".to_string(), U64(env::block_timestamp()+1605));
+    ob.transfer_between_pots("general".to_string(), "marketing".to_string(), U128(8), "campaign budget".to_string(), U64(env::block_timestamp()+1606));
+
+    ob.pay_out("adverts".to_string(), U128(5), "agency.testnet".to_string(), Some("marketing".to_string()), None, U64(env::block_timestamp()+1607));
+
+    assert_eq!(u128::from(ob.view_pot_balance("marketing".to_string())), 3);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 12);
+    assert_eq!(u128::from(ob.view_balance()), 15);
+}
+
+#[test]
+#[should_panic(expected = "INSUFFICIENT FUNDS AVAILABLE. REQUIRED AMOUNT: 5 AVAILABLE AMOUNT: 0")]
+fn test_pay_out_from_empty_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("marketing".to_string(), "marketing".to_string(), U64(env::block_timestamp()+1608));
+
+    ob.pay_out("adverts".to_string(), U128(5), "agency.testnet".to_string(), Some("marketing".to_string()), None, U64(env::block_timestamp()+1609));
+}
+
+#[test]
+fn test_execute_payroll_run_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("payroll".to_string(), "payroll".to_string(), U64(env::block_timestamp()+2611));
+    ob.transfer_between_pots("general".to_string(), "payroll".to_string(), U128(8), "salaries".to_string(), U64(env::block_timestamp()+2612));
+
+    let pr_ref = ob.create_payroll_run("march payroll".to_string(), vec![3, 3, 4].into_iter().map(|amount| super::MultiPaymentRequest { payee_account_id : "employee.testnet".to_string(), payout_amount : amount, description : "salary".to_string(), annotation : None }).collect(), Some("payroll".to_string()), U64(env::block_timestamp()+2613));
+    ob.approve_payroll_run(pr_ref, U64(env::block_timestamp()+2614));
+
+    // the lines are drawn from the payroll pot, the last line is more than is left in it
+    let pr = ob.execute_payroll_run(pr_ref, U64(3), U64(env::block_timestamp()+2615));
+    assert_eq!(pr.pot, "payroll");
+    assert_eq!(pr.paid_total, 6);
+    assert_eq!(pr.lines[2].status, "FAILED");
+    assert_eq!(u128::from(ob.view_pot_balance("payroll".to_string())), 2);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 12);
+    assert_eq!(u128::from(ob.view_balance()), 14);
+}
+
+#[test]
+fn test_withdraw_from_pot () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("savings".to_string(), "savings".to_string(), U64(env::block_timestamp()+2616));
+    ob.transfer_between_pots("general".to_string(), "savings".to_string(), U128(8), "savings".to_string(), U64(env::block_timestamp()+2617));
+
+    ob.withdraw("drawings".to_string(), U128(5), Some("savings".to_string()), None, U64(env::block_timestamp()+2618));
+
+    assert_eq!(u128::from(ob.view_pot_balance("savings".to_string())), 3);
+    assert_eq!(u128::from(ob.view_pot_balance("general".to_string())), 12);
+}
+
+#[test]
+fn test_transfer_between_pots () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_pot("reserve".to_string(), "reserve".to_string(), U64(env::block_timestamp()+1610));
+
+    let payment = ob.transfer_between_pots("general".to_string(), "reserve".to_string(), U128(20), "rainy day".to_string(), U64(env::block_timestamp()+1611));
+
+    assert_eq!(payment.payment_type, "INTERNAL_TRANSFER");
+    assert_eq!((payment.payer.as_str(), payment.payee.as_str()), ("general", "reserve"));
+
+    // moving money between pots does not change the bank balance
+    assert_eq!(u128::from(ob.view_balance()), 20);
+    let total : u128 = ob.find_pots().iter().map(|p| p.balance).sum();
+    assert_eq!(total, 20);
+    assert_eq!(u128::from(ob.view_pot_balance("reserve".to_string())), 20);
+}
+
+#[test]
+fn test_set_treasury_policy () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let policy = ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 5, maximum_balance : 100, warning_level : 10 }, U64(env::block_timestamp()+1700));
+
+    assert_eq!(policy, super::TreasuryPolicy { minimum_reserve : 5, maximum_balance : 100, warning_level : 10 });
+    assert_eq!(ob.view_treasury_policy(), policy);
+}
+
+#[test]
+#[should_panic(expected = "INVALID TREASURY POLICY. MAXIMUM BALANCE 5 NOT ABOVE MINIMUM RESERVE 5")]
+fn test_set_invalid_treasury_policy () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 5, maximum_balance : 5, warning_level : 0 }, U64(env::block_timestamp()+1701));
+}
+
+#[test]
+#[should_panic(expected = "MINIMUM RESERVE BREACHED. REQUIRED AMOUNT: 5 BALANCE: 20 MINIMUM RESERVE: 16")]
+fn test_pay_out_below_minimum_reserve () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 16, maximum_balance : 0, warning_level : 0 }, U64(env::block_timestamp()+1702));
+
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1703));
+}
+
+#[test]
+fn test_withdraw_down_to_minimum_reserve () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 16, maximum_balance : 0, warning_level : 0 }, U64(env::block_timestamp()+1704));
+
+    ob.withdraw("drawings".to_string(), U128(4), None, None, U64(env::block_timestamp()+1705));
+
+    assert_eq!(u128::from(ob.view_balance()), 16);
+}
+
+#[test]
+fn test_pay_in_over_maximum_balance_is_swept () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 0, maximum_balance : 25, warning_level : 0 }, U64(env::block_timestamp()+1706));
+
+    ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+1707));
+
+    assert_eq!(u128::from(ob.view_balance()), 25);
+    let sweep = ob.payments.iter().find(|p| p.payment_type == "SWEEP").unwrap();
+    assert_eq!(sweep.payee, "testnominee.testnet");
+    assert_eq!(sweep.amount, 5);
+}
+
+#[test]
+fn test_low_balance_event () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_treasury_policy(super::TreasuryPolicy { minimum_reserve : 0, maximum_balance : 0, warning_level : 18 }, U64(env::block_timestamp()+1708));
+
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1709));
+    // already below the warning level so no new event
+    ob.pay_out("supplies".to_string(), U128(1), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1710));
+
+    let events = ob.find_low_balance_events();
+    assert_eq!(events.len(), 1);
+    assert_eq!((events[0].balance, events[0].warning_level), (15, 18));
+}
+
+#[test]
+fn test_trial_balance () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let trial_balance = ob.view_trial_balance();
+    let total_debits : u128 = trial_balance.iter().map(|ledger_account| ledger_account.debits).sum();
+    let total_credits : u128 = trial_balance.iter().map(|ledger_account| ledger_account.credits).sum();
+
+    assert_eq!(total_debits, total_credits);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "CASH").unwrap().get_balance(), 20);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "REVENUE").unwrap().credits, 10);
+    assert!(ob.check_ledger());
+}
+
+#[test]
+fn test_pay_out_with_fee_journal () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+1800));
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+1801));
+
+    // 5 leaves the bank balance, 1 of it is held apart as a fee 
+    let trial_balance = ob.view_trial_balance();
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "EXPENSES").unwrap().get_balance(), 5);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "FEES").unwrap().get_balance(), -1);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "HELD_FUNDS").unwrap().get_balance(), 1);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "CASH").unwrap().get_balance(), 15);
+    assert!(ob.check_ledger());
+}
+
+#[test]
+fn test_held_funds_journal () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.create_escrow(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+1802));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+1803));
+    ob.charge_credit("robert.testnet".to_string(), U128(4), "usage".to_string(), U64(env::block_timestamp()+1804));
+
+    // the escrow and the remaining credit are owed, only the charged credit joins the revenue of the default pay in 
+    let trial_balance = ob.view_trial_balance();
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "ESCROW").unwrap().get_balance(), -5);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "PAYABLES").unwrap().get_balance(), -6);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "HELD_FUNDS").unwrap().get_balance(), 11);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "REVENUE").unwrap().get_balance(), -14);
+    assert_eq!(trial_balance.iter().find(|ledger_account| ledger_account.name == "CASH").unwrap().get_balance(), 19);
+    assert!(ob.check_ledger());
+}
+
+#[test]
+fn test_journal_payment_reference () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+2619));
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+2620));
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+2621));
+
+    // the pay in and its fee are behind the pay in
+    let entries : Vec<(String, String, u128)> = ob.journal.iter().filter(|entry| entry.payment_reference == pay_in.reference).map(|entry| (entry.debit_account.clone(), entry.credit_account.clone(), entry.amount)).collect();
+    assert_eq!(entries, vec![("HELD_FUNDS".to_string(), "FEES".to_string(), 2), ("CASH".to_string(), "REVENUE".to_string(), 8)]);
+
+    // the grant is set aside behind a payment of its own
+    let set_aside = ob.journal.last().unwrap().payment_reference;
+    assert_eq!(ob.find_payment(U64(set_aside)).payment_type, "SET_ASIDE");
+    assert_eq!(ob.find_payment(U64(set_aside)).linked_reference, u64::from(vg_ref));
+    assert!(ob.journal.iter().skip(1).all(|entry| entry.payment_reference != 0));
+    assert!(ob.check_ledger());
+}
+
+#[test]
+fn test_find_ledger_entries () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+1805));
+    ob.withdraw("drawings".to_string(), U128(3), None, None, U64(env::block_timestamp()+1806));
+
+    let entries = ob.find_ledger_entries("CAPITAL".to_string());
+    let last_two : Vec<(String, String, u128)> = entries.iter().rev().take(2).map(|entry| (entry.debit_account.clone(), entry.credit_account.clone(), entry.amount)).collect();
+
+    assert_eq!(last_two, vec![("CAPITAL".to_string(), "CASH".to_string(), 3), ("CASH".to_string(), "CAPITAL".to_string(), 10)]);
+    assert!(entries.windows(2).all(|pair| pair[0].sequence < pair[1].sequence));
+}
+
+#[test]
+fn test_pay_out_with_category () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.add_payment_category("OPEX".to_string(), "operating expenses".to_string(), U64(env::block_timestamp()+1900));
+
+    let payment = ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, Some(super::PaymentAnnotation { category : Some("OPEX".to_string()), tags : vec!["q3".to_string(), "office".to_string()], ..Default::default() }), U64(env::block_timestamp()+1901));
+
+    assert_eq!(payment.category, "OPEX");
+    assert_eq!(payment.tags, vec!["q3".to_string(), "office".to_string()]);
+    assert_eq!(ob.find_payment(U64(payment.reference)), payment);
+    assert_eq!(ob.find_payment_categories()[0].status, "ACTIVE");
+}
+
+#[test]
+#[should_panic(expected = "UNKNOWN PAYMENT CATEGORY CAPEX")]
+fn test_pay_in_with_unknown_category () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_in("mint".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { category : Some("CAPEX".to_string()), ..Default::default() }), U64(env::block_timestamp()+1902));
+}
+
+#[test]
+#[should_panic(expected = "UNKNOWN PAYMENT CATEGORY OPEX")]
+fn test_deposit_with_retired_category () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.add_payment_category("OPEX".to_string(), "operating expenses".to_string(), U64(env::block_timestamp()+1903));
+    ob.retire_payment_category("OPEX".to_string(), U64(env::block_timestamp()+1904));
+
+    ob.deposit("top up".to_string(), U128(10), None, Some(super::PaymentAnnotation { category : Some("OPEX".to_string()), ..Default::default() }), U64(env::block_timestamp()+1905));
+}
+
+#[test]
+fn test_request_debit_with_category () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.add_payment_category("RENT".to_string(), "premises".to_string(), U64(env::block_timestamp()+1906));
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("landlord.testnet".to_string(), "rent".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, Some(super::PaymentAnnotation { category : Some("RENT".to_string()), tags : vec!["lease".to_string()], ..Default::default() }), U64(env::block_timestamp()+1907));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+1908));
+
+    let payment = ob.request_debit(rd_ref, U64(env::block_timestamp()+1909));
+
+    assert_eq!(payment.category, "RENT");
+    assert_eq!(payment.tags, vec!["lease".to_string()]);
+}
+
+#[test]
+fn test_find_category_totals () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.add_payment_category("SALES".to_string(), "sales".to_string(), U64(env::block_timestamp()+1910));
+    ob.add_payment_category("OPEX".to_string(), "operating expenses".to_string(), U64(env::block_timestamp()+1911));
+
+    ob.pay_in("order 1".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { category : Some("SALES".to_string()), ..Default::default() }), U64(env::block_timestamp()+1912));
+    ob.pay_in("order 2".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { category : Some("SALES".to_string()), ..Default::default() }), U64(env::block_timestamp()+1913));
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, Some(super::PaymentAnnotation { category : Some("OPEX".to_string()), ..Default::default() }), U64(env::block_timestamp()+1914));
+
+    let time_now = env::block_timestamp() as i64;
+    assert_eq!(ob.find_category_totals(I64(time_now), I64(time_now + 1)), vec![("OPEX".to_string(), U128(5)), ("SALES".to_string(), U128(20))]);
+    assert!(ob.find_category_totals(I64(time_now + 1), I64(time_now + 1000)).is_empty());
+}
+
+#[test]
+fn test_find_payments_by_external_reference () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, Some(super::PaymentAnnotation { external_reference : Some("PO-1001".to_string()), metadata : Some(vec![("cost_centre".to_string(), "ops".to_string()), ("vat".to_string(), "20".to_string())].into_iter().collect()), ..Default::default() }), U64(env::block_timestamp()+2000));
+    ob.pay_out("supplies".to_string(), U128(1), "partner.testnet".to_string(), None, Some(super::PaymentAnnotation { external_reference : Some("PO-1002".to_string()), metadata : Some(Default::default()), ..Default::default() }), U64(env::block_timestamp()+2001));
+
+    assert_eq!(payment.external_reference, "PO-1001");
+    assert_eq!(payment.metadata.get("vat"), Some(&"20".to_string()));
+    assert_eq!(ob.find_payments_by_external_reference("PO-1001".to_string()), vec![payment]);
+    assert!(ob.find_payments_by_external_reference("".to_string()).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "EXTERNAL REFERENCE TOO LONG. MAXIMUM LENGTH: 64 ACTUAL LENGTH: 65")]
+fn test_pay_in_with_long_external_reference () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_in("mint".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { external_reference : Some("X".repeat(65)), metadata : Some(Default::default()), ..Default::default() }), U64(env::block_timestamp()+2002));
+}
+
+#[test]
+#[should_panic(expected = "TOO MANY METADATA ENTRIES. MAXIMUM ENTRIES: 16 ACTUAL ENTRIES: 17")]
+fn test_deposit_with_too_much_metadata () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let keys : Vec<String> = (0..17).map(|i| format!("key{}", i)).collect();
+    let metadata = keys.iter().map(|k| (k.clone(), "value".to_string())).collect();
+
+    ob.deposit("top up".to_string(), U128(10), None, Some(super::PaymentAnnotation { external_reference : Some("DEP-1".to_string()), metadata : Some(metadata), ..Default::default() }), U64(env::block_timestamp()+2003));
+}
+
+#[test]
+#[should_panic(expected = "DESCRIPTION TOO LONG. MAXIMUM LENGTH: 256 ACTUAL LENGTH: 257")]
+fn test_pay_out_with_long_description () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.pay_out("X".repeat(257), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2004));
+}
+
+#[test]
+#[should_panic(expected = "DESCRIPTION TOO LONG. MAXIMUM LENGTH: 256 ACTUAL LENGTH: 300")]
+fn test_register_request_debit_with_long_description () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    ob.register_request_debit("landlord.testnet".to_string(), "X".repeat(300), U128(3), I64(1000), I64(time_now), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2005));
+}
+
+#[test]
+fn test_get_bank_summary () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2100));
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2101));
+    ob.pay_out("supplies".to_string(), U128(2), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2102));
+    ob.withdraw("drawings".to_string(), U128(3), None, None, U64(env::block_timestamp()+2103));
+
+    let summary = ob.get_bank_summary(vec![0, 7]);
+
+    assert_eq!(summary.balance, 20);
+    assert_eq!(summary.activity.len(), 2);
+    for activity in summary.activity.iter() {
+        assert_eq!((activity.pay_ins.count, activity.pay_ins.total), (1, 10));
+        assert_eq!((activity.pay_outs.count, activity.pay_outs.total), (2, 7));
+        assert_eq!((activity.deposits.count, activity.deposits.total), (1, 10));
+        assert_eq!((activity.withdrawals.count, activity.withdrawals.total), (1, 3));
+    }
+    assert_eq!(summary.activity[1].days, 7);
+    assert_eq!(summary.last_activity_time, env::block_timestamp() as i64);
+}
+
+#[test]
+fn test_get_bank_summary_obligations_and_request_debits () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.create_escrow(get_escrow_request(vec!["robert.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+2104));
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("landlord.testnet".to_string(), "rent".to_string(), U128(3), I64(1000), I64(time_now), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2105));
+    ob.register_request_debit("cleaner.testnet".to_string(), "cleaning".to_string(), U128(1), I64(1000), I64(time_now), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2106));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+2107));
+
+    let summary = ob.get_bank_summary(vec![]);
+
+    assert_eq!(summary.outstanding_obligations, 5);
+    // the default bank has a pending request debit of its own 
+    assert_eq!(summary.request_debits_by_status, vec![("APPROVED".to_string(), 1), ("PENDING".to_string(), 2)]);
+}
+
+#[test]
+fn test_get_bank_summary_request_debit () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("landlord.testnet".to_string(), "rent".to_string(), U128(3), I64(1000), I64(time_now - 3500), I64(time_now + 10000), None, None, U64(env::block_timestamp()+2622));
+    ob.approve_request_debit(rd_ref, U64(env::block_timestamp()+2623));
+    ob.request_debit(rd_ref, U64(env::block_timestamp()+2624));
+
+    let summary = ob.get_bank_summary(vec![0, 1]);
+
+    // a request debit leaves the bank so it is counted with the pay outs 
+    for activity in summary.activity.iter() {
+        assert_eq!((activity.pay_outs.count, activity.pay_outs.total), (1, 3));
+        assert_eq!((activity.internal.count, activity.internal.total), (0, 0));
+    }
+    assert_eq!(summary.balance, 17);
+}
+
+#[test]
+fn test_record_activity_forgets_old_days () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let mut old_payment = ob.payments.iter().next().unwrap().clone();
+    old_payment.payment_time -= 400 * 24 * 60 * 60 * 1000;
+    ob.daily_activity.clear();
+    ob.record_activity(&old_payment);
+    let payment = ob.payments.iter().next().unwrap().clone();
+    ob.record_activity(&payment);
+
+    // only today is kept, the lifetime totals keep everything
+    assert_eq!(ob.daily_activity.len(), 1);
+    assert_eq!(ob.get_bank_summary(vec![0]).activity[0].pay_ins.count, 3);
+}
+
+#[test]
+#[should_panic(expected = "INVALID SUMMARY PERIOD 400 DAYS. MAXIMUM PERIOD: 366 DAYS")]
+fn test_get_bank_summary_invalid_period () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.get_bank_summary(vec![400]);
+}
+
+#[test]
+fn test_close_statement_period () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2200));
+    ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2201));
+    let first = ob.close_statement_period(U64(env::block_timestamp()+2202));
+
+    assert_eq!(first.number, 1);
+    assert_eq!(first.status, "CLOSED");
+    assert_eq!((first.opening_balance, first.closing_balance), (0, 26));
+    assert_eq!((first.first_payment_sequence, first.last_payment_sequence), (1, 3));
+    assert_eq!(first.totals_by_type.get("DEPOSIT").unwrap().total, 10);
+    assert_eq!(first.totals_by_type.get("PAY_OUT").unwrap().count, 1);
+    assert_eq!(first.closed_by, "robert.testnet");
+
+    ob.withdraw("drawings".to_string(), U128(6), None, None, U64(env::block_timestamp()+2203));
+    let second = ob.close_statement_period(U64(env::block_timestamp()+2204));
+
+    assert_eq!((second.opening_balance, second.closing_balance), (26, 20));
+    assert_eq!((second.first_payment_sequence, second.last_payment_sequence), (4, 4));
+    assert_eq!(second.first_journal_sequence, first.last_journal_sequence + 1);
+    assert_eq!(ob.find_statement_periods(), vec![first, second]);
+    assert!(ob.check_statement_period(U64(1)));
+    assert!(ob.check_statement_period(U64(2)));
+
+    let open = ob.view_open_statement_period();
+    assert_eq!((open.number, open.status.as_str(), open.opening_balance), (3, "OPEN", 20));
+    assert!(open.totals_by_type.is_empty());
+}
+
+#[test]
+fn test_process_due_statement_period () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let first_close_time = env::block_timestamp() as i64 + super::DAY_MILLIS;
+    ob.set_statement_schedule("DAILY".to_string(), I64(first_close_time), U64(env::block_timestamp()+2205));
+    assert_eq!(ob.view_open_statement_period().close_due_time, first_close_time);
+    assert_eq!(ob.process_due_statement_period(), None);
+
+    context.block_timestamp += super::DAY_MILLIS as u64;
+    testing_env!(context);
+    let closed = ob.process_due_statement_period().unwrap();
+
+    assert_eq!(closed.closing_balance, 20);
+    // the next period keeps to the schedule
+    assert_eq!(ob.view_open_statement_period().close_due_time, first_close_time + super::DAY_MILLIS);
+    assert_eq!(ob.process_due_statement_period(), None);
+}
+
+#[test]
+fn test_payment_after_due_statement_period () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    let first_close_time = env::block_timestamp() as i64 + super::DAY_MILLIS;
+    ob.set_statement_schedule("DAILY".to_string(), I64(first_close_time), U64(env::block_timestamp()+2625));
+
+    // the close is missed and a Payment is made after the period is due
+    context.block_timestamp += super::DAY_MILLIS as u64 + 1000;
+    set_context_with_transfer_results(context);
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2626));
+
+    let closed = ob.find_statement_periods();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].closing_time, first_close_time);
+    assert_eq!(closed[0].closing_balance, 20);
+    assert!(!closed[0].totals_by_type.contains_key("DEPOSIT"));
+    assert!(ob.check_statement_period(U64(1)));
+
+    let open = ob.view_open_statement_period();
+    assert_eq!(open.opening_time, first_close_time);
+    assert_eq!(open.opening_balance, 20);
+    assert_eq!(open.totals_by_type.get("DEPOSIT").map(|totals| totals.total), Some(10));
+    assert_eq!(ob.process_due_statement_period(), None);
+}
+
+#[test]
+#[should_panic]
+fn test_set_statement_schedule_unknown_frequency () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_statement_schedule("YEARLY".to_string(), I64(env::block_timestamp() as i64 + 1000), U64(env::block_timestamp()+2206));
+}
+
+#[test]
+fn test_check_statement_period_detects_changed_statement () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.close_statement_period(U64(env::block_timestamp()+2207));
+    assert!(ob.check_statement_period(U64(1)));
+
+    ob.statement_periods[0].closing_balance += 1;
+    assert!(!ob.check_statement_period(U64(1)));
+}
+
+#[test]
+fn test_statement_schedule_monthly_close_times () {
+
+    // 31 January 2024, later closes fall on the last day of shorter months without drifting 
+    let first_close_time = 1706659200000;
+    let statement_schedule = super::ob_io::StatementSchedule { frequency : "MONTHLY".to_string(), first_close_time, first_period_number : 2 };
+
+    assert_eq!(statement_schedule.get_close_time(1), 0);
+    assert_eq!(statement_schedule.get_close_time(2), first_close_time);
+    assert_eq!(statement_schedule.get_close_time(3), first_close_time + 29 * super::DAY_MILLIS);
+    assert_eq!(statement_schedule.get_close_time(4), first_close_time + 60 * super::DAY_MILLIS);
+}
+
+#[test]
+fn test_audit () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2300));
+    ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2301));
+
+    // the account holds just what the bank holds 
+    context.account_balance = 26 - context.attached_deposit;
+    testing_env!(context);
+    let audit_report = ob.audit(U64(100), true, U64(env::block_timestamp()+2302));
+
+    assert!(audit_report.complete);
+    assert_eq!(audit_report.audited_payment_sequence, ob.payment_sequence);
+    assert_eq!((audit_report.recomputed_balance, audit_report.bank_balance), (26, 26));
+    assert_eq!((audit_report.ledger_difference, audit_report.payment_difference), (0, 0));
+    assert_eq!(audit_report.held_balance, 26);
+    assert_eq!(audit_report.account_difference, 0);
+    assert!(!audit_report.mismatch);
+    assert!(!ob.is_outflows_paused());
+}
+
+#[test]
+fn test_audit_from_checkpoint () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2303));
+    let first = ob.audit(U64(1), false, U64(env::block_timestamp()+2304));
+    assert!(!first.complete);
+    assert_eq!(first.audited_payment_sequence, 1);
+    assert_eq!(first.ledger_difference, 0);
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2305));
+    let second = ob.audit(U64(100), false, U64(env::block_timestamp()+2627));
+    assert!(second.complete);
+    assert_eq!(second.recomputed_balance, 40);
+    assert_eq!(second.ledger_difference, 0);
+}
+
+#[test]
+fn test_audit_rebuilds_balance_from_payments () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    // fees, refunds, funds set aside and customer credit all move the balance differently 
+    ob.set_fee_schedule(super::FeeSchedule { pay_in : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, request_debit : super::ob_io::FeeRate { basis_points : 1000, flat : 1 }, pay_out : super::ob_io::FeeRate { basis_points : 1000, flat : 1 } }, U64(env::block_timestamp()+2628));
+    let pay_in = ob.pay_in("mint".to_string(), U128(10), None, None, None, U64(env::block_timestamp()+2629));
+    ob.refund_payment(U64(pay_in.reference), U128(4), "failed mint".to_string(), U64(env::block_timestamp()+2630));
+    ob.pay_out("supplies".to_string(), U128(5), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2631));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+2632));
+    ob.charge_credit("robert.testnet".to_string(), U128(3), "usage".to_string(), U64(env::block_timestamp()+2633));
+    ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "robert.testnet".to_string(), total : 10, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+2634));
+
+    let audit_report = ob.audit(U64(100), false, U64(env::block_timestamp()+2635));
+
+    assert!(audit_report.complete);
+    assert_eq!(audit_report.recomputed_balance, ob.bank_balance as i128);
+    assert_eq!((audit_report.ledger_difference, audit_report.payment_difference), (0, 0));
+}
+
+#[test]
+fn test_audit_finds_missing_payment () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let deposit = ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2636));
+    ob.payments_by_reference.remove(&deposit.reference);
+
+    let audit_report = ob.audit(U64(100), false, U64(env::block_timestamp()+2637));
+
+    // the balance can not be rebuilt without the deposit 
+    assert_eq!(audit_report.payment_difference, 1);
+    assert_eq!(audit_report.ledger_difference, 10);
+    assert!(audit_report.mismatch);
+}
+
+#[test]
+fn test_audit_with_storage_usage () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    // the account holds what the bank holds on top of what is locked for storage 
+    context.storage_usage = 1000;
+    context.account_balance = ob.bank_balance + 1000 * env::storage_byte_cost() - context.attached_deposit;
+    testing_env!(context);
+    let audit_report = ob.audit(U64(100), true, U64(env::block_timestamp()+2638));
+
+    assert!(audit_report.storage_cost > 0);
+    assert_eq!(audit_report.account_difference, 0);
+    assert!(!audit_report.mismatch);
+    assert!(!ob.is_outflows_paused());
+}
+
+#[test]
+fn test_audit_surplus_is_mismatch () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    // the account holds more than the bank e.g. from gas rewards 
+    context.account_balance = 100;
+    testing_env!(context);
+    let audit_report = ob.audit(U64(100), false, U64(env::block_timestamp()+2683));
+
+    assert_eq!(audit_report.account_difference, env::account_balance() as i128 - 20);
+    assert!(audit_report.mismatch);
+}
+
+#[test]
+fn test_audit_unlimited () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2684));
+    ob.audit(U64(1), false, U64(env::block_timestamp()+2685));
+    ob.deposit("top up".to_string(), U128(10), None, None, U64(env::block_timestamp()+2686));
+
+    // a limit past the end of the Payments audits up to the latest from the checkpoint 
+    let audit_report = ob.audit(U64(u64::MAX), false, U64(env::block_timestamp()+2687));
+
+    assert!(audit_report.complete);
+    assert_eq!(audit_report.recomputed_balance, 40);
+    assert_eq!(audit_report.ledger_difference, 0);
+}
+
+#[test]
+fn test_audit_strict_mode_pauses_outflows () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.bank_balance += 1;
+    let audit_report = ob.audit(U64(100), true, U64(env::block_timestamp()+2306));
+
+    assert_eq!(audit_report.ledger_difference, 1);
+    assert!(audit_report.mismatch);
+    assert!(audit_report.outflows_paused);
+    assert!(ob.is_outflows_paused());
+
+    assert!(!ob.resume_outflows(U64(env::block_timestamp()+2307)));
+    assert!(!ob.is_outflows_paused());
+}
+
+#[test]
+#[should_panic]
+fn test_pay_out_when_outflows_paused () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    ob.outflows_paused = true;
+    ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2308));
+}
+
+#[test]
+fn test_find_payment_in_privacy_mode () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2400));
+    // with test mode off the mocked Open Roles denies 'view_payments' 
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2401));
+    ob.deactivate_test_mode();
+    assert!(ob.is_privacy_mode());
+
+    // the signer and the payee are parties to the payment 
+    assert_eq!(ob.find_payment(U64(payment.reference)), payment);
+    context.signer_account_id = "partner.testnet".to_string();
+    set_context_with_transfer_results(context);
+    assert_eq!(ob.find_payment(U64(payment.reference)), payment);
+}
+
+#[test]
+#[should_panic]
+fn test_find_payment_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2402));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2403));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    set_context_with_transfer_results(context);
+    ob.find_payment(U64(payment.reference));
+}
+
+#[test]
+fn test_verify_payment () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2404));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2405));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    set_context_with_transfer_results(context);
+    let confirmation = ob.verify_payment(U64(payment.reference));
+
+    assert_eq!(confirmation.reference, payment.reference);
+    assert_eq!(confirmation.status, payment.status);
+    assert_eq!(confirmation.payment_time, payment.payment_time);
+}
+
+#[test]
+fn test_find_request_debits_in_privacy_mode () {
+
+    let mut context = get_context(vec![], false);
+    set_context_with_transfer_results(context.clone());
+    let mut ob = get_default_ob();
+
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2406));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    set_context_with_transfer_results(context.clone());
+    assert!(ob.find_request_debits_by_status("PENDING".to_string()).is_empty());
+
+    // the payee sees its own request debit 
+    context.signer_account_id = "testaccount.testnet".to_string();
+    set_context_with_transfer_results(context);
+    let request_debits = ob.find_request_debits_by_status("PENDING".to_string());
+    assert_eq!(request_debits.len(), 1);
+    let request_debit = request_debits.iter().next().unwrap();
+    assert_eq!(ob.find_request_debit(U64(request_debit.reference)).payee, "testaccount.testnet");
+}
+
+#[test]
+fn test_find_escrows_in_privacy_mode () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let es_ref = ob.create_escrow(get_escrow_request(vec!["checker.testnet"], 1, "", "REFUND"), None, U64(env::block_timestamp()+2639));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2640));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context.clone());
+    assert!(ob.find_escrows_by_status("HELD".to_string()).is_empty());
+
+    // an approver is a party to the escrow 
+    context.signer_account_id = "checker.testnet".to_string();
+    testing_env!(context);
+    assert_eq!(ob.find_escrows_by_status("HELD".to_string()).len(), 1);
+    assert_eq!(ob.find_escrow(es_ref).payee, "partner.testnet");
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_find_supplier_contract_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("partner.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+2641));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2642));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.find_supplier_contract(sc_ref);
+}
+
+#[test]
+fn test_find_supplier_contract_private () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let sc_ref = ob.create_supplier_contract("partner.testnet".to_string(), "website build".to_string(), vec![2, 3].into_iter().enumerate().map(|(i, amount)| super::MilestoneRequest { amount, description : format!("milestone {}", i + 1), due_date : env::block_timestamp() as i64 + (i as i64 + 1) * super::WEEK_MILLIS }).collect(), None, U64(env::block_timestamp()+2643));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2644));
+
+    // in test mode the signer is allowed to 'view_payments' 
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    let sc = ob.find_supplier_contract_private(sc_ref);
+    assert_eq!(sc.supplier, "partner.testnet");
+    assert_eq!(ob.find_milestone_private(U64(sc.milestones[0].id)).amount, 2);
+    assert_eq!(ob.find_supplier_contracts_by_supplier_private("partner.testnet".to_string()).len(), 1);
+}
+
+#[test]
+fn test_find_grants_streams_subscriptions_and_split_rules_in_privacy_mode () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "partner.testnet".to_string(), total : 4, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+2688));
+    ob.create_payment_stream("partner.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+2689));
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+2690));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+2691));
+    ob.subscribe(plan_ref, U64(env::block_timestamp()+2692));
+    ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner.testnet".to_string(), basis_points : 2000 }], distribution : "ACCRUED".to_string() }, U64(env::block_timestamp()+2693));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2694));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context.clone());
+    assert!(ob.find_vesting_grants_by_beneficiary("partner.testnet".to_string()).is_empty());
+    assert!(ob.find_payment_streams_by_recipient("partner.testnet".to_string()).is_empty());
+    assert!(ob.find_subscriptions_by_subscriber("robert.testnet".to_string()).is_empty());
+    assert!(ob.find_active_split_rules().is_empty());
+
+    // a payee of a leg is a party to the split rule 
+    context.signer_account_id = "partner.testnet".to_string();
+    testing_env!(context);
+    assert_eq!(ob.find_vesting_grants_by_beneficiary("partner.testnet".to_string()).len(), 1);
+    assert_eq!(ob.find_payment_streams_by_recipient("partner.testnet".to_string()).len(), 1);
+    assert_eq!(ob.find_active_split_rules().len(), 1);
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_view_customer_credit_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+2695));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2696));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.view_customer_credit("robert.testnet".to_string());
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_view_vested_amount_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "partner.testnet".to_string(), total : 4, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+2697));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2698));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.view_vested_amount(vg_ref);
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_view_payment_stream_balance_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let ps_ref = ob.create_payment_stream("partner.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+2699));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2700));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.view_payment_stream_balance(ps_ref);
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_find_subscription_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let plan_ref = ob.create_subscription_plan("pro plan".to_string(), U128(3), I64(super::DAY_MILLIS), I64(super::DAY_MILLIS), U64(env::block_timestamp()+2701));
+    ob.pay_in("prefund".to_string(), U128(10), Some("CREDIT".to_string()), None, None, U64(env::block_timestamp()+2702));
+    let subscription = ob.subscribe(plan_ref, U64(env::block_timestamp()+2703));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2704));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.find_subscription(U64(subscription.reference));
+}
+
+#[test]
+#[should_panic (expected = "NOT A PARTY")]
+fn test_find_split_rule_in_privacy_mode_not_party () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let sr_ref = ob.set_split_rule(super::SplitRuleRequest { match_type : "TAG".to_string(), match_value : "mint".to_string(), legs : vec![super::ob_io::SplitLeg { payee : "partner.testnet".to_string(), basis_points : 2000 }], distribution : "ACCRUED".to_string() }, U64(env::block_timestamp()+2705));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2706));
+    ob.deactivate_test_mode();
+
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    ob.find_split_rule(sr_ref);
+}
+
+#[test]
+fn test_find_vesting_grant_and_payment_stream_private () {
+
+    let mut context = get_context(vec![], false);
+    testing_env!(context.clone());
+    let mut ob = get_default_ob();
+
+    let vg_ref = ob.create_vesting_grant(super::VestingGrantRequest { beneficiary : "partner.testnet".to_string(), total : 4, start_date : env::block_timestamp() as i64 - 5000, cliff_date : env::block_timestamp() as i64 - 5000, end_date : env::block_timestamp() as i64 + 5000, release_type : "LINEAR".to_string(), step_interval : 4000, revocable : true }, None, U64(env::block_timestamp()+2707));
+    let ps_ref = ob.create_payment_stream("partner.testnet".to_string(), U128(1), I64(env::block_timestamp() as i64 - 5000), I64(env::block_timestamp() as i64 + 5000), None, U64(env::block_timestamp()+2708));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2709));
+
+    // in test mode the signer is allowed to 'view_payments' 
+    context.signer_account_id = "stranger.testnet".to_string();
+    testing_env!(context);
+    assert_eq!(ob.find_vesting_grant_private(vg_ref).beneficiary, "partner.testnet");
+    assert_eq!(ob.find_payment_stream_private(ps_ref).recipient, "partner.testnet");
+    assert_eq!(ob.view_customer_credit_private("robert.testnet".to_string()), U128(0));
+}
+
+#[test]
+#[should_panic (expected = "PAYMENT VIEW NOT ALLOWED")]
+fn test_find_payment_private_not_allowed () {
+
+    let context = get_context(vec![], false);
+    set_context_with_transfer_results(context);
+    let mut ob = get_default_ob();
+
+    let payment = ob.pay_out("supplies".to_string(), U128(4), "partner.testnet".to_string(), None, None, U64(env::block_timestamp()+2645));
+    ob.set_privacy_mode(true, U64(env::block_timestamp()+2646));
+    ob.deactivate_test_mode();
+
+    ob.find_payment_private(U64(payment.reference));
+}
+
+#[test]
+fn test_encrypted_memo_round_trip () {
+
+    let (memo_secret_key, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    assert_eq!(super::ob_memo::get_memo_public_key(&memo_secret_key).unwrap(), memo_public_key);
+
+    let encrypted_memo = super::ob_memo::encrypt_memo(&memo_public_key, "invoice INV-0042 for March").unwrap();
+
+    assert_eq!(encrypted_memo.bank_public_key, memo_public_key);
+    assert!(!encrypted_memo.ciphertext.contains("INV-0042"));
+    assert_eq!(super::ob_memo::decrypt_memo(&memo_secret_key, &encrypted_memo).unwrap(), "invoice INV-0042 for March");
+}
+
+#[test]
+fn test_decrypt_memo_with_wrong_key () {
+
+    let (_, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    let (other_secret_key, _) = super::ob_memo::generate_memo_key_pair();
+    let mut encrypted_memo = super::ob_memo::encrypt_memo(&memo_public_key, "invoice INV-0042").unwrap();
+
+    assert!(super::ob_memo::decrypt_memo(&other_secret_key, &encrypted_memo).is_err());
+
+    encrypted_memo.nonce = "not base64!".to_string();
+    assert!(super::ob_memo::decrypt_memo(&other_secret_key, &encrypted_memo).is_err());
+}
+
+#[test]
+fn test_pay_in_with_encrypted_memo () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let (memo_secret_key, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    ob.set_memo_public_key(memo_public_key.clone(), U64(env::block_timestamp()+2500));
+    assert_eq!(ob.view_memo_public_key(), memo_public_key);
+
+    let encrypted_memo = super::ob_memo::encrypt_memo(&ob.view_memo_public_key(), "invoice INV-0042").unwrap();
+    let payment = ob.pay_in("supplier payment".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { encrypted_memo : Some(encrypted_memo.clone()), ..Default::default() }), U64(env::block_timestamp()+2501));
+
+    let stored_memo = ob.find_payment(U64(payment.reference)).encrypted_memo.unwrap();
+    assert_eq!(stored_memo, encrypted_memo);
+    assert_eq!(super::ob_memo::decrypt_memo(&memo_secret_key, &stored_memo).unwrap(), "invoice INV-0042");
+}
+
+#[test]
+fn test_register_request_debit_with_encrypted_memo () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let (memo_secret_key, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    ob.set_memo_public_key(memo_public_key.clone(), U64(env::block_timestamp()+2502));
+
+    let encrypted_memo = super::ob_memo::encrypt_memo(&memo_public_key, "monthly rent, flat 4").unwrap();
+    let time_now = env::block_timestamp() as i64;
+    let rd_ref = ob.register_request_debit("landlord.testnet".to_string(), "rent".to_string(), U128(3), I64(1000), I64(time_now), I64(time_now + 10000), None, Some(super::PaymentAnnotation { encrypted_memo : Some(encrypted_memo), ..Default::default() }), U64(env::block_timestamp()+2503));
+
+    let stored_memo = ob.find_request_debit(rd_ref).annotation.encrypted_memo.unwrap();
+    assert_eq!(super::ob_memo::decrypt_memo(&memo_secret_key, &stored_memo).unwrap(), "monthly rent, flat 4");
+}
+
+#[test]
+#[should_panic]
+fn test_pay_in_with_encrypted_memo_for_unknown_key () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let (_, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    let (_, other_public_key) = super::ob_memo::generate_memo_key_pair();
+    ob.set_memo_public_key(memo_public_key, U64(env::block_timestamp()+2504));
+
+    let encrypted_memo = super::ob_memo::encrypt_memo(&other_public_key, "invoice INV-0042").unwrap();
+    ob.pay_in("supplier payment".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { encrypted_memo : Some(encrypted_memo), ..Default::default() }), U64(env::block_timestamp()+2505));
+}
+
+#[test]
+#[should_panic]
+fn test_pay_in_with_encrypted_memo_too_long () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    let (_, memo_public_key) = super::ob_memo::generate_memo_key_pair();
+    ob.set_memo_public_key(memo_public_key.clone(), U64(env::block_timestamp()+2506));
+
+    let encrypted_memo = super::ob_memo::encrypt_memo(&memo_public_key, &"x".repeat(super::MAX_DESCRIPTION_LENGTH + 1)).unwrap();
+    ob.pay_in("supplier payment".to_string(), U128(10), None, None, Some(super::PaymentAnnotation { encrypted_memo : Some(encrypted_memo), ..Default::default() }), U64(env::block_timestamp()+2507));
+}
+
+#[test]
+#[should_panic]
+fn test_set_memo_public_key_invalid () {
+
+    let context = get_context(vec![], false);
+    testing_env!(context);
+    let mut ob = get_default_ob();
+
+    ob.set_memo_public_key("not a key".to_string(), U64(env::block_timestamp()+2508));
+}